@@ -1,8 +1,4 @@
-use std::{
-    env, fs,
-    path::{Path, PathBuf},
-    process::Command,
-};
+use std::{fs, path::PathBuf, process::Command};
 
 fn main() {
     println!("🔥 build.rs is running!");
@@ -41,7 +37,7 @@ fn main() {
     // Run `npm run build`
     println!("🔨 Running `npm run build` in {}", ts_lib_dir.display());
     let build_status = Command::new("npm")
-        .args(&["run", "build"])
+        .args(["run", "build"])
         .current_dir(&ts_lib_dir)
         .status()
         .expect("❌ Failed to run `npm run build`");
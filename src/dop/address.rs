@@ -0,0 +1,177 @@
+use crate::dop::Chain;
+use anyhow::{Result, anyhow, bail};
+use bech32::{Bech32m, Hrp, primitives::decode::CheckedHrpstring};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Value, json};
+use std::{fmt, str::FromStr};
+
+pub const DOP_ADDRESS_PREFIX: &str = "0zk";
+pub const DOP_ADDRESS_VERSION: u8 = 1;
+
+// Inherited from the upstream engine: the network ID is XOR-ed with this
+// ASCII tag before encoding.
+const NETWORK_ID_XOR_KEY: [u8; 8] = *b"railgun\0";
+const ALL_CHAINS_NETWORK_ID: [u8; 8] = [0xff; 8];
+const PAYLOAD_LEN: usize = 1 + 32 + 8 + 32;
+const MAX_CHAIN_ID: u64 = (1 << 56) - 1;
+
+/// A private `0zk` address, decoded without the engine.
+///
+/// The bech32m payload is `version | master public key | network ID |
+/// viewing public key`. A network ID of all `0xff` bytes means the address is
+/// valid on every chain and decodes to `chain: None`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DopAddress {
+    pub master_public_key: [u8; 32],
+    pub viewing_public_key: [u8; 32],
+    pub chain: Option<Chain>,
+    pub version: u8,
+}
+
+impl DopAddress {
+    pub fn new(
+        master_public_key: [u8; 32],
+        viewing_public_key: [u8; 32],
+        chain: Option<Chain>,
+    ) -> Result<Self> {
+        if let Some(chain) = chain.filter(|c| c.id > MAX_CHAIN_ID) {
+            bail!("Chain ID {} does not fit in a DOP address", chain.id);
+        }
+        Ok(Self {
+            master_public_key,
+            viewing_public_key,
+            chain,
+            version: DOP_ADDRESS_VERSION,
+        })
+    }
+
+    pub fn is_valid(address: &str) -> bool {
+        address.parse::<DopAddress>().is_ok()
+    }
+
+    /// The master public key as a decimal string, which is how the engine
+    /// reports it.
+    pub fn master_public_key_decimal(&self) -> String {
+        let mut digits = Vec::new();
+        let mut value = self.master_public_key.to_vec();
+
+        while value.iter().any(|b| *b != 0) {
+            let mut remainder = 0u16;
+            for byte in value.iter_mut() {
+                let acc = (remainder << 8) | *byte as u16;
+                *byte = (acc / 10) as u8;
+                remainder = acc % 10;
+            }
+            digits.push(b'0' + remainder as u8);
+        }
+
+        if digits.is_empty() {
+            return "0".to_string();
+        }
+        digits.reverse();
+        String::from_utf8(digits).unwrap()
+    }
+
+    /// Same shape as the engine's address-data response.
+    pub fn to_address_data(&self) -> Value {
+        json!({
+            "masterPublicKey": self.master_public_key_decimal(),
+            "viewingPublicKey": self.viewing_public_key.to_vec(),
+            "version": self.version,
+            "chain": self.chain.map(|c| json!({ "type": c.chain_type, "id": c.id })),
+        })
+    }
+
+    fn network_id(&self) -> [u8; 8] {
+        let raw = match self.chain {
+            None => ALL_CHAINS_NETWORK_ID,
+            Some(chain) => {
+                let mut id = chain.id.to_be_bytes();
+                id[0] = chain.chain_type;
+                id
+            }
+        };
+        xor_network_id(raw)
+    }
+}
+
+fn xor_network_id(id: [u8; 8]) -> [u8; 8] {
+    let mut out = id;
+    for (byte, key) in out.iter_mut().zip(NETWORK_ID_XOR_KEY) {
+        *byte ^= key;
+    }
+    out
+}
+
+impl FromStr for DopAddress {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let checked = CheckedHrpstring::new::<Bech32m>(s)
+            .map_err(|e| anyhow!("Invalid DOP address encoding: {}", e))?;
+        if checked.hrp().as_str() != DOP_ADDRESS_PREFIX {
+            bail!("Invalid DOP address prefix: {}", checked.hrp());
+        }
+
+        let data: Vec<u8> = checked.byte_iter().collect();
+        if data.len() != PAYLOAD_LEN {
+            bail!("Invalid DOP address length: {} bytes", data.len());
+        }
+
+        let version = data[0];
+        if version != DOP_ADDRESS_VERSION {
+            bail!("Unsupported DOP address version: {}", version);
+        }
+
+        let network_id = xor_network_id(data[33..41].try_into().unwrap());
+        let chain = if network_id == ALL_CHAINS_NETWORK_ID {
+            None
+        } else {
+            let mut id = network_id;
+            let chain_type = id[0];
+            id[0] = 0;
+            Some(Chain {
+                chain_type,
+                id: u64::from_be_bytes(id),
+            })
+        };
+
+        Ok(Self {
+            master_public_key: data[1..33].try_into().unwrap(),
+            viewing_public_key: data[41..73].try_into().unwrap(),
+            chain,
+            version,
+        })
+    }
+}
+
+impl fmt::Display for DopAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.chain.is_some_and(|c| c.id > MAX_CHAIN_ID) {
+            return Err(fmt::Error);
+        }
+
+        let mut data = Vec::with_capacity(PAYLOAD_LEN);
+        data.push(self.version);
+        data.extend_from_slice(&self.master_public_key);
+        data.extend_from_slice(&self.network_id());
+        data.extend_from_slice(&self.viewing_public_key);
+
+        let hrp = Hrp::parse_unchecked(DOP_ADDRESS_PREFIX);
+        let encoded = bech32::encode::<Bech32m>(hrp, &data).map_err(|_| fmt::Error)?;
+        f.write_str(&encoded)
+    }
+}
+
+impl Serialize for DopAddress {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DopAddress {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
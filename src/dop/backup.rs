@@ -0,0 +1,184 @@
+use crate::dop::DopClient;
+use anyhow::{Result, anyhow, bail};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{
+    AeadCore, ChaCha20Poly1305, Key, KeyInit, Nonce,
+    aead::{Aead, OsRng, Payload, rand_core::RngCore},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub const WALLET_BACKUP_VERSION: u8 = 1;
+
+const WALLET_BACKUP_MAGIC: &[u8; 6] = b"DOPBAK";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const HEADER_LEN: usize = WALLET_BACKUP_MAGIC.len() + 1 + SALT_LEN + NONCE_LEN;
+
+/// Everything needed to recreate a wallet with `create_wallet` or
+/// `create_view_only_wallet`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletBackup {
+    #[serde(rename = "isViewOnlyWallet")]
+    pub is_view_only: bool,
+    pub mnemonic: Option<String>,
+    #[serde(rename = "shareableViewingKey")]
+    pub shareable_viewing_key: Option<String>,
+    #[serde(rename = "derivationIndex")]
+    pub derivation_index: Option<u32>,
+    #[serde(rename = "creationBlockNumbers")]
+    pub creation_block_numbers: Option<HashMap<String, u64>>,
+    pub label: Option<String>,
+}
+
+impl WalletBackup {
+    /// Serializes the backup and seals it with a key derived from `passphrase`.
+    ///
+    /// Layout: `DOPBAK | version | argon2id salt | nonce | ciphertext`, where
+    /// everything before the ciphertext is authenticated as associated data.
+    pub fn to_encrypted_bytes(&self, passphrase: &str) -> Result<Vec<u8>> {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(WALLET_BACKUP_MAGIC);
+        header.push(WALLET_BACKUP_VERSION);
+        header.extend_from_slice(&salt);
+        header.extend_from_slice(&nonce);
+
+        let cipher = ChaCha20Poly1305::new(&derive_backup_key(passphrase, &salt)?);
+        let plaintext = serde_json::to_vec(self)?;
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: &plaintext,
+                    aad: &header,
+                },
+            )
+            .map_err(|_| anyhow!("Failed to encrypt wallet backup"))?;
+
+        let mut out = header;
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    pub fn from_encrypted_bytes(bytes: &[u8], passphrase: &str) -> Result<Self> {
+        if bytes.len() <= HEADER_LEN || !bytes.starts_with(WALLET_BACKUP_MAGIC) {
+            bail!("Not a DOP wallet backup");
+        }
+
+        let version = bytes[WALLET_BACKUP_MAGIC.len()];
+        if version != WALLET_BACKUP_VERSION {
+            bail!("Unsupported wallet backup version: {}", version);
+        }
+
+        let (header, ciphertext) = bytes.split_at(HEADER_LEN);
+        let salt = &header[WALLET_BACKUP_MAGIC.len() + 1..][..SALT_LEN];
+        let nonce = Nonce::from_slice(&header[HEADER_LEN - NONCE_LEN..]);
+
+        let cipher = ChaCha20Poly1305::new(&derive_backup_key(passphrase, salt)?);
+        let plaintext = cipher
+            .decrypt(
+                nonce,
+                Payload {
+                    msg: ciphertext,
+                    aad: header,
+                },
+            )
+            .map_err(|_| anyhow!("Wrong passphrase or corrupted wallet backup"))?;
+
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+}
+
+fn derive_backup_key(passphrase: &str, salt: &[u8]) -> Result<Key> {
+    let params = Params::new(19 * 1024, 2, 1, Some(32))
+        .map_err(|e| anyhow!("Invalid backup KDF parameters: {}", e))?;
+    let mut key = Key::default();
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| anyhow!("Failed to derive backup key: {}", e))?;
+    Ok(key)
+}
+
+impl DopClient {
+    pub async fn get_wallet_backup(
+        &self,
+        wallet_id: &str,
+        encryption_key: &str,
+        label: Option<&str>,
+    ) -> Result<WalletBackup> {
+        let resp = self
+            .client
+            .get(format!(
+                "{}/wallet/{}/backup-info",
+                self.base_url(),
+                wallet_id
+            ))
+            .query(&[("encryptionKey", encryption_key)])
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("API call failed: {} (status {})", text, status));
+        }
+
+        let mut backup = resp.json::<WalletBackup>().await?;
+        backup.label = label.map(|l| l.to_string());
+        Ok(backup)
+    }
+
+    pub async fn export_wallet_backup(
+        &self,
+        wallet_id: &str,
+        encryption_key: &str,
+        passphrase: &str,
+        label: Option<&str>,
+    ) -> Result<Vec<u8>> {
+        self.get_wallet_backup(wallet_id, encryption_key, label)
+            .await?
+            .to_encrypted_bytes(passphrase)
+    }
+
+    pub async fn restore_wallet_backup(
+        &self,
+        backup: &[u8],
+        passphrase: &str,
+        encryption_key: &str,
+    ) -> Result<Value> {
+        let backup = WalletBackup::from_encrypted_bytes(backup, passphrase)?;
+        let creation_block_numbers = backup.creation_block_numbers.as_ref().map(|blocks| {
+            blocks
+                .iter()
+                .map(|(network, block)| (network.as_str(), *block))
+                .collect::<HashMap<&str, u64>>()
+        });
+
+        if backup.is_view_only {
+            let viewing_key = backup
+                .shareable_viewing_key
+                .as_deref()
+                .ok_or_else(|| anyhow!("View-only backup is missing its shareable viewing key"))?;
+            return self
+                .create_view_only_wallet(encryption_key, viewing_key, creation_block_numbers)
+                .await;
+        }
+
+        let mnemonic = backup
+            .mnemonic
+            .as_deref()
+            .ok_or_else(|| anyhow!("Wallet backup is missing its mnemonic"))?;
+        self.create_wallet_at_index(
+            mnemonic,
+            encryption_key,
+            creation_block_numbers,
+            backup.derivation_index,
+        )
+        .await
+    }
+}
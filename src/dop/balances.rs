@@ -1,7 +1,13 @@
-use crate::dop::DopClient;
-use anyhow::Result;
+use crate::dop::{Chain, DopClient, EvmAddress};
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
 use serde_json::{Value, json};
 
+#[derive(Debug, Deserialize)]
+struct TokenDecimalsResponse {
+    decimals: u8,
+}
+
 impl DopClient {
     pub async fn refresh_balances(
         &self,
@@ -14,7 +20,7 @@ impl DopClient {
         });
 
         self.client
-            .post(&format!("{}/refresh-balances", self.base_url()))
+            .post(format!("{}/refresh-balances", self.base_url()))
             .json(&payload)
             .send()
             .await?
@@ -34,7 +40,7 @@ impl DopClient {
         });
 
         self.client
-            .post(&format!("{}/rescan-full-utxo-merkletrees", self.base_url()))
+            .post(format!("{}/rescan-full-utxo-merkletrees", self.base_url()))
             .json(&payload)
             .send()
             .await?
@@ -49,7 +55,7 @@ impl DopClient {
         });
 
         self.client
-            .post(&format!("{}/reset-full-txid-merkletrees", self.base_url()))
+            .post(format!("{}/reset-full-txid-merkletrees", self.base_url()))
             .json(&payload)
             .send()
             .await?
@@ -57,4 +63,47 @@ impl DopClient {
 
         Ok(())
     }
+
+    /// Reads `decimals()` of an ERC20 token through the network's provider.
+    pub async fn token_decimals(
+        &self,
+        network_name: &str,
+        token_address: EvmAddress,
+    ) -> Result<u8> {
+        let resp = self
+            .client
+            .get(format!("{}/token-decimals", self.base_url()))
+            .query(&[
+                ("networkName", network_name.to_string()),
+                ("tokenAddress", token_address.to_string()),
+            ])
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("API call failed: {} (status {})", text, status));
+        }
+
+        Ok(resp.json::<TokenDecimalsResponse>().await?.decimals)
+    }
+
+    /// The chain the engine's network config gives for `network_name`.
+    pub async fn network_chain(&self, network_name: &str) -> Result<Chain> {
+        let resp = self
+            .client
+            .get(format!("{}/network-chain", self.base_url()))
+            .query(&[("networkName", network_name)])
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("API call failed: {} (status {})", text, status));
+        }
+
+        Ok(resp.json::<Chain>().await?)
+    }
 }
@@ -0,0 +1,142 @@
+use crate::dop::{
+    Chain, ContractTransaction, DopAddress, DopERC20AmountRecipient, EvmAddress, FeeTokenDetails,
+    TransactionGasDetails,
+};
+use anyhow::{Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use time::OffsetDateTime;
+
+/// Fees are quoted per unit of gas, scaled by 10^18: a fee of 10^18 means
+/// one base unit of the token per wei of gas cost.
+const FEE_PER_UNIT_GAS_SCALE: u128 = 1_000_000_000_000_000_000;
+
+/// A broadcaster's price for relaying a transaction that pays it in one
+/// token.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BroadcasterFeeQuote {
+    #[serde(rename = "tokenAddress")]
+    pub token_address: EvmAddress,
+    #[serde(rename = "feePerUnitGas")]
+    pub fee_per_unit_gas: String,
+    #[serde(with = "time::serde::timestamp::milliseconds")]
+    pub expiration: OffsetDateTime,
+    /// Identifies this quote when submitting to the broadcaster.
+    #[serde(rename = "feesID")]
+    pub fees_id: String,
+    /// Where the fee is paid.
+    #[serde(rename = "dopAddress")]
+    pub dop_address: DopAddress,
+    #[serde(rename = "availableWallets")]
+    pub available_wallets: u32,
+}
+
+impl BroadcasterFeeQuote {
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(OffsetDateTime::now_utc())
+    }
+
+    pub fn is_expired_at(&self, now: OffsetDateTime) -> bool {
+        now >= self.expiration
+    }
+
+    /// Fee in token base units for a transaction with `gas_details`,
+    /// charged on its worst-case cost.
+    pub fn fee_amount(&self, gas_details: &TransactionGasDetails) -> Result<u128> {
+        let fee_per_unit_gas: u128 = self
+            .fee_per_unit_gas
+            .parse()
+            .map_err(|_| anyhow!("Invalid fee per unit gas: {}", self.fee_per_unit_gas))?;
+        let scaled = gas_details
+            .max_cost()?
+            .checked_mul(fee_per_unit_gas)
+            .ok_or_else(|| anyhow!("Broadcaster fee overflows"))?;
+        Ok(scaled.div_ceil(FEE_PER_UNIT_GAS_SCALE))
+    }
+
+    /// The `broadcasterFeeERC20AmountRecipient` for a transaction with
+    /// `gas_details`. Fails if the quote has expired.
+    pub fn fee_for(&self, gas_details: &TransactionGasDetails) -> Result<DopERC20AmountRecipient> {
+        if self.is_expired() {
+            bail!("Broadcaster fee quote {} has expired", self.fees_id);
+        }
+        Ok(DopERC20AmountRecipient {
+            token_address: self.token_address,
+            amount: self.fee_amount(gas_details)?.to_string(),
+            recipient_address: self.dop_address.to_string(),
+        })
+    }
+
+    /// Fee token details for gas estimates that include this fee.
+    pub fn fee_token_details(
+        &self,
+        gas_details: &TransactionGasDetails,
+    ) -> Result<FeeTokenDetails> {
+        Ok(FeeTokenDetails {
+            token_address: self.token_address,
+            max_amount: self.fee_amount(gas_details)?.to_string(),
+        })
+    }
+}
+
+/// The cheapest quote that has not expired and has a free wallet.
+pub fn select_fee_quote(quotes: &[BroadcasterFeeQuote]) -> Option<&BroadcasterFeeQuote> {
+    let now = OffsetDateTime::now_utc();
+    quotes
+        .iter()
+        .filter(|q| !q.is_expired_at(now) && q.available_wallets > 0)
+        .filter_map(|q| Some((q.fee_per_unit_gas.parse::<u128>().ok()?, q)))
+        .min_by_key(|(fee, _)| *fee)
+        .map(|(_, q)| q)
+}
+
+/// A populated transaction handed to a broadcaster. It must have been
+/// populated with `send_with_public_wallet = false`, the quote's fee and
+/// `overall_batch_min_gas_price`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BroadcastRequest {
+    pub chain: Chain,
+    #[serde(rename = "feesID")]
+    pub fees_id: String,
+    pub to: EvmAddress,
+    pub data: String,
+    #[serde(rename = "broadcasterDopAddress")]
+    pub broadcaster_dop_address: DopAddress,
+    #[serde(rename = "minGasPrice")]
+    pub min_gas_price: String,
+    pub nullifiers: Vec<String>,
+}
+
+impl BroadcastRequest {
+    pub fn new(
+        chain: Chain,
+        quote: &BroadcasterFeeQuote,
+        transaction: &ContractTransaction,
+        min_gas_price: &str,
+        nullifiers: Vec<String>,
+    ) -> Self {
+        Self {
+            chain,
+            fees_id: quote.fees_id.clone(),
+            to: transaction.to,
+            data: transaction.data.clone(),
+            broadcaster_dop_address: quote.dop_address,
+            min_gas_price: min_gas_price.to_string(),
+            nullifiers,
+        }
+    }
+}
+
+/// How the SDK reaches broadcasters, e.g. over Waku or an HTTP gateway.
+pub trait BroadcasterTransport {
+    /// Current quotes on `chain` for fees paid in `token_address`.
+    fn fee_quotes(
+        &self,
+        chain: Chain,
+        token_address: EvmAddress,
+    ) -> impl Future<Output = Result<Vec<BroadcasterFeeQuote>>> + Send;
+
+    /// Hands the transaction to the broadcaster and returns the hash it was
+    /// sent under.
+    fn submit(&self, request: &BroadcastRequest) -> impl Future<Output = Result<String>> + Send;
+}
@@ -1,8 +1,7 @@
 use crate::dop::DopClient;
 use anyhow::Result;
-use serde_json::{Value, json};
 
-use super::MerkletreeScanUpdateEvent;
+use super::{MerkletreeScanUpdateEvent, ProofProgressEvent};
 
 impl DopClient {
     pub fn set_utxo_scan_callback<F>(&self, callback: F)
@@ -39,11 +38,33 @@ impl DopClient {
         });
     }
 
+    /// Called while any transfer or decrypt proof is being generated.
+    pub fn set_proof_progress_callback<F>(&self, callback: F)
+    where
+        F: Fn(ProofProgressEvent) + Send + 'static,
+    {
+        let mut cb = self.proof_progress_callback.lock().unwrap();
+        *cb = Some(Box::new(callback));
+
+        // Notify TS server to start forwarding prover progress
+        let client = self.client.clone();
+        let url = format!("{}/setup-proof-progress-callback", self.base_url());
+        tokio::spawn(async move {
+            if let Err(e) = client.post(&url).send().await {
+                eprintln!(
+                    "Failed to set up proof progress callback on TS server: {}",
+                    e
+                );
+            }
+        });
+    }
+
     pub async fn start_scan_listeners(&self) -> Result<(), warp::Error> {
         use warp::Filter;
 
         let utxo_cb = self.utxo_scan_callback.clone();
         let txid_cb = self.txid_scan_callback.clone();
+        let proof_cb = self.proof_progress_callback.clone();
 
         let utxo_route = warp::post()
             .and(warp::path("utxo-scan-update"))
@@ -65,7 +86,17 @@ impl DopClient {
                 warp::reply()
             });
 
-        let routes = utxo_route.or(txid_route);
+        let proof_route = warp::post()
+            .and(warp::path("proof-progress"))
+            .and(warp::body::json())
+            .map(move |event: ProofProgressEvent| {
+                if let Some(cb) = proof_cb.lock().unwrap().as_ref() {
+                    cb(event);
+                }
+                warp::reply()
+            });
+
+        let routes = utxo_route.or(txid_route).or(proof_route);
 
         tokio::spawn(async move {
             warp::serve(routes).run(([127, 0, 0, 1], 4000)).await;
@@ -1,12 +1,13 @@
+use crate::dop::proof_job::ActiveProofJobs;
 use reqwest::Client;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::{
     path::PathBuf,
     process::{Child, Command},
     sync::{Arc, Mutex},
 };
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Chain {
     #[serde(rename = "type")]
     pub chain_type: u8,
@@ -21,20 +22,45 @@ pub struct MerkletreeScanUpdateEvent {
     pub progress: f64,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProofProgressEvent {
+    #[serde(rename = "proofJobId")]
+    pub job_id: String,
+    /// `transfer`, `decrypt`, `decrypt-to-origin` or `decrypt-base-token`.
+    #[serde(rename = "proofType")]
+    pub proof_type: String,
+    /// Percentage, from 0 to 100.
+    pub progress: f64,
+    pub status: String,
+}
+
 pub enum ScanType {
     UTXOMerkletree,
     TXIDMerkletree,
 }
 
+pub(crate) type ScanCallback =
+    Arc<Mutex<Option<Box<dyn Fn(MerkletreeScanUpdateEvent) + Send + 'static>>>>;
+
+pub(crate) type ProofProgressCallback =
+    Arc<Mutex<Option<Box<dyn Fn(ProofProgressEvent) + Send + 'static>>>>;
+
 pub struct DopClient {
     pub(crate) child: Option<Child>,
     pub(crate) client: Client,
     pub(crate) port: u16,
+    pub(crate) poi_node_urls: Vec<String>,
+
+    pub(crate) utxo_scan_callback: ScanCallback,
+    pub(crate) txid_scan_callback: ScanCallback,
+    pub(crate) proof_progress_callback: ProofProgressCallback,
+    pub(crate) active_proof_jobs: ActiveProofJobs,
+}
 
-    pub(crate) utxo_scan_callback:
-        Arc<Mutex<Option<Box<dyn Fn(MerkletreeScanUpdateEvent) + Send + 'static>>>>,
-    pub(crate) txid_scan_callback:
-        Arc<Mutex<Option<Box<dyn Fn(MerkletreeScanUpdateEvent) + Send + 'static>>>>,
+impl Default for DopClient {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl DopClient {
@@ -47,11 +73,21 @@ impl DopClient {
             child: None,
             client: Client::new(),
             port,
+            poi_node_urls: Vec::new(),
             utxo_scan_callback: Arc::new(Mutex::new(None)),
             txid_scan_callback: Arc::new(Mutex::new(None)),
+            proof_progress_callback: Arc::new(Mutex::new(None)),
+            active_proof_jobs: Arc::new(Mutex::new(Default::default())),
         }
     }
 
+    /// POI nodes the engine checks Proof of Innocence against, passed on
+    /// at `init_engine`. Without any, POI statuses are not updated.
+    pub fn with_poi_node_urls(mut self, urls: Vec<String>) -> Self {
+        self.poi_node_urls = urls;
+        self
+    }
+
     pub(crate) fn base_url(&self) -> String {
         format!("http://localhost:{}", self.port)
     }
@@ -77,7 +113,7 @@ impl DopClient {
         if !dist_path.exists() {
             println!("🔨 dist/index.js not found — running `npm run build`...");
             let status = Command::new("npm")
-                .args(&["run", "build"])
+                .args(["run", "build"])
                 .current_dir(&ts_lib_dir)
                 .status()
                 .expect("❌ Failed to run `npm run build`");
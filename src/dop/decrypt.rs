@@ -0,0 +1,551 @@
+use crate::dop::{
+    ContractTransaction, DEFAULT_TXID_VERSION, DopClient, DopERC20Amount, DopERC20AmountRecipient,
+    DopNFTAmountRecipient, DopPopulateTransactionResponse, DopTransactionGasEstimateResponse,
+    EvmAddress, FeeTokenDetails, FeeTokenSelector, GasFeeOracle, ProvedTransaction,
+    TransactionGasDetails, UtxoSelection,
+    gas_oracle::settle_gas_and_fee,
+    tx_decrypt::{
+        decrypt_base_token_gas_estimate_payload, decrypt_base_token_proof_payload,
+        decrypt_gas_estimate_payload, decrypt_proof_payload,
+    },
+};
+use anyhow::{Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecryptMode {
+    /// ERC20s and NFTs go to the public addresses given as recipients.
+    ToAddress,
+    /// The single ERC20 recipient must be the wrapped base token (e.g. WETH);
+    /// it is unwrapped and the recipient receives the native token.
+    BaseTokenToAddress,
+    /// Everything from the given encrypt transaction goes back to the
+    /// address that encrypted it. If no recipients are set, they are looked
+    /// up from the engine.
+    ToOrigin(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct DecryptResult {
+    /// Unsigned transaction, ready to sign and send.
+    pub transaction: ContractTransaction,
+    pub nullifiers: Vec<String>,
+    pub gas_details: TransactionGasDetails,
+    pub broadcaster_fee: Option<DopERC20AmountRecipient>,
+    /// The recipients that were proved, including any looked up for
+    /// `DecryptMode::ToOrigin`.
+    pub erc20_recipients: Vec<DopERC20AmountRecipient>,
+    pub nft_recipients: Vec<DopNFTAmountRecipient>,
+}
+
+/// Proof of Innocence status of the notes created by an encrypt. Funds from
+/// a pending or blocked encrypt cannot be spent privately, but can still be
+/// decrypted back to their origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EncryptPoiStatus {
+    Pending,
+    Blocked,
+    Valid,
+    /// The engine has no POI information, e.g. no POI node is configured.
+    Unknown,
+}
+
+impl fmt::Display for EncryptPoiStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EncryptPoiStatus::Pending => "pending",
+            EncryptPoiStatus::Blocked => "blocked",
+            EncryptPoiStatus::Valid => "valid",
+            EncryptPoiStatus::Unknown => "unknown",
+        })
+    }
+}
+
+/// An encrypt whose notes are still unspent, together with what a decrypt
+/// to origin would send back.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DecryptToOriginCandidate {
+    pub txid: String,
+    #[serde(rename = "blockNumber")]
+    pub block_number: Option<u64>,
+    pub timestamp: Option<u64>,
+    pub status: EncryptPoiStatus,
+    #[serde(rename = "erc20AmountRecipients")]
+    pub erc20_recipients: Vec<DopERC20AmountRecipient>,
+    #[serde(rename = "nftAmountRecipients")]
+    pub nft_recipients: Vec<DopNFTAmountRecipient>,
+}
+
+impl DecryptToOriginCandidate {
+    /// Whether the funds can only leave the wallet through a decrypt to
+    /// origin.
+    pub fn is_stuck(&self) -> bool {
+        matches!(
+            self.status,
+            EncryptPoiStatus::Pending | EncryptPoiStatus::Blocked
+        )
+    }
+}
+
+/// A decrypt whose parameters are fixed once built, so the gas estimate,
+/// proof and populated transaction all describe the same decrypt and use the
+/// endpoints that belong to its mode.
+#[derive(Clone)]
+pub struct Decrypt<'a> {
+    client: &'a DopClient,
+    mode: DecryptMode,
+    txid_version: String,
+    network_name: String,
+    wallet_id: String,
+    encryption_key: String,
+    erc20_recipients: Vec<DopERC20AmountRecipient>,
+    nft_recipients: Vec<DopNFTAmountRecipient>,
+    broadcaster_fee_recipient: Option<DopERC20AmountRecipient>,
+    fee_budget: Option<FeeTokenSelector>,
+    send_with_public_wallet: bool,
+    overall_batch_min_gas_price: Option<String>,
+    value: String,
+    utxo_selection: UtxoSelection,
+    proved: Option<ProvedTransaction>,
+}
+
+impl DopClient {
+    pub fn decrypt(
+        &self,
+        network_name: &str,
+        wallet_id: &str,
+        encryption_key: &str,
+    ) -> Decrypt<'_> {
+        Decrypt {
+            client: self,
+            mode: DecryptMode::ToAddress,
+            txid_version: DEFAULT_TXID_VERSION.to_string(),
+            network_name: network_name.to_string(),
+            wallet_id: wallet_id.to_string(),
+            encryption_key: encryption_key.to_string(),
+            erc20_recipients: Vec::new(),
+            nft_recipients: Vec::new(),
+            broadcaster_fee_recipient: None,
+            fee_budget: None,
+            send_with_public_wallet: false,
+            overall_batch_min_gas_price: None,
+            value: "0".to_string(),
+            utxo_selection: UtxoSelection::default(),
+            proved: None,
+        }
+    }
+
+    /// The wallet's encrypts that can still be decrypted to origin, newest
+    /// first, with their POI status and amounts.
+    pub async fn decrypt_to_origin_candidates(
+        &self,
+        network_name: &str,
+        wallet_id: &str,
+    ) -> Result<Vec<DecryptToOriginCandidate>> {
+        let resp = self
+            .client
+            .post(format!("{}/decrypt-to-origin-candidates", self.base_url()))
+            .json(&json!({
+                "txidVersion": DEFAULT_TXID_VERSION,
+                "networkName": network_name,
+                "dopWalletID": wallet_id,
+            }))
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("API call failed: {} (status {})", text, status));
+        }
+
+        Ok(resp.json().await?)
+    }
+}
+
+impl Decrypt<'_> {
+    pub fn mode(mut self, mode: DecryptMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Decrypts `candidate` to its origin, using the recipients it was
+    /// listed with.
+    pub fn to_origin(mut self, candidate: &DecryptToOriginCandidate) -> Self {
+        self.mode = DecryptMode::ToOrigin(candidate.txid.clone());
+        self.erc20_recipients = candidate.erc20_recipients.clone();
+        self.nft_recipients = candidate.nft_recipients.clone();
+        self
+    }
+
+    pub fn txid_version(mut self, txid_version: &str) -> Self {
+        self.txid_version = txid_version.to_string();
+        self
+    }
+
+    pub fn erc20_recipient(mut self, recipient: DopERC20AmountRecipient) -> Self {
+        self.erc20_recipients.push(recipient);
+        self
+    }
+
+    pub fn erc20_recipients(mut self, recipients: Vec<DopERC20AmountRecipient>) -> Self {
+        self.erc20_recipients.extend(recipients);
+        self
+    }
+
+    pub fn nft_recipients(mut self, recipients: Vec<DopNFTAmountRecipient>) -> Self {
+        self.nft_recipients.extend(recipients);
+        self
+    }
+
+    pub fn broadcaster_fee(mut self, recipient: DopERC20AmountRecipient) -> Self {
+        self.broadcaster_fee_recipient = Some(recipient);
+        self
+    }
+
+    /// Balances the settled broadcaster fee must fit in, checked before
+    /// proving. Pass the selector that chose the fee token.
+    pub fn fee_budget(mut self, selector: FeeTokenSelector) -> Self {
+        self.fee_budget = Some(selector);
+        self
+    }
+
+    pub fn send_with_public_wallet(mut self, public: bool) -> Self {
+        self.send_with_public_wallet = public;
+        self
+    }
+
+    pub fn overall_batch_min_gas_price(mut self, min_gas_price: &str) -> Self {
+        self.overall_batch_min_gas_price = Some(min_gas_price.to_string());
+        self
+    }
+
+    /// Wei of native token sent with the decrypt call, as a decimal string.
+    /// Defaults to `"0"`. It is part of the proof, so the estimate and proof
+    /// always use the same value. Not used by `DecryptMode::BaseTokenToAddress`.
+    pub fn value(mut self, value: &str) -> Self {
+        self.value = value.to_string();
+        self
+    }
+
+    /// Limits the notes the decrypt may spend. Not available for
+    /// `DecryptMode::ToOrigin`, which spends the notes of its encrypt.
+    pub fn utxos(mut self, selection: UtxoSelection) -> Self {
+        self.utxo_selection = selection;
+        self
+    }
+
+    pub fn is_proved(&self) -> bool {
+        self.proved.is_some()
+    }
+
+    pub fn proved_transaction(&self) -> Option<&ProvedTransaction> {
+        self.proved.as_ref()
+    }
+
+    pub async fn estimate_gas(
+        &self,
+        original_gas_details: TransactionGasDetails,
+        fee_token_details: Option<FeeTokenDetails>,
+    ) -> Result<DopTransactionGasEstimateResponse> {
+        self.validate()?;
+        match &self.mode {
+            DecryptMode::ToAddress => {
+                let mut payload = decrypt_gas_estimate_payload(
+                    self.txid_version.clone(),
+                    self.network_name.clone(),
+                    self.wallet_id.clone(),
+                    self.encryption_key.clone(),
+                    self.erc20_recipients.clone(),
+                    self.nft_recipients.clone(),
+                    original_gas_details,
+                    fee_token_details,
+                    self.send_with_public_wallet,
+                    self.value.clone(),
+                )?;
+                self.utxo_selection.apply(&mut payload)?;
+                self.client
+                    .send_request("/gas-estimate-for-unproven-decrypt", payload)
+                    .await
+            }
+            DecryptMode::BaseTokenToAddress => {
+                let (public_wallet_address, wrapped) = self.base_token_amount()?;
+                let mut payload = decrypt_base_token_gas_estimate_payload(
+                    self.txid_version.clone(),
+                    self.network_name.clone(),
+                    public_wallet_address,
+                    self.wallet_id.clone(),
+                    self.encryption_key.clone(),
+                    wrapped,
+                    original_gas_details,
+                    fee_token_details,
+                    self.send_with_public_wallet,
+                )?;
+                self.utxo_selection.apply(&mut payload)?;
+                self.client
+                    .send_request("/gas-estimate-for-unproven-decrypt-base-token", payload)
+                    .await
+            }
+            DecryptMode::ToOrigin(original_txid) => {
+                let (erc20_recipients, nft_recipients) = self.origin_recipients().await?;
+                self.client
+                    .gas_estimate_for_unproven_decrypt_to_origin(
+                        original_txid.clone(),
+                        self.txid_version.clone(),
+                        self.network_name.clone(),
+                        self.wallet_id.clone(),
+                        self.encryption_key.clone(),
+                        erc20_recipients,
+                        self.value.clone(),
+                        nft_recipients,
+                    )
+                    .await
+            }
+        }
+    }
+
+    pub async fn prove(&mut self) -> Result<ProvedTransaction> {
+        self.proved = None;
+        self.validate()?;
+        let proved = match self.mode.clone() {
+            DecryptMode::ToAddress => {
+                let mut payload = decrypt_proof_payload(
+                    self.txid_version.clone(),
+                    self.network_name.clone(),
+                    self.wallet_id.clone(),
+                    self.encryption_key.clone(),
+                    self.erc20_recipients.clone(),
+                    self.nft_recipients.clone(),
+                    self.broadcaster_fee_recipient.clone(),
+                    self.send_with_public_wallet,
+                    self.overall_batch_min_gas_price.clone(),
+                    self.value.clone(),
+                )?;
+                self.utxo_selection.apply(&mut payload)?;
+                self.client
+                    .run_proof_job("/generate-decrypt-proof", payload)
+                    .await?
+            }
+            DecryptMode::BaseTokenToAddress => {
+                let (public_wallet_address, wrapped) = self.base_token_amount()?;
+                let mut payload = decrypt_base_token_proof_payload(
+                    self.txid_version.clone(),
+                    self.network_name.clone(),
+                    public_wallet_address,
+                    self.wallet_id.clone(),
+                    self.encryption_key.clone(),
+                    wrapped,
+                    self.broadcaster_fee_recipient.clone(),
+                    self.send_with_public_wallet,
+                    self.overall_batch_min_gas_price.clone(),
+                )?;
+                self.utxo_selection.apply(&mut payload)?;
+                self.client
+                    .run_proof_job("/generate-decrypt-base-token-proof", payload)
+                    .await?
+            }
+            DecryptMode::ToOrigin(original_txid) => {
+                // Keep the looked-up recipients so populate sends the same ones.
+                let (erc20_recipients, nft_recipients) = self.origin_recipients().await?;
+                self.erc20_recipients = erc20_recipients;
+                self.nft_recipients = nft_recipients;
+                self.client
+                    .generate_decrypt_to_origin_proof(
+                        original_txid,
+                        self.txid_version.clone(),
+                        self.network_name.clone(),
+                        self.wallet_id.clone(),
+                        self.encryption_key.clone(),
+                        self.erc20_recipients.clone(),
+                        self.nft_recipients.clone(),
+                        self.value.clone(),
+                    )
+                    .await?
+            }
+        };
+        self.proved = Some(proved.clone());
+        Ok(proved)
+    }
+
+    pub async fn populate(
+        &self,
+        gas_details: TransactionGasDetails,
+    ) -> Result<DopPopulateTransactionResponse> {
+        let Some(proved) = self.proved.as_ref() else {
+            bail!("Decrypt must be proved before it can be populated");
+        };
+        match &self.mode {
+            DecryptMode::ToAddress => {
+                self.client
+                    .populate_proved_decrypt(
+                        proved,
+                        self.txid_version.clone(),
+                        self.network_name.clone(),
+                        self.wallet_id.clone(),
+                        self.erc20_recipients.clone(),
+                        self.nft_recipients.clone(),
+                        self.broadcaster_fee_recipient.clone(),
+                        self.send_with_public_wallet,
+                        self.overall_batch_min_gas_price.clone(),
+                        gas_details,
+                    )
+                    .await
+            }
+            DecryptMode::BaseTokenToAddress => {
+                let (public_wallet_address, wrapped) = self.base_token_amount()?;
+                self.client
+                    .populate_proved_decrypt_base_token(
+                        proved,
+                        self.txid_version.clone(),
+                        self.network_name.clone(),
+                        public_wallet_address,
+                        self.wallet_id.clone(),
+                        wrapped,
+                        self.broadcaster_fee_recipient.clone(),
+                        self.send_with_public_wallet,
+                        self.overall_batch_min_gas_price.clone(),
+                        gas_details,
+                    )
+                    .await
+            }
+            DecryptMode::ToOrigin(_) => {
+                self.client
+                    .populate_proved_decrypt_to_origin(
+                        proved,
+                        self.txid_version.clone(),
+                        self.network_name.clone(),
+                        self.wallet_id.clone(),
+                        self.erc20_recipients.clone(),
+                        self.nft_recipients.clone(),
+                        gas_details,
+                    )
+                    .await
+            }
+        }
+    }
+
+    /// Runs estimate, prove and populate in one go, like
+    /// `PrivateTransfer::transfer`. Decrypts to origin cannot pay a
+    /// broadcaster, so `broadcaster_fee` must be `None` for them.
+    pub async fn decrypt<O, F>(
+        mut self,
+        oracle: &O,
+        broadcaster_fee: Option<F>,
+    ) -> Result<DecryptResult>
+    where
+        O: GasFeeOracle,
+        F: Fn(&TransactionGasDetails) -> Result<DopERC20AmountRecipient>,
+    {
+        if broadcaster_fee.is_some() && matches!(self.mode, DecryptMode::ToOrigin(_)) {
+            bail!("A decrypt to origin cannot pay a broadcaster fee");
+        }
+        if matches!(self.mode, DecryptMode::ToOrigin(_)) {
+            (self.erc20_recipients, self.nft_recipients) = self.origin_recipients().await?;
+        }
+
+        let settled = settle_gas_and_fee(
+            &self.network_name,
+            oracle,
+            broadcaster_fee.as_ref(),
+            self.fee_budget.as_ref(),
+            |gas_details, fee_token_details| self.estimate_gas(gas_details, fee_token_details),
+        )
+        .await?;
+        let gas_details = settled.gas_details;
+        if let Some(fee) = settled.broadcaster_fee {
+            self.broadcaster_fee_recipient = Some(fee);
+        }
+
+        let proved = self.prove().await?;
+        let populated = self.populate(gas_details.clone()).await?;
+
+        Ok(DecryptResult {
+            transaction: populated.transaction,
+            nullifiers: populated.nullifiers.unwrap_or(proved.nullifiers),
+            gas_details,
+            broadcaster_fee: self.broadcaster_fee_recipient,
+            erc20_recipients: self.erc20_recipients,
+            nft_recipients: self.nft_recipients,
+        })
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.value.trim().parse::<u128>().is_err() {
+            bail!("Invalid value: {}", self.value);
+        }
+        for recipient in &self.erc20_recipients {
+            recipient
+                .recipient_address
+                .parse::<EvmAddress>()
+                .map_err(|e| {
+                    anyhow!(
+                        "Invalid EVM recipient {}: {}",
+                        recipient.recipient_address,
+                        e
+                    )
+                })?;
+        }
+
+        match &self.mode {
+            DecryptMode::ToAddress => {
+                if self.erc20_recipients.is_empty() && self.nft_recipients.is_empty() {
+                    bail!("Decrypt has no recipients");
+                }
+            }
+            DecryptMode::BaseTokenToAddress => {
+                if self.erc20_recipients.len() != 1 || !self.nft_recipients.is_empty() {
+                    bail!("A base token decrypt takes exactly one ERC20 recipient and no NFTs");
+                }
+                if self.value.trim() != "0" {
+                    bail!("A base token decrypt does not take a value");
+                }
+            }
+            DecryptMode::ToOrigin(_) => {
+                if self.broadcaster_fee_recipient.is_some() {
+                    bail!("A decrypt to origin cannot pay a broadcaster fee");
+                }
+                if !self.utxo_selection.is_empty() {
+                    bail!(
+                        "A decrypt to origin spends the notes of its encrypt and takes no UTXO selection"
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn base_token_amount(&self) -> Result<(String, DopERC20Amount)> {
+        let [recipient] = self.erc20_recipients.as_slice() else {
+            bail!("A base token decrypt takes exactly one ERC20 recipient and no NFTs");
+        };
+        Ok((
+            recipient.recipient_address.clone(),
+            DopERC20Amount {
+                token_address: recipient.token_address,
+                amount: recipient.amount.clone(),
+            },
+        ))
+    }
+
+    async fn origin_recipients(
+        &self,
+    ) -> Result<(Vec<DopERC20AmountRecipient>, Vec<DopNFTAmountRecipient>)> {
+        let DecryptMode::ToOrigin(original_txid) = &self.mode else {
+            bail!("Not a decrypt to origin");
+        };
+        if !self.erc20_recipients.is_empty() || !self.nft_recipients.is_empty() {
+            return Ok((self.erc20_recipients.clone(), self.nft_recipients.clone()));
+        }
+        self.client
+            .get_erc20_and_nft_amount_recipients_for_decrypt_to_origin(
+                self.txid_version.clone(),
+                self.network_name.clone(),
+                self.wallet_id.clone(),
+                original_txid.clone(),
+            )
+            .await
+    }
+}
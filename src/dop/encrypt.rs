@@ -0,0 +1,84 @@
+use crate::dop::{
+    ContractTransaction, DEFAULT_TXID_VERSION, DopClient, DopERC20AmountRecipient,
+    DopNFTAmountRecipient, EvmAddress, GasFeeOracle, TransactionGasDetails,
+};
+use anyhow::Result;
+use sha3::{Digest, Keccak256};
+use std::future::Future;
+
+/// Signs messages for an EVM account, e.g. a `LocalSigner`, a hardware
+/// wallet or a remote signing service.
+pub trait MessageSigner {
+    fn address(&self) -> EvmAddress;
+
+    /// Signs `message` as an EIP-191 personal message and returns the 65
+    /// byte `r || s || v` signature.
+    fn sign_message(&self, message: &[u8]) -> impl Future<Output = Result<Vec<u8>>> + Send;
+}
+
+/// The encrypt private key is the keccak256 hash of the account's signature
+/// over the engine's signature message, as a `0x` hex string.
+pub fn encrypt_private_key_from_signature(signature: &[u8]) -> String {
+    format!("0x{}", hex::encode(Keccak256::digest(signature)))
+}
+
+#[derive(Debug, Clone)]
+pub struct EncryptResult {
+    /// Unsigned transaction from the signer's account, ready to sign and send.
+    pub transaction: ContractTransaction,
+    pub gas_details: TransactionGasDetails,
+}
+
+impl DopClient {
+    /// Asks `signer` to sign the engine's encrypt signature message and
+    /// derives the encrypt private key from the signature.
+    pub async fn derive_encrypt_private_key<S: MessageSigner>(&self, signer: &S) -> Result<String> {
+        let message = self.get_encrypt_private_key_signature_message().await?;
+        let signature = signer.sign_message(message.as_bytes()).await?;
+        Ok(encrypt_private_key_from_signature(&signature))
+    }
+
+    /// Encrypts tokens from the signer's public account into the private
+    /// pool: derives the encrypt key, estimates gas, prices it with `oracle`
+    /// and populates the transaction.
+    pub async fn encrypt<S: MessageSigner, O: GasFeeOracle>(
+        &self,
+        network_name: &str,
+        signer: &S,
+        oracle: &O,
+        erc20_recipients: Vec<DopERC20AmountRecipient>,
+        nft_recipients: Vec<DopNFTAmountRecipient>,
+    ) -> Result<EncryptResult> {
+        let encrypt_private_key = self.derive_encrypt_private_key(signer).await?;
+
+        let estimate = self
+            .gas_estimate_for_encrypt(
+                DEFAULT_TXID_VERSION.to_string(),
+                network_name.to_string(),
+                encrypt_private_key.clone(),
+                erc20_recipients.clone(),
+                nft_recipients.clone(),
+                signer.address().to_string(),
+            )
+            .await?;
+        let gas_details = oracle
+            .gas_details(network_name, &estimate.gas_estimate)
+            .await?;
+
+        let populated = self
+            .populate_encrypt(
+                DEFAULT_TXID_VERSION.to_string(),
+                network_name.to_string(),
+                encrypt_private_key,
+                erc20_recipients,
+                nft_recipients,
+                Some(serde_json::to_value(&gas_details)?),
+            )
+            .await?;
+
+        Ok(EncryptResult {
+            transaction: populated.transaction,
+            gas_details,
+        })
+    }
+}
@@ -9,7 +9,7 @@ impl DopClient {
         for _ in 0..10 {
             if self
                 .client
-                .get(&format!("{}/health", self.base_url()))
+                .get(format!("{}/health", self.base_url()))
                 .send()
                 .await
                 .is_ok()
@@ -31,16 +31,19 @@ impl DopClient {
         use_native_artifacts: Option<bool>,
         skip_merkletree_scans: Option<bool>,
     ) -> Result<()> {
-        let payload = serde_json::json!({
+        let mut payload = serde_json::json!({
             "dbPath": db_path.unwrap_or("database/DOP.db"),
             "engineName": engine_name.unwrap_or("DOP Engine"),
             "shouldDebug": should_debug.unwrap_or(false),
             "useNativeArtifacts": use_native_artifacts.unwrap_or(false),
             "skipMerkletreeScans": skip_merkletree_scans.unwrap_or(false),
         });
+        if !self.poi_node_urls.is_empty() {
+            payload["poiNodeURLs"] = json!(self.poi_node_urls);
+        }
 
         self.client
-            .post(&format!("{}/init", self.base_url()))
+            .post(format!("{}/init", self.base_url()))
             .json(&payload)
             .send()
             .await
@@ -50,7 +53,7 @@ impl DopClient {
 
     pub async fn close_engine(&self) -> Result<()> {
         self.client
-            .get(&format!("{}/close", self.base_url()))
+            .get(format!("{}/close", self.base_url()))
             .send()
             .await?;
         Ok(())
@@ -59,7 +62,7 @@ impl DopClient {
     pub async fn get_engine_info(&self) -> Result<Value> {
         let res = self
             .client
-            .get(&format!("{}/engine", self.base_url()))
+            .get(format!("{}/engine", self.base_url()))
             .send()
             .await?
             .json::<Value>()
@@ -85,7 +88,7 @@ impl DopClient {
         });
 
         self.client
-            .post(&format!("{}/scan-contract-history", self.base_url()))
+            .post(format!("{}/scan-contract-history", self.base_url()))
             .json(&payload)
             .send()
             .await?
@@ -96,7 +99,7 @@ impl DopClient {
 
     pub async fn set_loggers(&self) -> Result<()> {
         self.client
-            .post(&format!("{}/set-loggers", self.base_url()))
+            .post(format!("{}/set-loggers", self.base_url()))
             .send()
             .await?
             .error_for_status()
@@ -124,7 +127,7 @@ impl DopClient {
 
         let res = self
             .client
-            .post(&format!("{}/load-provider", self.base_url()))
+            .post(format!("{}/load-provider", self.base_url()))
             .json(&payload)
             .send()
             .await?;
@@ -0,0 +1,232 @@
+use crate::dop::{
+    BroadcasterFeeQuote, DopERC20Amount, DopERC20AmountRecipient, EvmAddress, FeeTokenDetails,
+    TransactionGasDetails,
+};
+use anyhow::{Result, anyhow, bail};
+use std::collections::HashMap;
+use std::fmt;
+use time::OffsetDateTime;
+
+/// Why a broadcaster quote was not used to pay the fee.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeeTokenRejection {
+    Expired,
+    NoAvailableWallets,
+    /// The wallet cannot cover the fee on top of what the transaction
+    /// already sends in that token.
+    InsufficientBalance {
+        needed: u128,
+        available: u128,
+    },
+    InvalidQuote(String),
+    /// Affordable, but another quote was cheaper. `cost` is the fee in the
+    /// common unit of `token_value`, if the token has a value.
+    NotCheapest {
+        fee: u128,
+        cost: Option<f64>,
+    },
+}
+
+impl fmt::Display for FeeTokenRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Expired => f.write_str("quote has expired"),
+            Self::NoAvailableWallets => f.write_str("broadcaster has no available wallets"),
+            Self::InsufficientBalance { needed, available } => {
+                write!(f, "needs {} but only {} is spendable", needed, available)
+            }
+            Self::InvalidQuote(reason) => write!(f, "invalid quote: {}", reason),
+            Self::NotCheapest {
+                fee,
+                cost: Some(cost),
+            } => write!(f, "fee {} costs more ({:.6})", fee, cost),
+            Self::NotCheapest { fee, cost: None } => {
+                write!(f, "fee {} has no token value to compare", fee)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FeeTokenChoice {
+    pub quote: BroadcasterFeeQuote,
+    /// For `gas_estimate_for_unproven_*`.
+    pub fee_token_details: FeeTokenDetails,
+    /// For `broadcaster_fee_recipient` when proving and populating.
+    pub broadcaster_fee: DopERC20AmountRecipient,
+}
+
+#[derive(Debug, Clone)]
+pub struct FeeTokenSelection {
+    pub choice: Option<FeeTokenChoice>,
+    /// Why every other quote was passed over: affordable ones first,
+    /// cheapest first, then the rest in the order given.
+    pub passed_over: Vec<(BroadcasterFeeQuote, FeeTokenRejection)>,
+}
+
+impl FeeTokenSelection {
+    /// One line per quote considered, starting with the chosen one.
+    pub fn explanation(&self) -> String {
+        let mut lines = Vec::new();
+        match &self.choice {
+            Some(choice) => lines.push(format!(
+                "chose {} from {}: fee {}",
+                choice.quote.token_address, choice.quote.fees_id, choice.broadcaster_fee.amount
+            )),
+            None => lines.push("no quote can be paid from this wallet".to_string()),
+        }
+        for (quote, reason) in &self.passed_over {
+            lines.push(format!(
+                "skipped {} from {}: {}",
+                quote.token_address, quote.fees_id, reason
+            ));
+        }
+        lines.join("\n")
+    }
+
+    pub fn into_choice(self) -> Result<FeeTokenChoice> {
+        let explanation = self.explanation();
+        self.choice.ok_or_else(|| anyhow!(explanation))
+    }
+}
+
+/// Picks the fee token for a broadcaster transaction from the wallet's
+/// spendable balances.
+///
+/// Fees in different tokens are compared through `token_value`. Tokens
+/// without a value rank after all valued ones, in the order of the quotes
+/// given to `select`.
+#[derive(Debug, Clone, Default)]
+pub struct FeeTokenSelector {
+    balances: HashMap<EvmAddress, u128>,
+    spending: HashMap<EvmAddress, u128>,
+    values: HashMap<EvmAddress, f64>,
+}
+
+impl FeeTokenSelector {
+    pub fn new(spendable_balances: impl IntoIterator<Item = DopERC20Amount>) -> Result<Self> {
+        let mut selector = Self::default();
+        for balance in spendable_balances {
+            let amount = parse_amount(&balance.amount)?;
+            *selector.balances.entry(balance.token_address).or_default() += amount;
+        }
+        Ok(selector)
+    }
+
+    /// Amounts the transaction itself sends. The fee has to fit in what is
+    /// left of each token.
+    pub fn spending(mut self, recipients: &[DopERC20AmountRecipient]) -> Result<Self> {
+        for recipient in recipients {
+            let amount = parse_amount(&recipient.amount)?;
+            *self.spending.entry(recipient.token_address).or_default() += amount;
+        }
+        Ok(self)
+    }
+
+    /// Price of one whole token in any common unit, e.g. USD.
+    pub fn token_value(mut self, token_address: EvmAddress, decimals: u8, price: f64) -> Self {
+        self.values
+            .insert(token_address, price / 10f64.powi(decimals as i32));
+        self
+    }
+
+    pub fn select(
+        &self,
+        quotes: &[BroadcasterFeeQuote],
+        gas_details: &TransactionGasDetails,
+    ) -> FeeTokenSelection {
+        let now = OffsetDateTime::now_utc();
+        let mut affordable = Vec::new();
+        let mut passed_over = Vec::new();
+
+        for (index, quote) in quotes.iter().enumerate() {
+            match self.check(quote, gas_details, now) {
+                Ok(fee) => {
+                    let cost = self
+                        .values
+                        .get(&quote.token_address)
+                        .map(|v| fee as f64 * v);
+                    affordable.push((cost, index, fee, quote));
+                }
+                Err(rejection) => passed_over.push((quote.clone(), rejection)),
+            }
+        }
+
+        // Valued tokens by cost, then the rest in the caller's order.
+        affordable.sort_by(|a, b| match (a.0, b.0) {
+            (Some(x), Some(y)) => x.total_cmp(&y).then(a.1.cmp(&b.1)),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => a.1.cmp(&b.1),
+        });
+
+        let mut affordable = affordable.into_iter();
+        let choice = affordable.next().map(|(_, _, fee, quote)| FeeTokenChoice {
+            quote: quote.clone(),
+            fee_token_details: FeeTokenDetails {
+                token_address: quote.token_address,
+                max_amount: fee.to_string(),
+            },
+            broadcaster_fee: DopERC20AmountRecipient {
+                token_address: quote.token_address,
+                amount: fee.to_string(),
+                recipient_address: quote.dop_address.to_string(),
+            },
+        });
+        let cheaper_first: Vec<_> = affordable
+            .map(|(cost, _, fee, quote)| {
+                (quote.clone(), FeeTokenRejection::NotCheapest { fee, cost })
+            })
+            .collect();
+
+        FeeTokenSelection {
+            choice,
+            passed_over: cheaper_first.into_iter().chain(passed_over).collect(),
+        }
+    }
+
+    fn check(
+        &self,
+        quote: &BroadcasterFeeQuote,
+        gas_details: &TransactionGasDetails,
+        now: OffsetDateTime,
+    ) -> Result<u128, FeeTokenRejection> {
+        if quote.is_expired_at(now) {
+            return Err(FeeTokenRejection::Expired);
+        }
+        if quote.available_wallets == 0 {
+            return Err(FeeTokenRejection::NoAvailableWallets);
+        }
+        let fee = quote
+            .fee_amount(gas_details)
+            .map_err(|e| FeeTokenRejection::InvalidQuote(e.to_string()))?;
+        self.check_balance(quote.token_address, fee)?;
+        Ok(fee)
+    }
+
+    /// Checks that the wallet can still pay `fee` once the gas estimate has
+    /// settled. Re-estimating with the fee token can raise the fee above the
+    /// amount `select` checked.
+    pub fn check_fee(&self, fee: &DopERC20AmountRecipient) -> Result<(), FeeTokenRejection> {
+        let amount = parse_amount(&fee.amount)
+            .map_err(|e| FeeTokenRejection::InvalidQuote(e.to_string()))?;
+        self.check_balance(fee.token_address, amount)
+    }
+
+    fn check_balance(&self, token_address: EvmAddress, fee: u128) -> Result<(), FeeTokenRejection> {
+        let spending = self.spending.get(&token_address).copied().unwrap_or(0);
+        let available = self.balances.get(&token_address).copied().unwrap_or(0);
+        let needed = fee.saturating_add(spending);
+        if needed > available {
+            return Err(FeeTokenRejection::InsufficientBalance { needed, available });
+        }
+        Ok(())
+    }
+}
+
+fn parse_amount(amount: &str) -> Result<u128> {
+    let Ok(value) = amount.parse() else {
+        bail!("Invalid amount: {}", amount);
+    };
+    Ok(value)
+}
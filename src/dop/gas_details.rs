@@ -0,0 +1,167 @@
+use crate::dop::TransactionGasDetails;
+use anyhow::{Result, anyhow, bail};
+
+impl TransactionGasDetails {
+    pub fn type0(gas_estimate: u128, gas_price: u128) -> Self {
+        Self::Type0 {
+            gas_estimate: gas_estimate.to_string(),
+            gas_price: gas_price.to_string(),
+        }
+    }
+
+    pub fn type1(gas_estimate: u128, gas_price: u128) -> Self {
+        Self::Type1 {
+            gas_estimate: gas_estimate.to_string(),
+            gas_price: gas_price.to_string(),
+        }
+    }
+
+    pub fn type2(
+        gas_estimate: u128,
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+    ) -> Result<Self> {
+        let details = Self::Type2 {
+            gas_estimate: gas_estimate.to_string(),
+            max_fee_per_gas: max_fee_per_gas.to_string(),
+            max_priority_fee_per_gas: max_priority_fee_per_gas.to_string(),
+        };
+        details.validate()?;
+        Ok(details)
+    }
+
+    /// The `evmGasType` this variant is sent as.
+    pub fn evm_gas_type(&self) -> u8 {
+        match self {
+            Self::Type0 { .. } => 0,
+            Self::Type1 { .. } => 1,
+            Self::Type2 { .. } => 2,
+        }
+    }
+
+    pub fn gas_estimate(&self) -> &str {
+        match self {
+            Self::Type0 { gas_estimate, .. }
+            | Self::Type1 { gas_estimate, .. }
+            | Self::Type2 { gas_estimate, .. } => gas_estimate,
+        }
+    }
+
+    /// Keeps the prices and replaces the gas estimate, e.g. with the one
+    /// from a `DopTransactionGasEstimateResponse`.
+    pub fn with_gas_estimate(mut self, new_estimate: &str) -> Self {
+        match &mut self {
+            Self::Type0 { gas_estimate, .. }
+            | Self::Type1 { gas_estimate, .. }
+            | Self::Type2 { gas_estimate, .. } => *gas_estimate = new_estimate.to_string(),
+        }
+        self
+    }
+
+    /// Highest price per gas the transaction can pay: the gas price, or the
+    /// max fee for EIP-1559.
+    pub fn max_fee_per_gas(&self) -> Result<u128> {
+        match self {
+            Self::Type0 { gas_price, .. } | Self::Type1 { gas_price, .. } => {
+                parse_wei(gas_price, "gas price")
+            }
+            Self::Type2 {
+                max_fee_per_gas, ..
+            } => parse_wei(max_fee_per_gas, "max fee per gas"),
+        }
+    }
+
+    /// Worst-case fee in wei: the whole gas estimate used at the highest
+    /// price per gas.
+    pub fn max_cost(&self) -> Result<u128> {
+        parse_wei(self.gas_estimate(), "gas estimate")?
+            .checked_mul(self.max_fee_per_gas()?)
+            .ok_or_else(|| anyhow!("Maximum transaction cost overflows"))
+    }
+
+    /// Checks that every field is a base-10 integer and, for EIP-1559, that
+    /// the priority fee does not exceed the max fee.
+    pub fn validate(&self) -> Result<()> {
+        parse_wei(self.gas_estimate(), "gas estimate")?;
+        let max_fee = self.max_fee_per_gas()?;
+        if let Self::Type2 {
+            max_priority_fee_per_gas,
+            ..
+        } = self
+        {
+            let priority_fee = parse_wei(max_priority_fee_per_gas, "max priority fee per gas")?;
+            if priority_fee > max_fee {
+                bail!(
+                    "max_priority_fee_per_gas ({}) exceeds max_fee_per_gas ({})",
+                    priority_fee,
+                    max_fee
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts to the given `evmGasType`, for networks that do not take the
+    /// current one. EIP-1559 details become a gas price equal to the max fee,
+    /// so the transaction never pays less than it could have; a gas price
+    /// becomes both the max fee and the priority fee.
+    pub fn into_gas_type(self, evm_gas_type: u8) -> Result<Self> {
+        if evm_gas_type > 2 {
+            bail!("Unknown evmGasType {}", evm_gas_type);
+        }
+        if evm_gas_type == self.evm_gas_type() {
+            return Ok(self);
+        }
+
+        let (gas_estimate, price) = self.into_estimate_and_price();
+        Ok(match evm_gas_type {
+            0 => Self::Type0 {
+                gas_estimate,
+                gas_price: price,
+            },
+            1 => Self::Type1 {
+                gas_estimate,
+                gas_price: price,
+            },
+            _ => Self::Type2 {
+                gas_estimate,
+                max_fee_per_gas: price.clone(),
+                max_priority_fee_per_gas: price,
+            },
+        })
+    }
+
+    /// Type 0 details, for networks without EIP-1559 or EIP-2930.
+    pub fn into_legacy(self) -> Self {
+        let (gas_estimate, gas_price) = self.into_estimate_and_price();
+        Self::Type0 {
+            gas_estimate,
+            gas_price,
+        }
+    }
+
+    /// The gas estimate and the highest price per gas.
+    fn into_estimate_and_price(self) -> (String, String) {
+        match self {
+            Self::Type0 {
+                gas_estimate,
+                gas_price,
+            }
+            | Self::Type1 {
+                gas_estimate,
+                gas_price,
+            } => (gas_estimate, gas_price),
+            Self::Type2 {
+                gas_estimate,
+                max_fee_per_gas,
+                ..
+            } => (gas_estimate, max_fee_per_gas),
+        }
+    }
+}
+
+pub(crate) fn parse_wei(value: &str, name: &str) -> Result<u128> {
+    value
+        .parse()
+        .map_err(|_| anyhow!("Invalid {}: {}", name, value))
+}
@@ -0,0 +1,305 @@
+use crate::dop::{
+    DopClient, DopERC20AmountRecipient, DopTransactionGasEstimateResponse, FeeTokenDetails,
+    FeeTokenSelector, GasFeeOracle, TransactionGasDetails, gas_details::parse_wei,
+};
+use anyhow::{Result, anyhow, bail};
+use serde::Deserialize;
+use std::future::Future;
+
+// Adding the broadcaster fee output can change the gas estimate, which in
+// turn changes the fee. This usually settles after one extra round.
+const MAX_GAS_ESTIMATE_ROUNDS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GasSpeed {
+    Slow,
+    #[default]
+    Average,
+    Fast,
+}
+
+impl GasSpeed {
+    /// Index into `GasFeeData::priority_fee_percentiles` (25th, 50th and
+    /// 75th percentile of recent priority fees).
+    fn percentile_index(self) -> usize {
+        match self {
+            Self::Slow => 0,
+            Self::Average => 1,
+            Self::Fast => 2,
+        }
+    }
+
+    /// Scales a single gas price when there is no fee history to pick from.
+    fn price_percent(self) -> u128 {
+        match self {
+            Self::Slow => 90,
+            Self::Average => 100,
+            Self::Fast => 125,
+        }
+    }
+}
+
+/// Current fee market on one network, as read from its loaded provider.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GasFeeData {
+    /// The `evmGasType` the engine expects on this network: 0 and 1 are
+    /// priced by `gas_price`, 2 by base and priority fee.
+    #[serde(rename = "evmGasType")]
+    pub evm_gas_type: u8,
+    #[serde(rename = "gasPrice")]
+    pub gas_price: Option<String>,
+    #[serde(rename = "baseFeePerGas")]
+    pub base_fee_per_gas: Option<String>,
+    #[serde(rename = "maxPriorityFeePerGas")]
+    pub max_priority_fee_per_gas: Option<String>,
+    /// Slow, average and fast priority fees from recent fee history.
+    #[serde(rename = "priorityFeePercentiles")]
+    pub priority_fee_percentiles: Option<Vec<String>>,
+}
+
+/// How to turn fee data into gas details. Margins are percentages added on
+/// top of the measured value.
+#[derive(Debug, Clone)]
+pub struct GasPriceOptions {
+    pub speed: GasSpeed,
+    /// Picks the gas type: broadcaster transactions use a different type
+    /// than self-signed ones on some networks.
+    pub send_with_public_wallet: bool,
+    pub gas_estimate_margin_percent: u32,
+    /// Headroom for base fee increases before the transaction is mined.
+    pub base_fee_margin_percent: u32,
+    pub gas_price_margin_percent: u32,
+}
+
+impl Default for GasPriceOptions {
+    fn default() -> Self {
+        Self {
+            speed: GasSpeed::Average,
+            send_with_public_wallet: true,
+            gas_estimate_margin_percent: 20,
+            base_fee_margin_percent: 100,
+            gas_price_margin_percent: 10,
+        }
+    }
+}
+
+impl GasPriceOptions {
+    pub fn speed(mut self, speed: GasSpeed) -> Self {
+        self.speed = speed;
+        self
+    }
+}
+
+impl GasFeeData {
+    /// Builds gas details for a transaction estimated at `gas_estimate`.
+    pub fn gas_details(
+        &self,
+        gas_estimate: &str,
+        options: &GasPriceOptions,
+    ) -> Result<TransactionGasDetails> {
+        let gas_estimate = add_margin(
+            parse_wei(gas_estimate, "gas estimate")?,
+            options.gas_estimate_margin_percent,
+        )
+        .to_string();
+
+        match self.evm_gas_type {
+            0 | 1 => {
+                let gas_price = self
+                    .gas_price
+                    .as_deref()
+                    .ok_or_else(|| anyhow!("Provider returned no gas price"))?;
+                let gas_price =
+                    parse_wei(gas_price, "gas price")? * options.speed.price_percent() / 100;
+                let gas_price = add_margin(gas_price, options.gas_price_margin_percent).to_string();
+                Ok(if self.evm_gas_type == 0 {
+                    TransactionGasDetails::Type0 {
+                        gas_estimate,
+                        gas_price,
+                    }
+                } else {
+                    TransactionGasDetails::Type1 {
+                        gas_estimate,
+                        gas_price,
+                    }
+                })
+            }
+            2 => {
+                let base_fee = self
+                    .base_fee_per_gas
+                    .as_deref()
+                    .ok_or_else(|| anyhow!("Provider returned no base fee"))?;
+                let base_fee = parse_wei(base_fee, "base fee")?;
+                let priority_fee = self.priority_fee(options.speed)?;
+                let max_fee = add_margin(base_fee, options.base_fee_margin_percent) + priority_fee;
+                Ok(TransactionGasDetails::Type2 {
+                    gas_estimate,
+                    max_fee_per_gas: max_fee.to_string(),
+                    max_priority_fee_per_gas: priority_fee.to_string(),
+                })
+            }
+            other => bail!("Unknown evmGasType {}", other),
+        }
+    }
+
+    fn priority_fee(&self, speed: GasSpeed) -> Result<u128> {
+        if let Some(fee) = self
+            .priority_fee_percentiles
+            .as_ref()
+            .and_then(|p| p.get(speed.percentile_index()))
+        {
+            return parse_wei(fee, "priority fee");
+        }
+        let fee = self
+            .max_priority_fee_per_gas
+            .as_deref()
+            .ok_or_else(|| anyhow!("Provider returned no priority fee"))?;
+        Ok(parse_wei(fee, "priority fee")? * speed.price_percent() / 100)
+    }
+}
+
+fn add_margin(value: u128, percent: u32) -> u128 {
+    value * (100 + percent as u128) / 100
+}
+
+/// `GasFeeOracle` backed by the providers loaded into the engine.
+#[derive(Clone)]
+pub struct ProviderGasOracle<'a> {
+    client: &'a DopClient,
+    options: GasPriceOptions,
+}
+
+impl GasFeeOracle for ProviderGasOracle<'_> {
+    async fn gas_details(
+        &self,
+        network_name: &str,
+        gas_estimate: &str,
+    ) -> Result<TransactionGasDetails> {
+        self.client
+            .suggest_gas_details_with(network_name, gas_estimate, &self.options)
+            .await
+    }
+}
+
+impl DopClient {
+    pub async fn gas_fee_data(
+        &self,
+        network_name: &str,
+        send_with_public_wallet: bool,
+    ) -> Result<GasFeeData> {
+        let resp = self
+            .client
+            .get(format!("{}/gas-fee-data", self.base_url()))
+            .query(&[
+                ("networkName", network_name.to_string()),
+                ("sendWithPublicWallet", send_with_public_wallet.to_string()),
+            ])
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("API call failed: {} (status {})", text, status));
+        }
+
+        Ok(resp.json::<GasFeeData>().await?)
+    }
+
+    /// Gas details for a self-signed transaction estimated at
+    /// `gas_estimate`, e.g. from `gas_estimate_for_unproven_transfer`, with
+    /// the default margins.
+    pub async fn suggest_gas_details(
+        &self,
+        network_name: &str,
+        speed: GasSpeed,
+        gas_estimate: &str,
+    ) -> Result<TransactionGasDetails> {
+        let options = GasPriceOptions::default().speed(speed);
+        self.suggest_gas_details_with(network_name, gas_estimate, &options)
+            .await
+    }
+
+    pub async fn suggest_gas_details_with(
+        &self,
+        network_name: &str,
+        gas_estimate: &str,
+        options: &GasPriceOptions,
+    ) -> Result<TransactionGasDetails> {
+        self.gas_fee_data(network_name, options.send_with_public_wallet)
+            .await?
+            .gas_details(gas_estimate, options)
+    }
+
+    pub fn gas_oracle(&self, options: GasPriceOptions) -> ProviderGasOracle<'_> {
+        ProviderGasOracle {
+            client: self,
+            options,
+        }
+    }
+}
+
+/// Gas details and broadcaster fee that `settle_gas_and_fee` agreed on.
+pub(crate) struct SettledGas {
+    pub gas_details: TransactionGasDetails,
+    pub broadcaster_fee: Option<DopERC20AmountRecipient>,
+}
+
+/// Estimates gas with `estimate` and prices it with `oracle`. With a
+/// `broadcaster_fee`, the fee is recomputed from each priced estimate and
+/// the transaction re-estimated until two raw estimates in a row agree.
+/// Running out of rounds is an error: proving then would pay a fee worked
+/// out for a different estimate. So is a settled fee that `fee_budget`
+/// cannot cover.
+pub(crate) async fn settle_gas_and_fee<O, F, E, Fut>(
+    network_name: &str,
+    oracle: &O,
+    broadcaster_fee: Option<&F>,
+    fee_budget: Option<&FeeTokenSelector>,
+    mut estimate: E,
+) -> Result<SettledGas>
+where
+    O: GasFeeOracle,
+    F: Fn(&TransactionGasDetails) -> Result<DopERC20AmountRecipient>,
+    E: FnMut(TransactionGasDetails, Option<FeeTokenDetails>) -> Fut,
+    Fut: Future<Output = Result<DopTransactionGasEstimateResponse>>,
+{
+    let mut gas_details = oracle.gas_details(network_name, "0").await?;
+    let mut fee_token_details = None;
+    let mut previous_estimate: Option<String> = None;
+
+    for _ in 0..MAX_GAS_ESTIMATE_ROUNDS {
+        // Compare raw estimates: the oracle may add a margin on top.
+        let raw_estimate = estimate(gas_details.clone(), fee_token_details.clone())
+            .await?
+            .gas_estimate;
+        gas_details = oracle.gas_details(network_name, &raw_estimate).await?;
+
+        let Some(fee_for) = broadcaster_fee else {
+            return Ok(SettledGas {
+                gas_details,
+                broadcaster_fee: None,
+            });
+        };
+        let fee = fee_for(&gas_details)?;
+        if previous_estimate.as_deref() == Some(raw_estimate.as_str()) {
+            if let Some(Err(rejection)) = fee_budget.map(|budget| budget.check_fee(&fee)) {
+                bail!("Settled broadcaster fee cannot be paid: {}", rejection);
+            }
+            return Ok(SettledGas {
+                gas_details,
+                broadcaster_fee: Some(fee),
+            });
+        }
+        fee_token_details = Some(FeeTokenDetails {
+            token_address: fee.token_address,
+            max_amount: fee.amount.clone(),
+        });
+        previous_estimate = Some(raw_estimate);
+    }
+
+    bail!(
+        "Gas estimate did not settle after {} rounds",
+        MAX_GAS_ESTIMATE_ROUNDS
+    )
+}
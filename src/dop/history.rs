@@ -0,0 +1,139 @@
+use crate::dop::{Chain, DopClient, EvmAddress};
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TransactionCategory {
+    Encrypt,
+    TransferIn,
+    TransferOut,
+    Decrypt,
+    /// The engine could not tell what the transaction did, e.g. a batch
+    /// mixing several kinds of outputs.
+    Unknown,
+}
+
+impl fmt::Display for TransactionCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Encrypt => "encrypt",
+            Self::TransferIn => "transfer-in",
+            Self::TransferOut => "transfer-out",
+            Self::Decrypt => "decrypt",
+            Self::Unknown => "unknown",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryTokenAmount {
+    #[serde(rename = "tokenAddress")]
+    pub token_address: EvmAddress,
+    pub amount: String,
+    /// Recipient of an outgoing amount, or the sender of an incoming one if
+    /// they chose to reveal it.
+    pub counterparty: Option<String>,
+    #[serde(rename = "memoText")]
+    pub memo_text: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionHistoryEntry {
+    pub txid: String,
+    #[serde(rename = "blockNumber")]
+    pub block_number: Option<u64>,
+    pub timestamp: Option<u64>,
+    pub category: TransactionCategory,
+    /// Amounts sent or received, not including change or the broadcaster fee.
+    #[serde(rename = "tokenAmounts")]
+    pub token_amounts: Vec<HistoryTokenAmount>,
+    #[serde(rename = "broadcasterFee")]
+    pub broadcaster_fee: Option<HistoryTokenAmount>,
+}
+
+/// Narrows down `transaction_history`. The default returns everything.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionHistoryFilter {
+    pub starting_block: Option<u64>,
+    /// Only these categories; empty means all.
+    pub categories: Vec<TransactionCategory>,
+    /// Only transactions moving this token.
+    pub token_address: Option<EvmAddress>,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransactionHistoryPage {
+    /// Number of entries matching the filter, across all pages.
+    pub total: usize,
+    /// Newest first.
+    pub items: Vec<TransactionHistoryEntry>,
+}
+
+impl TransactionHistoryFilter {
+    /// The filter for the page after `page`, or `None` if it was the last.
+    pub fn next_page(&self, page: &TransactionHistoryPage) -> Option<Self> {
+        let offset = self.offset + page.items.len();
+        if page.items.is_empty() || offset >= page.total {
+            return None;
+        }
+        Some(Self {
+            offset,
+            ..self.clone()
+        })
+    }
+
+    fn to_query(&self) -> Vec<(&'static str, String)> {
+        let mut query = vec![("offset", self.offset.to_string())];
+        if let Some(block) = self.starting_block {
+            query.push(("startingBlock", block.to_string()));
+        }
+        if !self.categories.is_empty() {
+            let categories: Vec<String> = self.categories.iter().map(|c| c.to_string()).collect();
+            query.push(("categories", categories.join(",")));
+        }
+        if let Some(token) = self.token_address {
+            query.push(("tokenAddress", token.to_string()));
+        }
+        if let Some(limit) = self.limit {
+            query.push(("limit", limit.to_string()));
+        }
+        query
+    }
+}
+
+impl DopClient {
+    /// What `wallet_id` has encrypted, sent, received and decrypted on
+    /// `chain`. The wallet must be loaded and scanned.
+    pub async fn transaction_history(
+        &self,
+        wallet_id: &str,
+        chain: Chain,
+        filter: &TransactionHistoryFilter,
+    ) -> Result<TransactionHistoryPage> {
+        let mut query = vec![("chain", serde_json::to_string(&chain)?)];
+        query.extend(filter.to_query());
+
+        let resp = self
+            .client
+            .get(format!(
+                "{}/wallet/{}/transaction-history",
+                self.base_url(),
+                wallet_id
+            ))
+            .query(&query)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("API call failed: {} (status {})", text, status));
+        }
+
+        Ok(resp.json::<TransactionHistoryPage>().await?)
+    }
+}
@@ -0,0 +1,262 @@
+use crate::dop::{
+    DopClient, EvmAddress, HistoryTokenAmount, TransactionCategory, TransactionHistoryEntry,
+    TransactionHistoryFilter,
+};
+use anyhow::{Result, anyhow, bail};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+use std::io::Write;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+const EXPORT_PAGE_SIZE: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    /// One JSON object per line, with the same fields as the CSV columns.
+    JsonLines,
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryExportOptions {
+    pub format: ExportFormat,
+    /// Inclusive start of the reported period.
+    pub from: Option<OffsetDateTime>,
+    /// Exclusive end of the reported period.
+    pub to: Option<OffsetDateTime>,
+    /// Known token decimals. Tokens missing here are looked up on-chain.
+    pub token_decimals: HashMap<EvmAddress, u8>,
+}
+
+impl HistoryExportOptions {
+    pub fn new(format: ExportFormat) -> Self {
+        Self {
+            format,
+            from: None,
+            to: None,
+            token_decimals: HashMap::new(),
+        }
+    }
+
+    pub fn period(mut self, from: OffsetDateTime, to: OffsetDateTime) -> Self {
+        self.from = Some(from);
+        self.to = Some(to);
+        self
+    }
+
+    pub fn token_decimals(mut self, token_address: EvmAddress, decimals: u8) -> Self {
+        self.token_decimals.insert(token_address, decimals);
+        self
+    }
+
+    /// Entries without a timestamp are only kept when no period is set.
+    fn includes(&self, entry: &TransactionHistoryEntry) -> bool {
+        if self.from.is_none() && self.to.is_none() {
+            return true;
+        }
+        let Some(time) = entry
+            .timestamp
+            .and_then(|t| OffsetDateTime::from_unix_timestamp(t as i64).ok())
+        else {
+            return false;
+        };
+        self.from.is_none_or(|from| time >= from) && self.to.is_none_or(|to| time < to)
+    }
+}
+
+/// One line of the report. Field names are the column names and are kept
+/// stable so spreadsheets and importers can rely on them.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryReportRow {
+    pub txid: String,
+    pub block_number: Option<u64>,
+    /// RFC 3339, UTC.
+    pub timestamp: Option<String>,
+    pub category: TransactionCategory,
+    /// `amount` for tokens moved, `broadcaster_fee` for the fee paid.
+    pub entry_type: &'static str,
+    pub token_address: EvmAddress,
+    pub decimals: u8,
+    /// `raw_amount` with the token decimals applied.
+    pub amount: String,
+    pub raw_amount: String,
+    pub counterparty: Option<String>,
+    pub memo: Option<String>,
+}
+
+/// Formats a base-unit integer as a decimal, e.g. `1500000` with 6
+/// decimals is `1.500000`.
+pub fn format_token_amount(raw_amount: &str, decimals: u8) -> Result<String> {
+    if raw_amount.is_empty() || !raw_amount.bytes().all(|b| b.is_ascii_digit()) {
+        bail!("Invalid token amount: {}", raw_amount);
+    }
+    if decimals == 0 {
+        return Ok(raw_amount.to_string());
+    }
+
+    let decimals = decimals as usize;
+    let digits = format!("{:0>width$}", raw_amount, width = decimals + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+    Ok(format!("{}.{}", whole, fraction))
+}
+
+/// Flattens history entries into report rows: one per token amount, then
+/// one for the broadcaster fee if there was one.
+pub fn history_report_rows(
+    entries: &[TransactionHistoryEntry],
+    token_decimals: &HashMap<EvmAddress, u8>,
+) -> Result<Vec<HistoryReportRow>> {
+    let mut rows = Vec::new();
+    for entry in entries {
+        let timestamp = entry
+            .timestamp
+            .map(|t| -> Result<String> {
+                Ok(OffsetDateTime::from_unix_timestamp(t as i64)?.format(&Rfc3339)?)
+            })
+            .transpose()?;
+
+        let amounts = entry.token_amounts.iter().map(|a| ("amount", a));
+        let fee = entry.broadcaster_fee.iter().map(|a| ("broadcaster_fee", a));
+        for (entry_type, amount) in amounts.chain(fee) {
+            let decimals = *token_decimals
+                .get(&amount.token_address)
+                .ok_or_else(|| anyhow!("Missing decimals for token {}", amount.token_address))?;
+            rows.push(HistoryReportRow {
+                txid: entry.txid.clone(),
+                block_number: entry.block_number,
+                timestamp: timestamp.clone(),
+                category: entry.category,
+                entry_type,
+                token_address: amount.token_address,
+                decimals,
+                amount: format_token_amount(&amount.amount, decimals)?,
+                raw_amount: amount.amount.clone(),
+                counterparty: amount.counterparty.clone(),
+                memo: amount.memo_text.clone(),
+            });
+        }
+    }
+    Ok(rows)
+}
+
+/// Writes report rows as CSV (with a header) or JSON Lines.
+pub fn write_history_report<W: Write>(
+    rows: &[HistoryReportRow],
+    format: ExportFormat,
+    mut writer: W,
+) -> Result<()> {
+    match format {
+        ExportFormat::Csv => {
+            let mut csv = csv::Writer::from_writer(writer);
+            for row in rows {
+                csv.serialize(row)?;
+            }
+            csv.flush()?;
+        }
+        ExportFormat::JsonLines => {
+            for row in rows {
+                serde_json::to_writer(&mut writer, row)?;
+                writer.write_all(b"\n")?;
+            }
+            writer.flush()?;
+        }
+    }
+    Ok(())
+}
+
+impl DopClient {
+    /// Writes the history of `wallet_id` on `network_name` for the period in
+    /// `options`, oldest first. Decimals of tokens not given in `options`
+    /// are read on-chain. Returns the number of rows written.
+    pub async fn export_transaction_history<W: Write>(
+        &self,
+        wallet_id: &str,
+        network_name: &str,
+        options: &HistoryExportOptions,
+        writer: W,
+    ) -> Result<usize> {
+        let chain = self.network_chain(network_name).await?;
+        let mut entries = Vec::new();
+        let mut filter = TransactionHistoryFilter {
+            limit: Some(EXPORT_PAGE_SIZE),
+            ..Default::default()
+        };
+        loop {
+            let page = self.transaction_history(wallet_id, chain, &filter).await?;
+            let next = filter.next_page(&page);
+            entries.extend(page.items.into_iter().filter(|e| options.includes(e)));
+            match next {
+                Some(next) => filter = next,
+                None => break,
+            }
+        }
+        entries.reverse();
+
+        let mut token_decimals = options.token_decimals.clone();
+        let missing: BTreeSet<EvmAddress> = entries
+            .iter()
+            .flat_map(|e| e.token_amounts.iter().chain(&e.broadcaster_fee))
+            .map(|a: &HistoryTokenAmount| a.token_address)
+            .filter(|token| !token_decimals.contains_key(token))
+            .collect();
+        for token in missing {
+            let decimals = self.token_decimals(network_name, token).await?;
+            token_decimals.insert(token, decimals);
+        }
+
+        let rows = history_report_rows(&entries, &token_decimals)?;
+        write_history_report(&rows, options.format, writer)?;
+        Ok(rows.len())
+    }
+
+    /// Same report for auditors holding only a shareable viewing key: loads
+    /// a temporary view-only wallet, scans `network_name` for it and exports
+    /// its history. The wallet is deleted again whether or not the export
+    /// succeeds; to export repeatedly, create the view-only wallet once and
+    /// use `export_transaction_history`.
+    ///
+    /// View-only wallet IDs are derived from the viewing key, so if a wallet
+    /// for this key is already stored it is reused and left in place.
+    pub async fn export_view_only_transaction_history<W: Write>(
+        &self,
+        shareable_viewing_key: &str,
+        encryption_key: &str,
+        creation_block_numbers: Option<HashMap<&str, u64>>,
+        network_name: &str,
+        options: &HistoryExportOptions,
+        writer: W,
+    ) -> Result<usize> {
+        let chain = self.network_chain(network_name).await?;
+        let stored = self.list_stored_wallet_ids().await?;
+        let wallet = self
+            .create_view_only_wallet(
+                encryption_key,
+                shareable_viewing_key,
+                creation_block_numbers,
+            )
+            .await?;
+        let wallet_id = wallet
+            .get("id")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing id in view-only wallet response"))?
+            .to_string();
+
+        let exported = async {
+            self.refresh_balances(serde_json::to_value(chain)?, Some(vec![wallet_id.clone()]))
+                .await?;
+            self.export_transaction_history(&wallet_id, network_name, options, writer)
+                .await
+        }
+        .await;
+        let deleted = if stored.contains(&wallet_id) {
+            Ok(())
+        } else {
+            self.delete_wallet_by_id(&wallet_id).await
+        };
+
+        // An export error matters more than a failed cleanup.
+        let rows = exported?;
+        deleted?;
+        Ok(rows)
+    }
+}
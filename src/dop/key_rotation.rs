@@ -0,0 +1,153 @@
+use crate::dop::DopClient;
+use anyhow::{Result, anyhow};
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyRotationStatus {
+    /// The wallet is now stored under the new key.
+    Rotated,
+    /// Dry run only: the old key decrypts the wallet, nothing was written.
+    Verified,
+    Failed(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyRotationProgress {
+    pub wallet_id: String,
+    pub status: KeyRotationStatus,
+    pub completed: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct KeyRotationReport {
+    pub dry_run: bool,
+    pub results: Vec<(String, KeyRotationStatus)>,
+}
+
+impl KeyRotationReport {
+    pub fn failed(&self) -> impl Iterator<Item = &(String, KeyRotationStatus)> {
+        self.results
+            .iter()
+            .filter(|(_, status)| matches!(status, KeyRotationStatus::Failed(_)))
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.failed().next().is_none()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StoredWalletsResponse {
+    #[serde(rename = "walletIds")]
+    wallet_ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RotateEncryptionKeyResponse {
+    rotated: bool,
+}
+
+impl DopClient {
+    pub async fn rotate_encryption_key(
+        &self,
+        wallet_id: &str,
+        old_key: &str,
+        new_key: &str,
+    ) -> Result<()> {
+        self.send_rotate_encryption_key(wallet_id, old_key, new_key, false)
+            .await?;
+        Ok(())
+    }
+
+    /// Rotates every wallet stored in the engine database, whether or not it
+    /// is loaded. Wallets that fail (for instance because they use a
+    /// different key) are reported and skipped; the others are still rotated.
+    pub async fn rotate_encryption_key_for_all_wallets<F>(
+        &self,
+        old_key: &str,
+        new_key: &str,
+        dry_run: bool,
+        on_progress: Option<F>,
+    ) -> Result<KeyRotationReport>
+    where
+        F: Fn(KeyRotationProgress),
+    {
+        let wallet_ids = self.list_stored_wallet_ids().await?;
+
+        let total = wallet_ids.len();
+        let mut results = Vec::with_capacity(total);
+
+        for (index, wallet_id) in wallet_ids.into_iter().enumerate() {
+            let status = match self
+                .send_rotate_encryption_key(&wallet_id, old_key, new_key, dry_run)
+                .await
+            {
+                Ok(true) => KeyRotationStatus::Rotated,
+                Ok(false) => KeyRotationStatus::Verified,
+                Err(err) => KeyRotationStatus::Failed(err.to_string()),
+            };
+
+            if let Some(cb) = on_progress.as_ref() {
+                cb(KeyRotationProgress {
+                    wallet_id: wallet_id.clone(),
+                    status: status.clone(),
+                    completed: index + 1,
+                    total,
+                });
+            }
+            results.push((wallet_id, status));
+        }
+
+        Ok(KeyRotationReport { dry_run, results })
+    }
+
+    /// IDs of all wallet records in the engine database, including wallets
+    /// that are not loaded in this session.
+    pub async fn list_stored_wallet_ids(&self) -> Result<Vec<String>> {
+        let resp = self
+            .client
+            .get(format!("{}/wallets/stored", self.base_url()))
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("API call failed: {} (status {})", text, status));
+        }
+
+        Ok(resp.json::<StoredWalletsResponse>().await?.wallet_ids)
+    }
+
+    async fn send_rotate_encryption_key(
+        &self,
+        wallet_id: &str,
+        old_key: &str,
+        new_key: &str,
+        dry_run: bool,
+    ) -> Result<bool> {
+        let payload = json!({
+            "dopWalletID": wallet_id,
+            "oldEncryptionKey": old_key,
+            "newEncryptionKey": new_key,
+            "dryRun": dry_run,
+        });
+
+        let resp = self
+            .client
+            .post(format!("{}/wallet/rotate-encryption-key", self.base_url()))
+            .json(&payload)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("API call failed: {} (status {})", text, status));
+        }
+
+        Ok(resp.json::<RotateEncryptionKeyResponse>().await?.rotated)
+    }
+}
@@ -0,0 +1,82 @@
+use crate::dop::{Chain, DopClient, EvmAddress};
+use anyhow::{Result, anyhow, bail};
+use serde::Deserialize;
+
+/// Largest encoded memo the engine's transfer routes accept. Mirrors
+/// `MAX_MEMO_ENCODED_BYTES` in ts-lib/src/core/memo.ts.
+pub const MAX_MEMO_ENCODED_BYTES: usize = 1024;
+
+/// Size of `memo_text` as the engine encodes it. The engine stores memo text
+/// as its UTF-8 bytes.
+pub fn encoded_memo_len(memo_text: &str) -> usize {
+    memo_text.len()
+}
+
+/// Checks a memo before any gas estimate or proof is requested for it.
+pub fn validate_memo_text(memo_text: &str) -> Result<()> {
+    let size = encoded_memo_len(memo_text);
+    if size > MAX_MEMO_ENCODED_BYTES {
+        bail!(
+            "Memo text encodes to {} bytes, the maximum is {}",
+            size,
+            MAX_MEMO_ENCODED_BYTES
+        );
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_optional_memo_text(memo_text: Option<&str>) -> Result<()> {
+    memo_text.map_or(Ok(()), validate_memo_text)
+}
+
+/// An incoming ERC20 note together with its decrypted memo.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReceivedMemo {
+    pub txid: String,
+    #[serde(rename = "blockNumber")]
+    pub block_number: Option<u64>,
+    pub timestamp: Option<u64>,
+    #[serde(rename = "tokenAddress")]
+    pub token_address: EvmAddress,
+    pub amount: String,
+    #[serde(rename = "memoText")]
+    pub memo_text: Option<String>,
+    /// Only set when the sender chose to show their address to recipients.
+    #[serde(rename = "senderAddress")]
+    pub sender_address: Option<String>,
+}
+
+impl DopClient {
+    /// Memos of the notes received by `wallet_id` on `chain`, optionally
+    /// starting at `starting_block`. The wallet must be loaded and scanned.
+    pub async fn get_received_memos(
+        &self,
+        wallet_id: &str,
+        chain: Chain,
+        starting_block: Option<u64>,
+    ) -> Result<Vec<ReceivedMemo>> {
+        let mut query = vec![("chain", serde_json::to_string(&chain)?)];
+        if let Some(block) = starting_block {
+            query.push(("startingBlock", block.to_string()));
+        }
+
+        let resp = self
+            .client
+            .get(format!(
+                "{}/wallet/{}/received-memos",
+                self.base_url(),
+                wallet_id
+            ))
+            .query(&query)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("API call failed: {} (status {})", text, status));
+        }
+
+        Ok(resp.json::<Vec<ReceivedMemo>>().await?)
+    }
+}
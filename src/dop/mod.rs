@@ -1,18 +1,54 @@
+mod address;
+mod backup;
 mod balances;
+mod broadcaster;
 mod callback;
 mod client;
+mod decrypt;
+mod encrypt;
 mod engine;
+mod evm_address;
+mod fee_token;
+mod gas_details;
+mod gas_oracle;
+mod history;
+mod history_export;
+mod key_rotation;
+mod memo;
+mod poi;
+mod private_transfer;
+mod proof_job;
+mod provider;
+mod recipients;
+mod signature;
+#[cfg(feature = "signer")]
+mod signer;
 mod tx_decrypt;
 mod tx_encrypt;
 mod tx_transfer;
 mod utils;
+mod utxos;
 mod wallet;
-pub use balances::*;
-pub use callback::*;
+pub use address::*;
+pub use backup::*;
+pub use broadcaster::*;
 pub use client::*;
-pub use engine::*;
-pub use tx_decrypt::*;
+pub use decrypt::*;
+pub use encrypt::*;
+pub use evm_address::*;
+pub use fee_token::*;
+pub use gas_oracle::*;
+pub use history::*;
+pub use history_export::*;
+pub use key_rotation::*;
+pub use memo::*;
+pub use poi::*;
+pub use private_transfer::*;
+pub use proof_job::*;
+pub use provider::*;
+pub use recipients::*;
+pub use signature::*;
+#[cfg(feature = "signer")]
+pub use signer::*;
 pub use tx_encrypt::*;
-pub use tx_transfer::*;
-pub use utils::*;
-pub use wallet::*;
+pub use utxos::*;
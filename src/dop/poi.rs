@@ -0,0 +1,169 @@
+use crate::dop::{DEFAULT_TXID_VERSION, DopClient};
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{collections::BTreeMap, fmt};
+
+/// Proof of Innocence status of a note on one list. Variants are ordered
+/// from best to worst, so the status of a note is the maximum over its
+/// lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum PoiStatus {
+    Valid,
+    ProofSubmitted,
+    Missing,
+    ShieldBlocked,
+}
+
+impl fmt::Display for PoiStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PoiStatus::Valid => "Valid",
+            PoiStatus::ProofSubmitted => "ProofSubmitted",
+            PoiStatus::Missing => "Missing",
+            PoiStatus::ShieldBlocked => "ShieldBlocked",
+        })
+    }
+}
+
+/// POI status of a note the wallet has received.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommitmentPoiStatus {
+    pub tree: u32,
+    pub position: u64,
+    pub txid: String,
+    pub commitment: String,
+    #[serde(rename = "blindedCommitment")]
+    pub blinded_commitment: String,
+    /// Status per POI list key. `None` until the engine has asked a POI node.
+    #[serde(rename = "poisPerList")]
+    pub pois_per_list: Option<BTreeMap<String, PoiStatus>>,
+}
+
+impl CommitmentPoiStatus {
+    /// The worst status over all lists; `Missing` if there are none yet.
+    pub fn status(&self) -> PoiStatus {
+        self.pois_per_list
+            .as_ref()
+            .and_then(|lists| lists.values().max().copied())
+            .unwrap_or(PoiStatus::Missing)
+    }
+
+    /// Notes can only be spent privately once every list has a valid POI.
+    pub fn is_spendable(&self) -> bool {
+        self.status() == PoiStatus::Valid
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PendingPoiResponse {
+    txids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PoiRequiredResponse {
+    required: bool,
+}
+
+fn same_txid(a: &str, b: &str) -> bool {
+    let strip = |txid: &str| txid.trim_start_matches("0x").to_ascii_lowercase();
+    strip(a) == strip(b)
+}
+
+impl DopClient {
+    /// Whether private spends on `network_name` need POIs.
+    pub async fn poi_required(&self, network_name: &str) -> Result<bool> {
+        let resp = self
+            .client
+            .get(format!("{}/poi/required", self.base_url()))
+            .query(&[("networkName", network_name)])
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("API call failed: {} (status {})", text, status));
+        }
+
+        Ok(resp.json::<PoiRequiredResponse>().await?.required)
+    }
+
+    /// POI status of every note the wallet has received. Notes that are not
+    /// `is_spendable` explain a private balance that cannot be spent yet.
+    pub async fn received_poi_statuses(
+        &self,
+        network_name: &str,
+        wallet_id: &str,
+    ) -> Result<Vec<CommitmentPoiStatus>> {
+        Ok(self
+            .post_poi("/poi/received-statuses", network_name, wallet_id)
+            .await?
+            .json()
+            .await?)
+    }
+
+    /// POI status of the notes the wallet received in transaction `txid`.
+    pub async fn transaction_poi_statuses(
+        &self,
+        network_name: &str,
+        wallet_id: &str,
+        txid: &str,
+    ) -> Result<Vec<CommitmentPoiStatus>> {
+        let mut statuses = self.received_poi_statuses(network_name, wallet_id).await?;
+        statuses.retain(|s| same_txid(&s.txid, txid));
+        Ok(statuses)
+    }
+
+    /// Transactions sent from the wallet that still need POI proofs.
+    pub async fn transactions_pending_poi(
+        &self,
+        network_name: &str,
+        wallet_id: &str,
+    ) -> Result<Vec<String>> {
+        let resp = self
+            .post_poi("/poi/pending-transactions", network_name, wallet_id)
+            .await?;
+        Ok(resp.json::<PendingPoiResponse>().await?.txids)
+    }
+
+    /// Generates and submits POI proofs for the wallet's pending
+    /// transactions.
+    pub async fn generate_pois(&self, network_name: &str, wallet_id: &str) -> Result<()> {
+        self.post_poi("/poi/generate", network_name, wallet_id)
+            .await?;
+        Ok(())
+    }
+
+    /// Asks the POI node again for the status of the wallet's received notes.
+    pub async fn refresh_received_pois(&self, network_name: &str, wallet_id: &str) -> Result<()> {
+        self.post_poi("/poi/refresh-received", network_name, wallet_id)
+            .await?;
+        Ok(())
+    }
+
+    async fn post_poi(
+        &self,
+        endpoint: &str,
+        network_name: &str,
+        wallet_id: &str,
+    ) -> Result<reqwest::Response> {
+        let resp = self
+            .client
+            .post(format!("{}{}", self.base_url(), endpoint))
+            .json(&json!({
+                "txidVersion": DEFAULT_TXID_VERSION,
+                "networkName": network_name,
+                "dopWalletID": wallet_id,
+            }))
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("API call failed: {} (status {})", text, status));
+        }
+        Ok(resp)
+    }
+}
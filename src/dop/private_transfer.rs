@@ -0,0 +1,267 @@
+use crate::dop::{
+    ContractTransaction, DopClient, DopERC20AmountRecipient, DopNFTAmountRecipient,
+    DopPopulateTransactionResponse, DopTransactionGasEstimateResponse, FeeTokenDetails,
+    FeeTokenSelector, ProvedTransaction, TransactionGasDetails, UtxoSelection,
+    gas_oracle::settle_gas_and_fee,
+    tx_transfer::{transfer_gas_estimate_payload, transfer_proof_payload},
+};
+use anyhow::{Result, bail};
+use std::future::Future;
+
+pub const DEFAULT_TXID_VERSION: &str = "V2_PoseidonMerkle";
+
+/// Supplies gas prices for `PrivateTransfer::transfer`.
+pub trait GasFeeOracle {
+    /// Returns gas details on `network_name` for a transaction using
+    /// `gas_estimate` gas.
+    fn gas_details(
+        &self,
+        network_name: &str,
+        gas_estimate: &str,
+    ) -> impl Future<Output = Result<TransactionGasDetails>> + Send;
+}
+
+/// Fixed prices: the gas estimate is replaced and everything else is kept.
+impl GasFeeOracle for TransactionGasDetails {
+    async fn gas_details(
+        &self,
+        _network_name: &str,
+        gas_estimate: &str,
+    ) -> Result<TransactionGasDetails> {
+        Ok(self.clone().with_gas_estimate(gas_estimate))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PrivateTransferResult {
+    /// Unsigned transaction, ready to sign and send.
+    pub transaction: ContractTransaction,
+    pub nullifiers: Vec<String>,
+    pub gas_details: TransactionGasDetails,
+    pub broadcaster_fee: Option<DopERC20AmountRecipient>,
+}
+
+/// A private transfer whose parameters are fixed once built, so the gas
+/// estimate, proof and populated transaction all describe the same transfer.
+#[derive(Clone)]
+pub struct PrivateTransfer<'a> {
+    client: &'a DopClient,
+    txid_version: String,
+    network_name: String,
+    wallet_id: String,
+    encryption_key: String,
+    erc20_recipients: Vec<DopERC20AmountRecipient>,
+    nft_recipients: Vec<DopNFTAmountRecipient>,
+    memo_text: Option<String>,
+    show_sender_address_to_recipient: bool,
+    broadcaster_fee_recipient: Option<DopERC20AmountRecipient>,
+    fee_budget: Option<FeeTokenSelector>,
+    send_with_public_wallet: bool,
+    overall_batch_min_gas_price: Option<String>,
+    utxo_selection: UtxoSelection,
+    proved: Option<ProvedTransaction>,
+}
+
+impl DopClient {
+    pub fn private_transfer(
+        &self,
+        network_name: &str,
+        wallet_id: &str,
+        encryption_key: &str,
+    ) -> PrivateTransfer<'_> {
+        PrivateTransfer {
+            client: self,
+            txid_version: DEFAULT_TXID_VERSION.to_string(),
+            network_name: network_name.to_string(),
+            wallet_id: wallet_id.to_string(),
+            encryption_key: encryption_key.to_string(),
+            erc20_recipients: Vec::new(),
+            nft_recipients: Vec::new(),
+            memo_text: None,
+            show_sender_address_to_recipient: false,
+            broadcaster_fee_recipient: None,
+            fee_budget: None,
+            send_with_public_wallet: false,
+            overall_batch_min_gas_price: None,
+            utxo_selection: UtxoSelection::default(),
+            proved: None,
+        }
+    }
+}
+
+impl PrivateTransfer<'_> {
+    pub fn txid_version(mut self, txid_version: &str) -> Self {
+        self.txid_version = txid_version.to_string();
+        self
+    }
+
+    pub fn erc20_recipient(mut self, recipient: DopERC20AmountRecipient) -> Self {
+        self.erc20_recipients.push(recipient);
+        self
+    }
+
+    pub fn erc20_recipients(mut self, recipients: Vec<DopERC20AmountRecipient>) -> Self {
+        self.erc20_recipients.extend(recipients);
+        self
+    }
+
+    pub fn nft_recipients(mut self, recipients: Vec<DopNFTAmountRecipient>) -> Self {
+        self.nft_recipients.extend(recipients);
+        self
+    }
+
+    pub fn memo_text(mut self, memo_text: &str) -> Self {
+        self.memo_text = Some(memo_text.to_string());
+        self
+    }
+
+    pub fn show_sender_address_to_recipient(mut self, show: bool) -> Self {
+        self.show_sender_address_to_recipient = show;
+        self
+    }
+
+    pub fn broadcaster_fee(mut self, recipient: DopERC20AmountRecipient) -> Self {
+        self.broadcaster_fee_recipient = Some(recipient);
+        self
+    }
+
+    /// Balances the settled broadcaster fee must fit in, checked before
+    /// proving. Pass the selector that chose the fee token.
+    pub fn fee_budget(mut self, selector: FeeTokenSelector) -> Self {
+        self.fee_budget = Some(selector);
+        self
+    }
+
+    pub fn send_with_public_wallet(mut self, public: bool) -> Self {
+        self.send_with_public_wallet = public;
+        self
+    }
+
+    pub fn overall_batch_min_gas_price(mut self, min_gas_price: &str) -> Self {
+        self.overall_batch_min_gas_price = Some(min_gas_price.to_string());
+        self
+    }
+
+    /// Limits the notes the transfer may spend. Applies to both the gas
+    /// estimate and the proof.
+    pub fn utxos(mut self, selection: UtxoSelection) -> Self {
+        self.utxo_selection = selection;
+        self
+    }
+
+    pub fn is_proved(&self) -> bool {
+        self.proved.is_some()
+    }
+
+    pub fn proved_transaction(&self) -> Option<&ProvedTransaction> {
+        self.proved.as_ref()
+    }
+
+    pub async fn estimate_gas(
+        &self,
+        original_gas_details: TransactionGasDetails,
+        fee_token_details: Option<FeeTokenDetails>,
+    ) -> Result<DopTransactionGasEstimateResponse> {
+        let mut payload = transfer_gas_estimate_payload(
+            self.txid_version.clone(),
+            self.network_name.clone(),
+            self.wallet_id.clone(),
+            self.encryption_key.clone(),
+            self.memo_text.clone(),
+            self.erc20_recipients.clone(),
+            self.nft_recipients.clone(),
+            original_gas_details,
+            fee_token_details,
+            self.send_with_public_wallet,
+        )?;
+        self.utxo_selection.apply(&mut payload)?;
+        self.client.send_transfer_gas_estimate(payload).await
+    }
+
+    pub async fn prove(&mut self) -> Result<ProvedTransaction> {
+        self.proved = None;
+        let mut payload = transfer_proof_payload(
+            self.txid_version.clone(),
+            self.network_name.clone(),
+            self.wallet_id.clone(),
+            self.encryption_key.clone(),
+            self.show_sender_address_to_recipient,
+            self.memo_text.clone(),
+            self.erc20_recipients.clone(),
+            self.nft_recipients.clone(),
+            self.broadcaster_fee_recipient.clone(),
+            self.send_with_public_wallet,
+            self.overall_batch_min_gas_price.clone(),
+        )?;
+        self.utxo_selection.apply(&mut payload)?;
+        let proved = self
+            .client
+            .run_proof_job("/generate-transfer-proof", payload)
+            .await?;
+        self.proved = Some(proved.clone());
+        Ok(proved)
+    }
+
+    pub async fn populate(
+        &self,
+        gas_details: TransactionGasDetails,
+    ) -> Result<DopPopulateTransactionResponse> {
+        let Some(proved) = self.proved.as_ref() else {
+            bail!("Transfer must be proved before it can be populated");
+        };
+        self.client
+            .populate_proved_transfer(
+                proved,
+                self.txid_version.clone(),
+                self.network_name.clone(),
+                self.wallet_id.clone(),
+                self.show_sender_address_to_recipient,
+                self.memo_text.clone(),
+                self.erc20_recipients.clone(),
+                self.nft_recipients.clone(),
+                self.broadcaster_fee_recipient.clone(),
+                self.send_with_public_wallet,
+                self.overall_batch_min_gas_price.clone(),
+                gas_details,
+            )
+            .await
+    }
+
+    /// Runs estimate, prove and populate in one go. Gas details come from
+    /// `oracle`; if `broadcaster_fee` is given, the fee is recomputed from
+    /// each new estimate and the transfer re-estimated until the gas
+    /// estimate no longer changes. Fails without proving if it never does,
+    /// or if the settled fee exceeds the `fee_budget`.
+    pub async fn transfer<O, F>(
+        mut self,
+        oracle: &O,
+        broadcaster_fee: Option<F>,
+    ) -> Result<PrivateTransferResult>
+    where
+        O: GasFeeOracle,
+        F: Fn(&TransactionGasDetails) -> Result<DopERC20AmountRecipient>,
+    {
+        let settled = settle_gas_and_fee(
+            &self.network_name,
+            oracle,
+            broadcaster_fee.as_ref(),
+            self.fee_budget.as_ref(),
+            |gas_details, fee_token_details| self.estimate_gas(gas_details, fee_token_details),
+        )
+        .await?;
+        let gas_details = settled.gas_details;
+        if let Some(fee) = settled.broadcaster_fee {
+            self.broadcaster_fee_recipient = Some(fee);
+        }
+
+        let proved = self.prove().await?;
+        let populated = self.populate(gas_details.clone()).await?;
+
+        Ok(PrivateTransferResult {
+            transaction: populated.transaction,
+            nullifiers: populated.nullifiers.unwrap_or(proved.nullifiers),
+            gas_details,
+            broadcaster_fee: self.broadcaster_fee_recipient,
+        })
+    }
+}
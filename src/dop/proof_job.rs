@@ -0,0 +1,147 @@
+use crate::dop::DopClient;
+use anyhow::{Result, anyhow};
+use chacha20poly1305::aead::{OsRng, rand_core::RngCore};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex},
+};
+use time::OffsetDateTime;
+
+pub(crate) type ActiveProofJobs = Arc<Mutex<HashSet<String>>>;
+
+/// Handle to a generated proof. The matching `populate_proved_*` call must
+/// be given this handle; the engine rejects it if a newer proof has replaced
+/// it, the parameters differ from the proved ones, or the proof is stale.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvedTransaction {
+    pub id: String,
+    #[serde(rename = "parametersHash")]
+    pub parameters_hash: String,
+    pub nullifiers: Vec<String>,
+    #[serde(rename = "createdAt", with = "time::serde::timestamp::milliseconds")]
+    pub created_at: OffsetDateTime,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProofResponse {
+    #[serde(rename = "provedTransaction")]
+    proved_transaction: ProvedTransaction,
+}
+
+#[derive(Debug, Deserialize)]
+struct CancelProofResponse {
+    cancelled: bool,
+}
+
+/// Cancels the engine-side proof if the request future is dropped before the
+/// engine has answered.
+struct ProofJobGuard {
+    client: Client,
+    base_url: String,
+    job_id: String,
+    active_jobs: ActiveProofJobs,
+    finished: bool,
+}
+
+impl Drop for ProofJobGuard {
+    fn drop(&mut self) {
+        self.active_jobs.lock().unwrap().remove(&self.job_id);
+        if self.finished {
+            return;
+        }
+
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let client = self.client.clone();
+        let base_url = self.base_url.clone();
+        let job_id = self.job_id.clone();
+        handle.spawn(async move {
+            if let Err(e) = send_cancel_proof(&client, &base_url, &job_id).await {
+                eprintln!("Failed to cancel proof job {}: {}", job_id, e);
+            }
+        });
+    }
+}
+
+async fn send_cancel_proof(client: &Client, base_url: &str, job_id: &str) -> Result<bool> {
+    let resp = client
+        .post(format!("{}/cancel-proof", base_url))
+        .json(&json!({ "proofJobId": job_id }))
+        .send()
+        .await?;
+
+    let status = resp.status();
+    if !status.is_success() {
+        let text = resp.text().await.unwrap_or_default();
+        return Err(anyhow!("API call failed: {} (status {})", text, status));
+    }
+
+    Ok(resp.json::<CancelProofResponse>().await?.cancelled)
+}
+
+impl DopClient {
+    /// IDs of proofs this client is currently waiting on. Progress events
+    /// carry the same ID.
+    pub fn active_proof_jobs(&self) -> Vec<String> {
+        self.active_proof_jobs
+            .lock()
+            .unwrap()
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Cancels a running proof, or invalidates a finished one so that no
+    /// `populate_proved_*` call can use it. Returns `false` if the engine
+    /// does not know the job.
+    pub async fn cancel_proof(&self, job_id: &str) -> Result<bool> {
+        send_cancel_proof(&self.client, &self.base_url(), job_id).await
+    }
+
+    /// Posts a proof request under a fresh job ID. Dropping the returned
+    /// future before it completes cancels the job in the engine.
+    pub(crate) async fn run_proof_job(
+        &self,
+        endpoint: &str,
+        mut payload: Value,
+    ) -> Result<ProvedTransaction> {
+        let mut id = [0u8; 16];
+        OsRng.fill_bytes(&mut id);
+        let job_id = hex::encode(id);
+        payload["proofJobId"] = json!(job_id);
+
+        self.active_proof_jobs
+            .lock()
+            .unwrap()
+            .insert(job_id.clone());
+        let mut guard = ProofJobGuard {
+            client: self.client.clone(),
+            base_url: self.base_url(),
+            job_id,
+            active_jobs: self.active_proof_jobs.clone(),
+            finished: false,
+        };
+
+        let resp = self
+            .client
+            .post(format!("{}{}", self.base_url(), endpoint))
+            .json(&payload)
+            .send()
+            .await;
+        // The engine has answered, so there is nothing left to cancel.
+        guard.finished = resp.is_ok();
+        let resp = resp?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("API call failed: {} (status {})", text, status));
+        }
+
+        Ok(resp.json::<ProofResponse>().await?.proved_transaction)
+    }
+}
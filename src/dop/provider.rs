@@ -0,0 +1,137 @@
+use crate::dop::{DopClient, EvmAddress};
+use anyhow::{Result, anyhow, bail};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransactionReceipt {
+    #[serde(rename = "transactionHash")]
+    pub transaction_hash: String,
+    #[serde(rename = "blockNumber")]
+    pub block_number: u64,
+    #[serde(rename = "blockHash")]
+    pub block_hash: String,
+    pub from: EvmAddress,
+    pub to: Option<EvmAddress>,
+    #[serde(rename = "contractAddress")]
+    pub contract_address: Option<EvmAddress>,
+    /// 1 on success, 0 if the transaction reverted. `None` for
+    /// pre-Byzantium receipts.
+    pub status: Option<u8>,
+    #[serde(rename = "gasUsed")]
+    pub gas_used: String,
+    #[serde(rename = "effectiveGasPrice")]
+    pub effective_gas_price: String,
+    pub confirmations: u64,
+}
+
+impl TransactionReceipt {
+    pub fn succeeded(&self) -> bool {
+        self.status == Some(1)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SendTransactionResponse {
+    #[serde(rename = "txHash")]
+    tx_hash: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TransactionCountResponse {
+    nonce: u64,
+}
+
+impl DopClient {
+    /// Broadcasts a signed transaction through the provider loaded for
+    /// `network_name` and returns its hash.
+    pub async fn send_raw_transaction(
+        &self,
+        network_name: &str,
+        signed_transaction: &[u8],
+    ) -> Result<String> {
+        let payload = json!({
+            "networkName": network_name,
+            "signedTransaction": format!("0x{}", hex::encode(signed_transaction)),
+        });
+
+        let resp = self
+            .client
+            .post(format!("{}/send-raw-transaction", self.base_url()))
+            .json(&payload)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("API call failed: {} (status {})", text, status));
+        }
+
+        Ok(resp.json::<SendTransactionResponse>().await?.tx_hash)
+    }
+
+    /// Next nonce for `address`, counting pending transactions.
+    pub async fn transaction_count(&self, network_name: &str, address: EvmAddress) -> Result<u64> {
+        let resp = self
+            .client
+            .get(format!("{}/transaction-count", self.base_url()))
+            .query(&[
+                ("networkName", network_name.to_string()),
+                ("address", address.to_string()),
+            ])
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("API call failed: {} (status {})", text, status));
+        }
+
+        Ok(resp.json::<TransactionCountResponse>().await?.nonce)
+    }
+
+    /// Waits until `tx_hash` has `confirmations` confirmations and returns
+    /// its receipt. A reverted transaction still returns its receipt; check
+    /// `succeeded`.
+    pub async fn wait_for_confirmation(
+        &self,
+        network_name: &str,
+        tx_hash: &str,
+        confirmations: u32,
+        timeout: Duration,
+    ) -> Result<TransactionReceipt> {
+        let payload = json!({
+            "networkName": network_name,
+            "txHash": tx_hash,
+            "confirmations": confirmations,
+            "timeoutMs": timeout.as_millis() as u64,
+        });
+
+        let resp = self
+            .client
+            .post(format!("{}/wait-for-confirmation", self.base_url()))
+            .json(&payload)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if status == StatusCode::REQUEST_TIMEOUT {
+            bail!(
+                "Timed out after {:?} waiting for {} confirmations of {}",
+                timeout,
+                confirmations,
+                tx_hash
+            );
+        }
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("API call failed: {} (status {})", text, status));
+        }
+
+        Ok(resp.json::<TransactionReceipt>().await?)
+    }
+}
@@ -0,0 +1,238 @@
+use crate::dop::{Chain, DopAddress, DopClient, DopERC20AmountRecipient, EvmAddress};
+use anyhow::{Result, anyhow, bail};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Output limits of the engine's transaction circuits, as reported by
+/// `DopClient::circuit_limits`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct CircuitLimits {
+    /// Outputs one transaction can create, change notes included.
+    #[serde(rename = "maxOutputs")]
+    pub max_outputs: usize,
+    /// Decrypt outputs one transaction can carry for the same token.
+    #[serde(rename = "maxDecryptOutputsPerToken")]
+    pub max_decrypt_outputs_per_token: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecipientKind {
+    /// Private transfer: recipients are `0zk` addresses.
+    Transfer,
+    /// Decrypt: recipients are public EVM addresses.
+    Decrypt,
+}
+
+/// ERC20 recipients for one or more private transactions.
+///
+/// Amounts sent to the same recipient in the same token are merged, and
+/// every entry is checked before anything reaches the engine. Amounts are
+/// base-unit integers and must fit in a `u128`.
+#[derive(Debug, Clone)]
+pub struct RecipientSet {
+    kind: RecipientKind,
+    chain: Option<Chain>,
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    token_address: EvmAddress,
+    recipient_address: String,
+    amount: u128,
+}
+
+impl RecipientSet {
+    pub fn for_transfer() -> Self {
+        Self::new(RecipientKind::Transfer)
+    }
+
+    pub fn for_decrypt() -> Self {
+        Self::new(RecipientKind::Decrypt)
+    }
+
+    fn new(kind: RecipientKind) -> Self {
+        Self {
+            kind,
+            chain: None,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Rejects `0zk` addresses bound to a different chain. Without this,
+    /// the first chain-specific address fixes the chain for the set.
+    pub fn with_chain(mut self, chain: Chain) -> Self {
+        self.chain = Some(chain);
+        self
+    }
+
+    pub fn from_recipients(
+        kind: RecipientKind,
+        recipients: impl IntoIterator<Item = DopERC20AmountRecipient>,
+    ) -> Result<Self> {
+        let mut set = Self::new(kind);
+        for recipient in recipients {
+            set.add_recipient(recipient)?;
+        }
+        Ok(set)
+    }
+
+    pub fn kind(&self) -> RecipientKind {
+        self.kind
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn add(&mut self, token_address: EvmAddress, amount: &str, recipient: &str) -> Result<()> {
+        let amount = parse_amount(amount)?;
+        let recipient_address = self.normalize_recipient(recipient)?;
+
+        if let Some(entry) = self
+            .entries
+            .iter_mut()
+            .find(|e| e.token_address == token_address && e.recipient_address == recipient_address)
+        {
+            entry.amount = entry
+                .amount
+                .checked_add(amount)
+                .ok_or_else(|| anyhow!("Total amount for {} overflows", recipient))?;
+            return Ok(());
+        }
+
+        self.entries.push(Entry {
+            token_address,
+            recipient_address,
+            amount,
+        });
+        Ok(())
+    }
+
+    pub fn add_recipient(&mut self, recipient: DopERC20AmountRecipient) -> Result<()> {
+        self.add(
+            recipient.token_address,
+            &recipient.amount,
+            &recipient.recipient_address,
+        )
+    }
+
+    /// All recipients in one list, in the order they were first added.
+    pub fn recipients(&self) -> Vec<DopERC20AmountRecipient> {
+        self.entries.iter().map(Entry::to_recipient).collect()
+    }
+
+    /// Splits the recipients into batches that each fit in one transaction,
+    /// counting one output per recipient plus one change output per token.
+    /// Decrypt batches also hold at most
+    /// `limits.max_decrypt_outputs_per_token` recipients of each token.
+    pub fn into_batches(self, limits: &CircuitLimits) -> Result<Vec<Vec<DopERC20AmountRecipient>>> {
+        if limits.max_outputs < 2 {
+            bail!("A transaction needs room for at least one recipient and its change");
+        }
+        if self.kind == RecipientKind::Decrypt && limits.max_decrypt_outputs_per_token == 0 {
+            bail!("A decrypt needs room for at least one recipient per token");
+        }
+
+        let mut batches = Vec::new();
+        let mut batch: Vec<DopERC20AmountRecipient> = Vec::new();
+        let mut per_token: HashMap<EvmAddress, usize> = HashMap::new();
+
+        for entry in &self.entries {
+            let count = per_token.get(&entry.token_address).copied().unwrap_or(0);
+            let outputs = batch.len() + 1 + per_token.len() + usize::from(count == 0);
+            let token_full = self.kind == RecipientKind::Decrypt
+                && count >= limits.max_decrypt_outputs_per_token;
+            if (outputs > limits.max_outputs || token_full) && !batch.is_empty() {
+                batches.push(std::mem::take(&mut batch));
+                per_token.clear();
+            }
+            *per_token.entry(entry.token_address).or_default() += 1;
+            batch.push(entry.to_recipient());
+        }
+
+        if !batch.is_empty() {
+            batches.push(batch);
+        }
+        Ok(batches)
+    }
+
+    fn normalize_recipient(&mut self, recipient: &str) -> Result<String> {
+        match self.kind {
+            RecipientKind::Transfer => {
+                let address: DopAddress = recipient
+                    .parse()
+                    .map_err(|e| anyhow!("Invalid DOP recipient {}: {}", recipient, e))?;
+                if let Some(chain) = address.chain {
+                    match self.chain {
+                        Some(expected) if expected != chain => {
+                            bail!(
+                                "Recipient {} is for chain {}:{}, expected {}:{}",
+                                recipient,
+                                chain.chain_type,
+                                chain.id,
+                                expected.chain_type,
+                                expected.id
+                            );
+                        }
+                        Some(_) => {}
+                        None => self.chain = Some(chain),
+                    }
+                }
+                Ok(address.to_string())
+            }
+            RecipientKind::Decrypt => {
+                let address: EvmAddress = recipient
+                    .parse()
+                    .map_err(|e| anyhow!("Invalid EVM recipient {}: {}", recipient, e))?;
+                Ok(address.to_string())
+            }
+        }
+    }
+}
+
+impl DopClient {
+    pub async fn circuit_limits(&self) -> Result<CircuitLimits> {
+        let resp = self
+            .client
+            .get(format!("{}/circuit-limits", self.base_url()))
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("API call failed: {} (status {})", text, status));
+        }
+
+        Ok(resp.json::<CircuitLimits>().await?)
+    }
+}
+
+impl Entry {
+    fn to_recipient(&self) -> DopERC20AmountRecipient {
+        DopERC20AmountRecipient {
+            token_address: self.token_address,
+            amount: self.amount.to_string(),
+            recipient_address: self.recipient_address.clone(),
+        }
+    }
+}
+
+fn parse_amount(amount: &str) -> Result<u128> {
+    let trimmed = amount.trim();
+    if trimmed.starts_with('-') {
+        bail!("Amount must not be negative: {}", amount);
+    }
+    let value: u128 = trimmed
+        .parse()
+        .map_err(|_| anyhow!("Invalid amount: {}", amount))?;
+    if value == 0 {
+        bail!("Amount must be greater than zero");
+    }
+    Ok(value)
+}
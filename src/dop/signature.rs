@@ -0,0 +1,309 @@
+use crate::dop::DopClient;
+use anyhow::{Result, anyhow, bail};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Value, json};
+use std::{fmt, str::FromStr};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+
+/// Length of a packed `DopSignature` in bytes.
+pub const DOP_SIGNATURE_LEN: usize = 192;
+
+/// EdDSA signature made with a wallet's spending key. Six 32-byte words: the
+/// signature (R8.x, R8.y, S), then the spending public key (x, y) and the
+/// nullifying key, which together hash to the master public key in the
+/// wallet's DOP address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DopSignature([u8; DOP_SIGNATURE_LEN]);
+
+impl DopSignature {
+    pub fn from_bytes(bytes: [u8; DOP_SIGNATURE_LEN]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; DOP_SIGNATURE_LEN] {
+        &self.0
+    }
+}
+
+impl fmt::Display for DopSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+impl FromStr for DopSignature {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let bytes = hex::decode(s.trim_start_matches("0x"))
+            .map_err(|e| anyhow!("Invalid signature hex: {}", e))?;
+        let bytes: [u8; DOP_SIGNATURE_LEN] = bytes
+            .try_into()
+            .map_err(|b: Vec<u8>| anyhow!("Invalid signature length: {} bytes", b.len()))?;
+        Ok(Self(bytes))
+    }
+}
+
+impl Serialize for DopSignature {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for DopSignature {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+const SIGN_IN_HEADER: &str = " wants you to sign in with your DOP account:";
+
+/// Sign-in message modelled on EIP-4361, with a DOP address in place of the
+/// Ethereum account. The rendered text is what the wallet signs.
+///
+/// The message is signed with the wallet's spending key, so a shareable
+/// viewing key is not enough to sign in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignInWithDop {
+    pub domain: String,
+    pub dop_address: String,
+    pub statement: Option<String>,
+    pub uri: String,
+    pub version: String,
+    pub chain_id: Option<u64>,
+    pub nonce: String,
+    pub issued_at: OffsetDateTime,
+    pub expiration_time: Option<OffsetDateTime>,
+    pub not_before: Option<OffsetDateTime>,
+}
+
+impl SignInWithDop {
+    pub fn new(domain: &str, dop_address: &str, uri: &str, nonce: &str) -> Self {
+        Self {
+            domain: domain.to_string(),
+            dop_address: dop_address.to_string(),
+            statement: None,
+            uri: uri.to_string(),
+            version: "1".to_string(),
+            chain_id: None,
+            nonce: nonce.to_string(),
+            issued_at: OffsetDateTime::now_utc().replace_nanosecond(0).unwrap(),
+            expiration_time: None,
+            not_before: None,
+        }
+    }
+
+    pub fn with_statement(mut self, statement: &str) -> Self {
+        self.statement = Some(statement.to_string());
+        self
+    }
+
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    pub fn with_expiration_time(mut self, expiration_time: OffsetDateTime) -> Self {
+        self.expiration_time = Some(expiration_time);
+        self
+    }
+
+    pub fn with_not_before(mut self, not_before: OffsetDateTime) -> Self {
+        self.not_before = Some(not_before);
+        self
+    }
+
+    /// Checks the validity window against `now`.
+    pub fn check_time(&self, now: OffsetDateTime) -> Result<()> {
+        if self
+            .expiration_time
+            .is_some_and(|expiration| now >= expiration)
+        {
+            bail!("Sign-in message has expired");
+        }
+        if self.not_before.is_some_and(|not_before| now < not_before) {
+            bail!("Sign-in message is not yet valid");
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SignInWithDop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rfc3339 = |t: &OffsetDateTime| t.format(&Rfc3339).map_err(|_| fmt::Error);
+
+        writeln!(f, "{}{}", self.domain, SIGN_IN_HEADER)?;
+        writeln!(f, "{}", self.dop_address)?;
+        writeln!(f)?;
+        if let Some(statement) = &self.statement {
+            writeln!(f, "{}", statement)?;
+            writeln!(f)?;
+        }
+        writeln!(f, "URI: {}", self.uri)?;
+        write!(f, "Version: {}", self.version)?;
+        if let Some(chain_id) = self.chain_id {
+            write!(f, "\nChain ID: {}", chain_id)?;
+        }
+        write!(f, "\nNonce: {}", self.nonce)?;
+        write!(f, "\nIssued At: {}", rfc3339(&self.issued_at)?)?;
+        if let Some(expiration) = &self.expiration_time {
+            write!(f, "\nExpiration Time: {}", rfc3339(expiration)?)?;
+        }
+        if let Some(not_before) = &self.not_before {
+            write!(f, "\nNot Before: {}", rfc3339(not_before)?)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SignInWithDop {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut lines = s.lines().peekable();
+
+        let domain = lines
+            .next()
+            .and_then(|l| l.strip_suffix(SIGN_IN_HEADER))
+            .filter(|d| !d.is_empty())
+            .ok_or_else(|| anyhow!("Missing sign-in header"))?
+            .to_string();
+        let dop_address = lines
+            .next()
+            .filter(|a| !a.is_empty())
+            .ok_or_else(|| anyhow!("Missing DOP address"))?
+            .to_string();
+        if lines.next() != Some("") {
+            bail!("Expected blank line after DOP address");
+        }
+
+        let mut statement = None;
+        if lines.peek().is_some_and(|l| !l.starts_with("URI: ")) {
+            statement = lines.next().map(|l| l.to_string());
+            if lines.next() != Some("") {
+                bail!("Expected blank line after statement");
+            }
+        }
+
+        let mut field = |name: &str, required: bool| -> Result<Option<String>> {
+            let prefix = format!("{}: ", name);
+            match lines.peek().and_then(|l| l.strip_prefix(prefix.as_str())) {
+                Some(value) => {
+                    let value = value.to_string();
+                    lines.next();
+                    Ok(Some(value))
+                }
+                None if required => Err(anyhow!("Missing {} field", name)),
+                None => Ok(None),
+            }
+        };
+        let parse_time = |value: String| {
+            OffsetDateTime::parse(&value, &Rfc3339)
+                .map_err(|e| anyhow!("Invalid timestamp {}: {}", value, e))
+        };
+
+        let uri = field("URI", true)?.unwrap();
+        let version = field("Version", true)?.unwrap();
+        let chain_id = field("Chain ID", false)?
+            .map(|v| v.parse::<u64>())
+            .transpose()?;
+        let nonce = field("Nonce", true)?.unwrap();
+        let issued_at = parse_time(field("Issued At", true)?.unwrap())?;
+        let expiration_time = field("Expiration Time", false)?
+            .map(parse_time)
+            .transpose()?;
+        let not_before = field("Not Before", false)?.map(parse_time).transpose()?;
+
+        if let Some(extra) = lines.next() {
+            bail!("Unexpected line in sign-in message: {}", extra);
+        }
+
+        Ok(Self {
+            domain,
+            dop_address,
+            statement,
+            uri,
+            version,
+            chain_id,
+            nonce,
+            issued_at,
+            expiration_time,
+            not_before,
+        })
+    }
+}
+
+impl DopClient {
+    /// Verifies a signature from `sign_message_with_wallet` or
+    /// `sign_bytes_with_wallet` against the spending public key it carries,
+    /// and checks that this key and the nullifying key hash to the master
+    /// public key encoded in `dop_address`.
+    ///
+    /// The nullifying key lets its holder recognise the wallet's spent notes,
+    /// so only hand signatures to parties you trust with that.
+    pub async fn verify_dop_signature(
+        &self,
+        dop_address: &str,
+        message: impl AsRef<[u8]>,
+        signature: &DopSignature,
+    ) -> Result<bool> {
+        let payload = json!({
+            "dopAddress": dop_address,
+            "messageHex": hex::encode(message.as_ref()),
+            "signature": signature.to_string(),
+        });
+
+        let resp = self
+            .client
+            .post(format!("{}/wallet/verify-signature", self.base_url()))
+            .json(&payload)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("API call failed: {} (status {})", text, status));
+        }
+
+        resp.json::<Value>()
+            .await?
+            .get("valid")
+            .and_then(|v| v.as_bool())
+            .ok_or_else(|| anyhow!("Missing validation result for signature"))
+    }
+
+    pub async fn sign_in_with_dop(
+        &self,
+        wallet_id: &str,
+        encryption_key: &str,
+        message: &SignInWithDop,
+    ) -> Result<DopSignature> {
+        let dop_address = self.get_dop_address(wallet_id).await?;
+        if dop_address != message.dop_address {
+            bail!("Sign-in message is for a different DOP address");
+        }
+        self.sign_message_with_wallet(wallet_id, encryption_key, &message.to_string())
+            .await
+    }
+
+    /// Parses a signed sign-in message, checks its validity window and
+    /// signature, and returns it so the caller can match domain and nonce.
+    pub async fn verify_sign_in_with_dop(
+        &self,
+        message: &str,
+        signature: &DopSignature,
+    ) -> Result<SignInWithDop> {
+        let parsed: SignInWithDop = message.parse()?;
+        parsed.check_time(OffsetDateTime::now_utc())?;
+
+        if !self
+            .verify_dop_signature(&parsed.dop_address, message, signature)
+            .await?
+        {
+            bail!("Invalid sign-in signature");
+        }
+        Ok(parsed)
+    }
+}
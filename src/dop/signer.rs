@@ -0,0 +1,241 @@
+use crate::dop::{ContractTransaction, EvmAddress, MessageSigner, TransactionGasDetails};
+use anyhow::{Result, anyhow, bail};
+use k256::ecdsa::{RecoveryId, Signature, SigningKey};
+use sha3::{Digest, Keccak256};
+use std::{fmt, future::Future};
+
+/// Signs populated transactions for an EVM account.
+pub trait TransactionSigner {
+    fn address(&self) -> EvmAddress;
+
+    /// Returns the raw signed transaction, ready for `eth_sendRawTransaction`.
+    /// The gas limit is the gas estimate in `gas_details`, and its type picks
+    /// the envelope: type 0 is a legacy EIP-155 transaction, type 1 EIP-2930
+    /// and type 2 EIP-1559, both with an empty access list.
+    fn sign_transaction(
+        &self,
+        transaction: &ContractTransaction,
+        gas_details: &TransactionGasDetails,
+        nonce: u64,
+        chain_id: u64,
+    ) -> impl Future<Output = Result<Vec<u8>>> + Send;
+}
+
+/// An EVM account whose private key is held in memory.
+#[derive(Clone)]
+pub struct LocalSigner {
+    key: SigningKey,
+    address: EvmAddress,
+}
+
+impl LocalSigner {
+    /// `private_key` is 32 bytes of hex, with or without `0x`.
+    pub fn from_private_key(private_key: &str) -> Result<Self> {
+        let digits = private_key.strip_prefix("0x").unwrap_or(private_key);
+        let bytes = hex::decode(digits).map_err(|_| anyhow!("Invalid private key hex"))?;
+        let key = SigningKey::from_slice(&bytes).map_err(|_| anyhow!("Invalid private key"))?;
+        Ok(Self::from_signing_key(key))
+    }
+
+    fn from_signing_key(key: SigningKey) -> Self {
+        let public_key = key.verifying_key().to_encoded_point(false);
+        let hash = Keccak256::digest(&public_key.as_bytes()[1..]);
+        let address = EvmAddress::from_bytes(hash[12..].try_into().unwrap());
+        Self { key, address }
+    }
+
+    pub fn address(&self) -> EvmAddress {
+        self.address
+    }
+
+    /// Signs a 32-byte digest. Signatures are deterministic (RFC 6979) and
+    /// use the low-s form Ethereum requires.
+    pub fn sign_hash(&self, hash: &[u8; 32]) -> Result<(Signature, RecoveryId)> {
+        Ok(self.key.sign_prehash_recoverable(hash)?)
+    }
+
+    /// EIP-191 `personal_sign`: 65 bytes of `r || s || v` with `v` 27 or 28,
+    /// the same bytes `signMessage` gives in ethers.
+    pub fn sign_personal_message(&self, message: &[u8]) -> Result<[u8; 65]> {
+        let mut hasher = Keccak256::new();
+        hasher.update(format!("\x19Ethereum Signed Message:\n{}", message.len()));
+        hasher.update(message);
+        let (signature, recovery_id) = self.sign_hash(&hasher.finalize().into())?;
+
+        let mut out = [0u8; 65];
+        out[..64].copy_from_slice(&signature.to_bytes());
+        out[64] = 27 + recovery_id.to_byte();
+        Ok(out)
+    }
+}
+
+impl MessageSigner for LocalSigner {
+    fn address(&self) -> EvmAddress {
+        self.address
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> Result<Vec<u8>> {
+        Ok(self.sign_personal_message(message)?.to_vec())
+    }
+}
+
+impl TransactionSigner for LocalSigner {
+    fn address(&self) -> EvmAddress {
+        self.address
+    }
+
+    async fn sign_transaction(
+        &self,
+        transaction: &ContractTransaction,
+        gas_details: &TransactionGasDetails,
+        nonce: u64,
+        chain_id: u64,
+    ) -> Result<Vec<u8>> {
+        if let Some(from) = transaction.from
+            && from != self.address
+        {
+            bail!("Transaction is from {}, signer is {}", from, self.address);
+        }
+
+        let data = transaction
+            .data
+            .strip_prefix("0x")
+            .unwrap_or(&transaction.data);
+        let data = hex::decode(data).map_err(|_| anyhow!("Invalid transaction data hex"))?;
+        let value = parse_quantity(transaction.value.as_deref().unwrap_or("0"), "value")?;
+        let to = transaction.to.as_bytes();
+
+        match gas_details {
+            TransactionGasDetails::Type0 {
+                gas_estimate,
+                gas_price,
+            } => {
+                let mut fields = vec![
+                    rlp_uint(nonce as u128),
+                    rlp_uint(parse_quantity(gas_price, "gas price")?),
+                    rlp_uint(parse_quantity(gas_estimate, "gas estimate")?),
+                    rlp_bytes(to),
+                    rlp_uint(value),
+                    rlp_bytes(&data),
+                ];
+
+                let mut unsigned = fields.clone();
+                unsigned.extend([rlp_uint(chain_id as u128), rlp_uint(0), rlp_uint(0)]);
+                let hash = Keccak256::digest(rlp_list(&unsigned));
+                let (signature, recovery_id) = self.sign_hash(&hash.into())?;
+
+                let v = chain_id as u128 * 2 + 35 + recovery_id.to_byte() as u128;
+                fields.push(rlp_uint(v));
+                fields.extend(rlp_signature(&signature));
+                Ok(rlp_list(&fields))
+            }
+            TransactionGasDetails::Type1 {
+                gas_estimate,
+                gas_price,
+            } => {
+                let fields = vec![
+                    rlp_uint(chain_id as u128),
+                    rlp_uint(nonce as u128),
+                    rlp_uint(parse_quantity(gas_price, "gas price")?),
+                    rlp_uint(parse_quantity(gas_estimate, "gas estimate")?),
+                    rlp_bytes(to),
+                    rlp_uint(value),
+                    rlp_bytes(&data),
+                    rlp_list(&[]),
+                ];
+                self.sign_typed(0x01, fields)
+            }
+            TransactionGasDetails::Type2 {
+                gas_estimate,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => {
+                let fields = vec![
+                    rlp_uint(chain_id as u128),
+                    rlp_uint(nonce as u128),
+                    rlp_uint(parse_quantity(
+                        max_priority_fee_per_gas,
+                        "max priority fee",
+                    )?),
+                    rlp_uint(parse_quantity(max_fee_per_gas, "max fee")?),
+                    rlp_uint(parse_quantity(gas_estimate, "gas estimate")?),
+                    rlp_bytes(to),
+                    rlp_uint(value),
+                    rlp_bytes(&data),
+                    rlp_list(&[]),
+                ];
+                self.sign_typed(0x02, fields)
+            }
+        }
+    }
+}
+
+impl LocalSigner {
+    /// EIP-2718 typed transaction: `type || rlp(fields ++ [y_parity, r, s])`,
+    /// signed over `type || rlp(fields)`.
+    fn sign_typed(&self, tx_type: u8, mut fields: Vec<Vec<u8>>) -> Result<Vec<u8>> {
+        let mut unsigned = vec![tx_type];
+        unsigned.extend(rlp_list(&fields));
+        let (signature, recovery_id) = self.sign_hash(&Keccak256::digest(&unsigned).into())?;
+
+        fields.push(rlp_uint(recovery_id.to_byte() as u128));
+        fields.extend(rlp_signature(&signature));
+        let mut signed = vec![tx_type];
+        signed.extend(rlp_list(&fields));
+        Ok(signed)
+    }
+}
+
+fn parse_quantity(value: &str, name: &str) -> Result<u128> {
+    value
+        .parse()
+        .map_err(|_| anyhow!("Invalid {}: {}", name, value))
+}
+
+fn rlp_signature(signature: &Signature) -> [Vec<u8>; 2] {
+    let (r, s) = signature.split_bytes();
+    [rlp_bytes(trim_zeros(&r)), rlp_bytes(trim_zeros(&s))]
+}
+
+fn trim_zeros(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+fn rlp_uint(value: u128) -> Vec<u8> {
+    rlp_bytes(trim_zeros(&value.to_be_bytes()))
+}
+
+fn rlp_bytes(bytes: &[u8]) -> Vec<u8> {
+    if bytes.len() == 1 && bytes[0] < 0x80 {
+        return bytes.to_vec();
+    }
+    let mut out = rlp_length_prefix(0x80, bytes.len());
+    out.extend_from_slice(bytes);
+    out
+}
+
+fn rlp_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload = items.concat();
+    let mut out = rlp_length_prefix(0xc0, payload.len());
+    out.extend(payload);
+    out
+}
+
+fn rlp_length_prefix(offset: u8, len: usize) -> Vec<u8> {
+    if len <= 55 {
+        return vec![offset + len as u8];
+    }
+    let len_bytes = trim_zeros(&len.to_be_bytes()).to_vec();
+    let mut out = vec![offset + 55 + len_bytes.len() as u8];
+    out.extend(len_bytes);
+    out
+}
+
+impl fmt::Debug for LocalSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalSigner")
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
@@ -1,12 +1,16 @@
 use crate::dop::{
     DopClient, DopERC20Amount, DopERC20AmountRecipient, DopNFTAmountRecipient,
     DopPopulateTransactionResponse, DopTransactionGasEstimateResponse, FeeTokenDetails,
-    TransactionGasDetails,
+    ProvedTransaction, TransactionGasDetails,
 };
 use anyhow::{Result, anyhow};
 use serde_json::json;
 
 impl DopClient {
+    /// `value` is the wei of native token sent with the decrypt call, as a
+    /// decimal string, and is usually `"0"`. The gas estimate must be given
+    /// the same value. `Decrypt` chains these calls for each decrypt mode.
+    #[allow(clippy::too_many_arguments)]
     pub async fn generate_decrypt_proof(
         &self,
         txid_version: String,
@@ -19,25 +23,23 @@ impl DopClient {
         send_with_public_wallet: bool,
         overall_batch_min_gas_price: Option<String>,
         value: String,
-    ) -> Result<()> {
-        let payload = json!({
-            "txidVersion": txid_version,
-            "networkName": network_name,
-            "dopWalletID": dop_wallet_id,
-            "encryptionKey": encryption_key,
-            "erc20AmountRecipients": erc20_amount_recipients,
-            "nftAmountRecipients": nft_amount_recipients,
-            "broadcasterFeeERC20AmountRecipient": broadcaster_fee_recipient,
-            "sendWithPublicWallet": send_with_public_wallet,
-            "overallBatchMinGasPrice": overall_batch_min_gas_price,
-            "value": value
-        });
-
-        self.send_request::<serde_json::Value>("/generate-decrypt-proof", payload)
-            .await?;
-        Ok(())
+    ) -> Result<ProvedTransaction> {
+        let payload = decrypt_proof_payload(
+            txid_version,
+            network_name,
+            dop_wallet_id,
+            encryption_key,
+            erc20_amount_recipients,
+            nft_amount_recipients,
+            broadcaster_fee_recipient,
+            send_with_public_wallet,
+            overall_batch_min_gas_price,
+            value,
+        )?;
+        self.run_proof_job("/generate-decrypt-proof", payload).await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn generate_decrypt_to_origin_proof(
         &self,
         original_encrypt_txid: String,
@@ -48,7 +50,7 @@ impl DopClient {
         erc20_amount_recipients: Vec<DopERC20AmountRecipient>,
         nft_amount_recipients: Vec<DopNFTAmountRecipient>,
         value: String,
-    ) -> Result<()> {
+    ) -> Result<ProvedTransaction> {
         let payload = json!({
             "originalEncryptTxid": original_encrypt_txid,
             "txidVersion": txid_version,
@@ -60,11 +62,11 @@ impl DopClient {
             "value": value
         });
 
-        self.send_request::<serde_json::Value>("/generate-decrypt-to-origin-proof", payload)
-            .await?;
-        Ok(())
+        self.run_proof_job("/generate-decrypt-to-origin-proof", payload)
+            .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn generate_decrypt_base_token_proof(
         &self,
         txid_version: String,
@@ -76,26 +78,26 @@ impl DopClient {
         broadcaster_fee_recipient: Option<DopERC20AmountRecipient>,
         send_with_public_wallet: bool,
         overall_batch_min_gas_price: Option<String>,
-    ) -> Result<()> {
-        let payload = json!({
-            "txidVersion": txid_version,
-            "networkName": network_name,
-            "publicWalletAddress": public_wallet_address,
-            "dopWalletID": dop_wallet_id,
-            "encryptionKey": encryption_key,
-            "wrappedERC20Amount": wrapped_erc20_amount,
-            "broadcasterFeeERC20AmountRecipient": broadcaster_fee_recipient,
-            "sendWithPublicWallet": send_with_public_wallet,
-            "overallBatchMinGasPrice": overall_batch_min_gas_price
-        });
-
-        self.send_request::<serde_json::Value>("/generate-decrypt-base-token-proof", payload)
-            .await?;
-        Ok(())
+    ) -> Result<ProvedTransaction> {
+        let payload = decrypt_base_token_proof_payload(
+            txid_version,
+            network_name,
+            public_wallet_address,
+            dop_wallet_id,
+            encryption_key,
+            wrapped_erc20_amount,
+            broadcaster_fee_recipient,
+            send_with_public_wallet,
+            overall_batch_min_gas_price,
+        )?;
+        self.run_proof_job("/generate-decrypt-base-token-proof", payload)
+            .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn populate_proved_decrypt(
         &self,
+        proved_transaction: &ProvedTransaction,
         txid_version: String,
         network_name: String,
         dop_wallet_id: String,
@@ -113,7 +115,8 @@ impl DopClient {
             "erc20AmountRecipients": erc20_recipients,
             "nftAmountRecipients": nft_recipients,
             "sendWithPublicWallet": send_with_public_wallet,
-            "gasDetails": gas_details
+            "gasDetails": gas_details,
+            "provedTransaction": proved_transaction,
         });
 
         if let Some(recipient) = broadcaster_fee_recipient {
@@ -133,8 +136,10 @@ impl DopClient {
         self.send_request("/populate-proved-decrypt", payload).await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn populate_proved_decrypt_base_token(
         &self,
+        proved_transaction: &ProvedTransaction,
         txid_version: String,
         network_name: String,
         public_wallet_address: String,
@@ -153,6 +158,7 @@ impl DopClient {
             "wrappedERC20Amount": wrapped_erc20_amount,
             "sendWithPublicWallet": send_with_public_wallet,
             "gasDetails": gas_details,
+            "provedTransaction": proved_transaction,
         });
 
         if let Some(recipient) = broadcaster_fee_recipient {
@@ -173,6 +179,7 @@ impl DopClient {
             .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn gas_estimate_for_unproven_decrypt(
         &self,
         txid_version: String,
@@ -186,29 +193,23 @@ impl DopClient {
         send_with_public_wallet: bool,
         value: String,
     ) -> Result<DopTransactionGasEstimateResponse> {
-        let mut payload = json!({
-            "txidVersion": txid_version,
-            "networkName": network_name,
-            "dopWalletID": dop_wallet_id,
-            "encryptionKey": encryption_key,
-            "erc20AmountRecipients": erc20_recipients,
-            "nftAmountRecipients": nft_recipients,
-            "originalGasDetails": original_gas_details,
-            "sendWithPublicWallet": send_with_public_wallet,
-            "value": value
-        });
-
-        if let Some(fee_token) = fee_token_details {
-            payload.as_object_mut().unwrap().insert(
-                "feeTokenDetails".to_string(),
-                serde_json::to_value(fee_token)?,
-            );
-        }
-
+        let payload = decrypt_gas_estimate_payload(
+            txid_version,
+            network_name,
+            dop_wallet_id,
+            encryption_key,
+            erc20_recipients,
+            nft_recipients,
+            original_gas_details,
+            fee_token_details,
+            send_with_public_wallet,
+            value,
+        )?;
         self.send_request("/gas-estimate-for-unproven-decrypt", payload)
             .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn gas_estimate_for_unproven_decrypt_base_token(
         &self,
         txid_version: String,
@@ -221,24 +222,17 @@ impl DopClient {
         fee_token_details: Option<FeeTokenDetails>,
         send_with_public_wallet: bool,
     ) -> Result<DopTransactionGasEstimateResponse> {
-        let mut payload = json!({
-            "txidVersion": txid_version,
-            "networkName": network_name,
-            "publicWalletAddress": public_wallet_address,
-            "dopWalletID": dop_wallet_id,
-            "encryptionKey": encryption_key,
-            "wrappedERC20Amount": wrapped_erc20_amount,
-            "originalGasDetails": original_gas_details,
-            "sendWithPublicWallet": send_with_public_wallet
-        });
-
-        if let Some(fee_token) = fee_token_details {
-            payload.as_object_mut().unwrap().insert(
-                "feeTokenDetails".to_string(),
-                serde_json::to_value(fee_token)?,
-            );
-        }
-
+        let payload = decrypt_base_token_gas_estimate_payload(
+            txid_version,
+            network_name,
+            public_wallet_address,
+            dop_wallet_id,
+            encryption_key,
+            wrapped_erc20_amount,
+            original_gas_details,
+            fee_token_details,
+            send_with_public_wallet,
+        )?;
         self.send_request("/gas-estimate-for-unproven-decrypt-base-token", payload)
             .await
     }
@@ -259,7 +253,7 @@ impl DopClient {
 
         let resp = self
             .client
-            .post(&format!(
+            .post(format!(
                 "{}/get-recipients-for-decrypt-to-origin",
                 self.base_url()
             ))
@@ -279,8 +273,10 @@ impl DopClient {
         Ok((erc20, nft))
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn populate_proved_decrypt_to_origin(
         &self,
+        proved_transaction: &ProvedTransaction,
         txid_version: String,
         network_name: String,
         dop_wallet_id: String,
@@ -294,13 +290,15 @@ impl DopClient {
             "dopWalletID": dop_wallet_id,
             "erc20AmountRecipients": erc20_recipients,
             "nftAmountRecipients": nft_recipients,
-            "gasDetails": gas_details
+            "gasDetails": gas_details,
+            "provedTransaction": proved_transaction,
         });
 
         self.send_request("/populate-proved-decrypt-to-origin", payload)
             .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn gas_estimate_for_unproven_decrypt_to_origin(
         &self,
         original_encrypt_txid: String,
@@ -327,14 +325,14 @@ impl DopClient {
             .await
     }
 
-    async fn send_request<T: for<'de> serde::Deserialize<'de>>(
+    pub(crate) async fn send_request<T: for<'de> serde::Deserialize<'de>>(
         &self,
         endpoint: &str,
         payload: serde_json::Value,
     ) -> Result<T> {
         let resp = self
             .client
-            .post(&format!("{}{}", self.base_url(), endpoint))
+            .post(format!("{}{}", self.base_url(), endpoint))
             .json(&payload)
             .send()
             .await?;
@@ -348,3 +346,133 @@ impl DopClient {
         Ok(resp.json::<T>().await?)
     }
 }
+
+// Payloads are built separately so `Decrypt` can add a UTXO selection to
+// them.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn decrypt_proof_payload(
+    txid_version: String,
+    network_name: String,
+    dop_wallet_id: String,
+    encryption_key: String,
+    erc20_amount_recipients: Vec<DopERC20AmountRecipient>,
+    nft_amount_recipients: Vec<DopNFTAmountRecipient>,
+    broadcaster_fee_recipient: Option<DopERC20AmountRecipient>,
+    send_with_public_wallet: bool,
+    overall_batch_min_gas_price: Option<String>,
+    value: String,
+) -> Result<serde_json::Value> {
+    validate_value(&value)?;
+    Ok(json!({
+        "txidVersion": txid_version,
+        "networkName": network_name,
+        "dopWalletID": dop_wallet_id,
+        "encryptionKey": encryption_key,
+        "erc20AmountRecipients": erc20_amount_recipients,
+        "nftAmountRecipients": nft_amount_recipients,
+        "broadcasterFeeERC20AmountRecipient": broadcaster_fee_recipient,
+        "sendWithPublicWallet": send_with_public_wallet,
+        "overallBatchMinGasPrice": overall_batch_min_gas_price,
+        "value": value
+    }))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn decrypt_base_token_proof_payload(
+    txid_version: String,
+    network_name: String,
+    public_wallet_address: String,
+    dop_wallet_id: String,
+    encryption_key: String,
+    wrapped_erc20_amount: DopERC20Amount,
+    broadcaster_fee_recipient: Option<DopERC20AmountRecipient>,
+    send_with_public_wallet: bool,
+    overall_batch_min_gas_price: Option<String>,
+) -> Result<serde_json::Value> {
+    Ok(json!({
+        "txidVersion": txid_version,
+        "networkName": network_name,
+        "publicWalletAddress": public_wallet_address,
+        "dopWalletID": dop_wallet_id,
+        "encryptionKey": encryption_key,
+        "wrappedERC20Amount": wrapped_erc20_amount,
+        "broadcasterFeeERC20AmountRecipient": broadcaster_fee_recipient,
+        "sendWithPublicWallet": send_with_public_wallet,
+        "overallBatchMinGasPrice": overall_batch_min_gas_price
+    }))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn decrypt_gas_estimate_payload(
+    txid_version: String,
+    network_name: String,
+    dop_wallet_id: String,
+    encryption_key: String,
+    erc20_recipients: Vec<DopERC20AmountRecipient>,
+    nft_recipients: Vec<DopNFTAmountRecipient>,
+    original_gas_details: TransactionGasDetails,
+    fee_token_details: Option<FeeTokenDetails>,
+    send_with_public_wallet: bool,
+    value: String,
+) -> Result<serde_json::Value> {
+    validate_value(&value)?;
+    let mut payload = json!({
+        "txidVersion": txid_version,
+        "networkName": network_name,
+        "dopWalletID": dop_wallet_id,
+        "encryptionKey": encryption_key,
+        "erc20AmountRecipients": erc20_recipients,
+        "nftAmountRecipients": nft_recipients,
+        "originalGasDetails": original_gas_details,
+        "sendWithPublicWallet": send_with_public_wallet,
+        "value": value
+    });
+
+    if let Some(fee_token) = fee_token_details {
+        payload.as_object_mut().unwrap().insert(
+            "feeTokenDetails".to_string(),
+            serde_json::to_value(fee_token)?,
+        );
+    }
+    Ok(payload)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn decrypt_base_token_gas_estimate_payload(
+    txid_version: String,
+    network_name: String,
+    public_wallet_address: String,
+    dop_wallet_id: String,
+    encryption_key: String,
+    wrapped_erc20_amount: DopERC20Amount,
+    original_gas_details: TransactionGasDetails,
+    fee_token_details: Option<FeeTokenDetails>,
+    send_with_public_wallet: bool,
+) -> Result<serde_json::Value> {
+    let mut payload = json!({
+        "txidVersion": txid_version,
+        "networkName": network_name,
+        "publicWalletAddress": public_wallet_address,
+        "dopWalletID": dop_wallet_id,
+        "encryptionKey": encryption_key,
+        "wrappedERC20Amount": wrapped_erc20_amount,
+        "originalGasDetails": original_gas_details,
+        "sendWithPublicWallet": send_with_public_wallet
+    });
+
+    if let Some(fee_token) = fee_token_details {
+        payload.as_object_mut().unwrap().insert(
+            "feeTokenDetails".to_string(),
+            serde_json::to_value(fee_token)?,
+        );
+    }
+    Ok(payload)
+}
+
+fn validate_value(value: &str) -> Result<()> {
+    value
+        .trim()
+        .parse::<u128>()
+        .map_err(|_| anyhow!("Invalid value: {}", value))?;
+    Ok(())
+}
@@ -1,10 +1,10 @@
-use super::DopClient;
+use super::{DopClient, EvmAddress};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DopERC20AmountRecipient {
     #[serde(rename = "tokenAddress")]
-    pub token_address: String,
+    pub token_address: EvmAddress,
     pub amount: String,
     #[serde(rename = "recipientAddress")]
     pub recipient_address: String,
@@ -13,7 +13,7 @@ pub struct DopERC20AmountRecipient {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DopNFTAmountRecipient {
     #[serde(rename = "tokenAddress")]
-    pub token_address: String,
+    pub token_address: EvmAddress,
     #[serde(rename = "tokenId")]
     pub token_id: String,
     #[serde(rename = "recipientAddress")]
@@ -28,9 +28,13 @@ pub struct CommitmentSummary {
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ContractTransaction {
-    pub to: String,
+    pub to: EvmAddress,
     pub data: String,
-    pub from: Option<String>,
+    pub from: Option<EvmAddress>,
+    /// Wei sent with the call, as a decimal string. Only set by base token
+    /// encrypts.
+    #[serde(default)]
+    pub value: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -70,14 +74,14 @@ pub enum TransactionGasDetails {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DopERC20Amount {
     #[serde(rename = "tokenAddress")]
-    pub token_address: String,
+    pub token_address: EvmAddress,
     pub amount: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FeeTokenDetails {
     #[serde(rename = "tokenAddress")]
-    pub token_address: String,
+    pub token_address: EvmAddress,
     #[serde(rename = "maxAmount")]
     pub max_amount: String,
 }
@@ -112,7 +116,7 @@ impl DopClient {
 
         let resp = self
             .client
-            .post(&format!(
+            .post(format!(
                 "{}/gas-estimate-for-encrypt-base-token",
                 self.base_url()
             ))
@@ -135,6 +139,7 @@ impl DopClient {
     }
 
     // Encrypt Base Token - Populate Transaction
+    #[allow(clippy::too_many_arguments)]
     pub async fn populate_encrypt_base_token(
         &self,
         txid_version: String,
@@ -163,7 +168,7 @@ impl DopClient {
 
         let resp = self
             .client
-            .post(&format!("{}/populate-encrypt-base-token", self.base_url()))
+            .post(format!("{}/populate-encrypt-base-token", self.base_url()))
             .json(&payload)
             .send()
             .await?;
@@ -203,7 +208,7 @@ impl DopClient {
 
         let resp = self
             .client
-            .post(&format!("{}/gas-estimate-for-encrypt", self.base_url()))
+            .post(format!("{}/gas-estimate-for-encrypt", self.base_url()))
             .json(&payload)
             .send()
             .await?;
@@ -249,7 +254,7 @@ impl DopClient {
 
         let resp = self
             .client
-            .post(&format!("{}/populate-encrypt", self.base_url()))
+            .post(format!("{}/populate-encrypt", self.base_url()))
             .json(&payload)
             .send()
             .await?;
@@ -272,7 +277,7 @@ impl DopClient {
     pub async fn get_encrypt_private_key_signature_message(&self) -> anyhow::Result<String> {
         let resp = self
             .client
-            .post(&format!(
+            .post(format!(
                 "{}/get-encrypt-private-key-signature-message",
                 self.base_url()
             ))
@@ -315,7 +320,7 @@ impl DopClient {
 
         let resp = self
             .client
-            .post(&format!("{}/generate-encrypt-transaction", self.base_url()))
+            .post(format!("{}/generate-encrypt-transaction", self.base_url()))
             .json(&payload)
             .send()
             .await?;
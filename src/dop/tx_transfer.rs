@@ -1,14 +1,15 @@
 use super::{
-    CommitmentSummary, DopClient, DopERC20AmountRecipient, DopNFTAmountRecipient,
-    DopPopulateTransactionResponse, DopTransactionGasEstimateResponse, FeeTokenDetails,
-    TransactionGasDetails,
+    DopClient, DopERC20AmountRecipient, DopNFTAmountRecipient, DopPopulateTransactionResponse,
+    DopTransactionGasEstimateResponse, FeeTokenDetails, ProvedTransaction, TransactionGasDetails,
+    validate_optional_memo_text,
 };
-use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 impl DopClient {
+    #[allow(clippy::too_many_arguments)]
     pub async fn populate_proved_transfer(
         &self,
+        proved_transaction: &ProvedTransaction,
         txid_version: String,
         network_name: String,
         dop_wallet_id: String,
@@ -21,6 +22,7 @@ impl DopClient {
         overall_batch_min_gas_price: Option<String>,
         gas_details: TransactionGasDetails,
     ) -> anyhow::Result<DopPopulateTransactionResponse> {
+        validate_optional_memo_text(memo_text.as_deref())?;
         let gas_details_json = serde_json::to_value(gas_details)?;
 
         let payload = serde_json::json!({
@@ -35,11 +37,12 @@ impl DopClient {
             "sendWithPublicWallet": send_with_public_wallet,
             "overallBatchMinGasPrice": overall_batch_min_gas_price,
             "gasDetails": gas_details_json,
+            "provedTransaction": proved_transaction,
         });
 
         let resp = self
             .client
-            .post(&format!("{}/populate-proved-transfer", self.base_url()))
+            .post(format!("{}/populate-proved-transfer", self.base_url()))
             .json(&payload)
             .send()
             .await?;
@@ -58,6 +61,7 @@ impl DopClient {
         Ok(result)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn gas_estimate_for_unproven_transfer(
         &self,
         txid_version: String,
@@ -71,24 +75,28 @@ impl DopClient {
         fee_token_details: Option<FeeTokenDetails>,
         send_with_public_wallet: bool,
     ) -> anyhow::Result<DopTransactionGasEstimateResponse> {
-        let gas_details_json = serde_json::to_value(original_gas_details)?;
-
-        let payload = serde_json::json!({
-            "txidVersion": txid_version,
-            "networkName": network_name,
-            "dopWalletID": dop_wallet_id,
-            "encryptionKey": encryption_key,
-            "memoText": memo_text,
-            "erc20AmountRecipients": erc20_recipients,
-            "nftAmountRecipients": nft_recipients,
-            "originalGasDetails": gas_details_json,
-            "feeTokenDetails": fee_token_details,
-            "sendWithPublicWallet": send_with_public_wallet,
-        });
+        let payload = transfer_gas_estimate_payload(
+            txid_version,
+            network_name,
+            dop_wallet_id,
+            encryption_key,
+            memo_text,
+            erc20_recipients,
+            nft_recipients,
+            original_gas_details,
+            fee_token_details,
+            send_with_public_wallet,
+        )?;
+        self.send_transfer_gas_estimate(payload).await
+    }
 
+    pub(crate) async fn send_transfer_gas_estimate(
+        &self,
+        payload: serde_json::Value,
+    ) -> anyhow::Result<DopTransactionGasEstimateResponse> {
         let resp = self
             .client
-            .post(&format!(
+            .post(format!(
                 "{}/gas-estimate-for-unproven-transfer",
                 self.base_url()
             ))
@@ -110,6 +118,7 @@ impl DopClient {
         Ok(result)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn generate_transfer_proof(
         &self,
         txid_version: String,
@@ -123,38 +132,83 @@ impl DopClient {
         broadcaster_fee_recipient: Option<DopERC20AmountRecipient>,
         send_with_public_wallet: bool,
         overall_batch_min_gas_price: Option<String>,
-    ) -> Result<(), anyhow::Error> {
-        let payload = json!({
-            "txidVersion": txid_version,
-            "networkName": network_name,
-            "dopWalletID": dop_wallet_id,
-            "encryptionKey": encryption_key,
-            "showSenderAddressToRecipient": show_sender_address_to_recipient,
-            "memoText": memo_text,
-            "erc20AmountRecipients": erc20_recipients,
-            "nftAmountRecipients": nft_recipients,
-            "broadcasterFeeERC20AmountRecipient": broadcaster_fee_recipient,
-            "sendWithPublicWallet": send_with_public_wallet,
-            "overallBatchMinGasPrice": overall_batch_min_gas_price,
-        });
+    ) -> anyhow::Result<ProvedTransaction> {
+        let payload = transfer_proof_payload(
+            txid_version,
+            network_name,
+            dop_wallet_id,
+            encryption_key,
+            show_sender_address_to_recipient,
+            memo_text,
+            erc20_recipients,
+            nft_recipients,
+            broadcaster_fee_recipient,
+            send_with_public_wallet,
+            overall_batch_min_gas_price,
+        )?;
+        self.run_proof_job("/generate-transfer-proof", payload)
+            .await
+    }
+}
 
-        let resp = self
-            .client
-            .post(&format!("{}/generate-transfer-proof", self.base_url()))
-            .json(&payload)
-            .send()
-            .await?;
+// Payloads are built separately so `PrivateTransfer` can add a UTXO
+// selection to them.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn transfer_gas_estimate_payload(
+    txid_version: String,
+    network_name: String,
+    dop_wallet_id: String,
+    encryption_key: String,
+    memo_text: Option<String>,
+    erc20_recipients: Vec<DopERC20AmountRecipient>,
+    nft_recipients: Vec<DopNFTAmountRecipient>,
+    original_gas_details: TransactionGasDetails,
+    fee_token_details: Option<FeeTokenDetails>,
+    send_with_public_wallet: bool,
+) -> anyhow::Result<serde_json::Value> {
+    validate_optional_memo_text(memo_text.as_deref())?;
+    let gas_details_json = serde_json::to_value(original_gas_details)?;
 
-        let status = resp.status();
-        if !status.is_success() {
-            let text = resp.text().await.unwrap_or_default();
-            return Err(anyhow::anyhow!(
-                "API call failed: {} (status {})",
-                text,
-                status
-            ));
-        }
+    Ok(serde_json::json!({
+        "txidVersion": txid_version,
+        "networkName": network_name,
+        "dopWalletID": dop_wallet_id,
+        "encryptionKey": encryption_key,
+        "memoText": memo_text,
+        "erc20AmountRecipients": erc20_recipients,
+        "nftAmountRecipients": nft_recipients,
+        "originalGasDetails": gas_details_json,
+        "feeTokenDetails": fee_token_details,
+        "sendWithPublicWallet": send_with_public_wallet,
+    }))
+}
 
-        Ok(())
-    }
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn transfer_proof_payload(
+    txid_version: String,
+    network_name: String,
+    dop_wallet_id: String,
+    encryption_key: String,
+    show_sender_address_to_recipient: bool,
+    memo_text: Option<String>,
+    erc20_recipients: Vec<DopERC20AmountRecipient>,
+    nft_recipients: Vec<DopNFTAmountRecipient>,
+    broadcaster_fee_recipient: Option<DopERC20AmountRecipient>,
+    send_with_public_wallet: bool,
+    overall_batch_min_gas_price: Option<String>,
+) -> anyhow::Result<serde_json::Value> {
+    validate_optional_memo_text(memo_text.as_deref())?;
+    Ok(json!({
+        "txidVersion": txid_version,
+        "networkName": network_name,
+        "dopWalletID": dop_wallet_id,
+        "encryptionKey": encryption_key,
+        "showSenderAddressToRecipient": show_sender_address_to_recipient,
+        "memoText": memo_text,
+        "erc20AmountRecipients": erc20_recipients,
+        "nftAmountRecipients": nft_recipients,
+        "broadcasterFeeERC20AmountRecipient": broadcaster_fee_recipient,
+        "sendWithPublicWallet": send_with_public_wallet,
+        "overallBatchMinGasPrice": overall_batch_min_gas_price,
+    }))
 }
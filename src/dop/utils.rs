@@ -1,6 +1,4 @@
 use crate::dop::DopClient;
-use anyhow::{Context, Result};
-use serde_json::{json, Value};
 
 impl DopClient {
   
@@ -0,0 +1,143 @@
+use crate::dop::{Chain, DopClient, EvmAddress, PoiStatus};
+use anyhow::{Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+
+/// Identifies a note by its place in the UTXO merkle trees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct UtxoRef {
+    pub tree: u32,
+    pub position: u64,
+}
+
+/// A note received by the wallet.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Utxo {
+    #[serde(rename = "commitmentHash")]
+    pub commitment_hash: String,
+    pub tree: u32,
+    pub position: u64,
+    /// Transaction that created the note.
+    pub txid: String,
+    #[serde(rename = "blockNumber")]
+    pub block_number: Option<u64>,
+    pub timestamp: Option<u64>,
+    #[serde(rename = "tokenAddress")]
+    pub token_address: EvmAddress,
+    /// Base units, as a decimal string.
+    pub value: String,
+    pub spent: bool,
+    #[serde(rename = "spendTxid")]
+    pub spend_txid: Option<String>,
+    /// Status per POI list key. `None` until the engine has asked a POI node.
+    #[serde(rename = "poisPerList")]
+    pub pois_per_list: Option<BTreeMap<String, PoiStatus>>,
+}
+
+impl Utxo {
+    pub fn utxo_ref(&self) -> UtxoRef {
+        UtxoRef {
+            tree: self.tree,
+            position: self.position,
+        }
+    }
+
+    /// The worst POI status over all lists; `Missing` if there are none yet.
+    pub fn poi_status(&self) -> PoiStatus {
+        self.pois_per_list
+            .as_ref()
+            .and_then(|lists| lists.values().max().copied())
+            .unwrap_or(PoiStatus::Missing)
+    }
+
+    pub fn amount(&self) -> Result<u128> {
+        self.value
+            .parse()
+            .map_err(|_| anyhow!("Invalid UTXO value: {}", self.value))
+    }
+}
+
+/// Restricts which notes the engine may spend in a transfer or decrypt.
+/// With `include`, only those notes are used; notes in `exclude` are never
+/// used. The default leaves the choice to the engine.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct UtxoSelection {
+    pub include: Vec<UtxoRef>,
+    pub exclude: Vec<UtxoRef>,
+}
+
+impl UtxoSelection {
+    /// Spend only `utxos`, e.g. to consolidate them into one note.
+    pub fn only(utxos: impl IntoIterator<Item = UtxoRef>) -> Self {
+        Self {
+            include: utxos.into_iter().collect(),
+            exclude: Vec::new(),
+        }
+    }
+
+    /// Spend anything except `utxos`, e.g. to keep funds from one source
+    /// apart.
+    pub fn excluding(utxos: impl IntoIterator<Item = UtxoRef>) -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: utxos.into_iter().collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if let Some(utxo) = self.include.iter().find(|u| self.exclude.contains(u)) {
+            bail!(
+                "UTXO {}:{} is both included and excluded",
+                utxo.tree,
+                utxo.position
+            );
+        }
+        Ok(())
+    }
+
+    /// Adds the selection to a gas estimate or proof request.
+    pub(crate) fn apply(&self, payload: &mut Value) -> Result<()> {
+        self.validate()?;
+        if !self.is_empty() {
+            payload["utxoSelection"] = json!(self);
+        }
+        Ok(())
+    }
+}
+
+impl DopClient {
+    /// Every note `wallet_id` has received on `chain`, spent ones included,
+    /// ordered by tree and position. With `token_address`, only notes of
+    /// that token.
+    pub async fn list_utxos(
+        &self,
+        wallet_id: &str,
+        chain: Chain,
+        token_address: Option<EvmAddress>,
+    ) -> Result<Vec<Utxo>> {
+        let mut query = vec![("chain", serde_json::to_string(&chain)?)];
+        if let Some(token) = token_address {
+            query.push(("tokenAddress", token.to_string()));
+        }
+
+        let resp = self
+            .client
+            .get(format!("{}/wallet/{}/utxos", self.base_url(), wallet_id))
+            .query(&query)
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let text = resp.text().await.unwrap_or_default();
+            return Err(anyhow!("API call failed: {} (status {})", text, status));
+        }
+
+        Ok(resp.json::<Vec<Utxo>>().await?)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::dop::DopClient;
+use crate::dop::{DopAddress, DopClient, DopSignature, EvmAddress};
 use anyhow::{Result, anyhow};
 use serde_json::{Value, json};
 use std::collections::HashMap;
@@ -22,6 +22,17 @@ impl DopClient {
         mnemonic: &str,
         encryption_key: &str,
         creation_block_numbers: Option<HashMap<&str, u64>>,
+    ) -> Result<Value> {
+        self.create_wallet_at_index(mnemonic, encryption_key, creation_block_numbers, None)
+            .await
+    }
+
+    pub async fn create_wallet_at_index(
+        &self,
+        mnemonic: &str,
+        encryption_key: &str,
+        creation_block_numbers: Option<HashMap<&str, u64>>,
+        derivation_index: Option<u32>,
     ) -> Result<Value> {
         let mut payload = json!({
             "mnemonic": mnemonic,
@@ -32,9 +43,13 @@ impl DopClient {
             payload["creationBlockNumbers"] = json!(blocks);
         }
 
+        if let Some(index) = derivation_index {
+            payload["derivationIndex"] = json!(index);
+        }
+
         let res = self
             .client
-            .post(&format!("{}/wallet", self.base_url()))
+            .post(format!("{}/wallet", self.base_url()))
             .json(&payload)
             .send()
             .await?
@@ -47,7 +62,7 @@ impl DopClient {
     pub async fn get_wallet(&self, wallet_id: &str) -> Result<Value> {
         let res = self
             .client
-            .get(&format!("{}/wallet/{}", self.base_url(), wallet_id))
+            .get(format!("{}/wallet/{}", self.base_url(), wallet_id))
             .send()
             .await?
             .json::<Value>()
@@ -62,7 +77,7 @@ impl DopClient {
     ) -> Result<String> {
         let res = self
             .client
-            .get(&format!(
+            .get(format!(
                 "{}/wallet/{}/mnemonic?encryptionKey={}",
                 self.base_url(),
                 wallet_id,
@@ -82,7 +97,7 @@ impl DopClient {
     pub async fn get_shareable_viewing_key(&self, wallet_id: &str) -> Result<String> {
         let res = self
             .client
-            .get(&format!(
+            .get(format!(
                 "{}/wallet/{}/shareable-viewing-key",
                 self.base_url(),
                 wallet_id
@@ -115,7 +130,7 @@ impl DopClient {
 
         let res = self
             .client
-            .post(&format!("{}/wallet/view-only", self.base_url()))
+            .post(format!("{}/wallet/view-only", self.base_url()))
             .json(&payload)
             .send()
             .await?
@@ -125,15 +140,42 @@ impl DopClient {
         Ok(res)
     }
 
-    pub async fn sign_message_with_wallet(&self, wallet_id: &str, message: &str) -> Result<String> {
+    /// Signs `message` with the wallet's spending key. See
+    /// `verify_dop_signature`.
+    pub async fn sign_message_with_wallet(
+        &self,
+        wallet_id: &str,
+        encryption_key: &str,
+        message: &str,
+    ) -> Result<DopSignature> {
         let payload = json!({
             "walletId": wallet_id,
+            "encryptionKey": encryption_key,
             "message": message
         });
 
+        self.send_sign_message(payload).await
+    }
+
+    pub async fn sign_bytes_with_wallet(
+        &self,
+        wallet_id: &str,
+        encryption_key: &str,
+        message: &[u8],
+    ) -> Result<DopSignature> {
+        let payload = json!({
+            "walletId": wallet_id,
+            "encryptionKey": encryption_key,
+            "messageHex": hex::encode(message)
+        });
+
+        self.send_sign_message(payload).await
+    }
+
+    async fn send_sign_message(&self, payload: Value) -> Result<DopSignature> {
         let res = self
             .client
-            .post(&format!("{}/wallet/sign-message", self.base_url()))
+            .post(format!("{}/wallet/sign-message", self.base_url()))
             .json(&payload)
             .send()
             .await?
@@ -142,8 +184,8 @@ impl DopClient {
 
         res.get("signature")
             .and_then(|v| v.as_str())
-            .map(|s| s.to_string())
-            .ok_or_else(|| anyhow!("Missing signature in response"))
+            .ok_or_else(|| anyhow!("Missing signature in response"))?
+            .parse()
     }
 
     pub async fn load_wallet_by_id(
@@ -160,7 +202,7 @@ impl DopClient {
 
         let res = self
             .client
-            .post(&format!("{}/wallet/load", self.base_url()))
+            .post(format!("{}/wallet/load", self.base_url()))
             .json(&payload)
             .send()
             .await?
@@ -172,7 +214,7 @@ impl DopClient {
 
     pub async fn unload_wallet_by_id(&self, wallet_id: &str) -> Result<()> {
         self.client
-            .get(&format!("{}/wallet/{}/unload", self.base_url(), wallet_id))
+            .get(format!("{}/wallet/{}/unload", self.base_url(), wallet_id))
             .send()
             .await?
             .error_for_status()?; // Ensure 2xx response
@@ -181,7 +223,7 @@ impl DopClient {
 
     pub async fn delete_wallet_by_id(&self, wallet_id: &str) -> Result<()> {
         self.client
-            .delete(&format!("{}/wallet/{}/delete", self.base_url(), wallet_id))
+            .delete(format!("{}/wallet/{}/delete", self.base_url(), wallet_id))
             .send()
             .await?
             .error_for_status()?;
@@ -189,25 +231,13 @@ impl DopClient {
     }
 
     pub async fn get_dop_wallet_address_data(&self, address: &str) -> Result<Value> {
-        let res = self
-            .client
-            .get(&format!(
-                "{}/wallet/address-data?address={}",
-                self.base_url(),
-                address
-            ))
-            .send()
-            .await?
-            .json::<Value>()
-            .await?;
-
-        Ok(res)
+        Ok(address.parse::<DopAddress>()?.to_address_data())
     }
 
     pub async fn get_private_viewing_key(&self, wallet_id: &str) -> Result<String> {
         let res = self
             .client
-            .get(&format!(
+            .get(format!(
                 "{}/wallet/{}/private-viewing-key",
                 self.base_url(),
                 wallet_id
@@ -226,7 +256,7 @@ impl DopClient {
     pub async fn get_dop_address(&self, wallet_id: &str) -> Result<String> {
         let res = self
             .client
-            .get(&format!(
+            .get(format!(
                 "{}/wallet/{}/dop-address",
                 self.base_url(),
                 wallet_id
@@ -243,45 +273,17 @@ impl DopClient {
     }
 
     pub async fn validate_dop_address(&self, address: &str) -> Result<bool> {
-        let res = self
-            .client
-            .get(&format!(
-                "{}/validate/dop-address?address={}",
-                self.base_url(),
-                address
-            ))
-            .send()
-            .await?
-            .json::<Value>()
-            .await?;
-
-        res.get("valid")
-            .and_then(|v| v.as_bool())
-            .ok_or_else(|| anyhow!("Missing validation result for DOP address"))
+        Ok(DopAddress::is_valid(address))
     }
 
     pub async fn validate_eth_address(&self, address: &str) -> Result<bool> {
-        let res = self
-            .client
-            .get(&format!(
-                "{}/validate/eth-address?address={}",
-                self.base_url(),
-                address
-            ))
-            .send()
-            .await?
-            .json::<Value>()
-            .await?;
-
-        res.get("valid")
-            .and_then(|v| v.as_bool())
-            .ok_or_else(|| anyhow!("Missing validation result for ETH address"))
+        Ok(EvmAddress::is_valid(address))
     }
 
     pub async fn scan_wallet(&self, wallet_id: &str, chain: Value) -> Result<Value> {
         let res = self
             .client
-            .get(&format!("{}/wallet/{}/scan", self.base_url(), wallet_id))
+            .get(format!("{}/wallet/{}/scan", self.base_url(), wallet_id))
             .query(&[("chain", chain.to_string())])
             .send()
             .await?
@@ -298,7 +300,7 @@ impl DopClient {
         count: u32,
     ) -> Result<()> {
         self.client
-            .get(&format!(
+            .get(format!(
                 "{}/wallet/{}/scan-multiple",
                 self.base_url(),
                 wallet_id
@@ -312,38 +314,16 @@ impl DopClient {
     }
 
     pub async fn assert_valid_eth_address(&self, address: &str) -> Result<()> {
-        let res = self
-            .client
-            .get(&format!(
-                "{}/assert/eth-address?address={}",
-                self.base_url(),
-                address
-            ))
-            .send()
-            .await?;
-
-        if res.status().is_success() {
-            Ok(())
-        } else {
-            Err(anyhow!("Invalid ETH address format"))
-        }
+        address
+            .parse::<EvmAddress>()
+            .map(|_| ())
+            .map_err(|_| anyhow!("Invalid ETH address format"))
     }
 
     pub async fn assert_valid_dop_address(&self, address: &str) -> Result<()> {
-        let res = self
-            .client
-            .get(&format!(
-                "{}/assert/dop-address?address={}",
-                self.base_url(),
-                address
-            ))
-            .send()
-            .await?;
-
-        if res.status().is_success() {
-            Ok(())
-        } else {
-            Err(anyhow!("Invalid DOP address format"))
-        }
+        address
+            .parse::<DopAddress>()
+            .map(|_| ())
+            .map_err(|_| anyhow!("Invalid DOP address format"))
     }
 }
@@ -1,7 +1,5 @@
-mod dop;
-use dop::{DopClient, DopERC20Amount};
+use dop::dop::DopClient;
 use serde_json::json;
-use std::{collections::HashMap, ffi::c_long};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -16,7 +14,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let wallet_info = engine
         .create_wallet(&mnemonic, encryption_key, None)
         .await?;
-    let dop_address = wallet_info["dopAddress"]
+    let _dop_address = wallet_info["dopAddress"]
         .as_str()
         .expect("Missing dopAddress in walletInfo");
 
@@ -1,2 +1 @@
 pub mod tx;
-pub use tx::*;
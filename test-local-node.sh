@@ -0,0 +1,23 @@
+#!/bin/bash
+set -e  # Stop on error
+
+# Runs the tests that need a local EVM node (ignored by `cargo test`)
+# against a fresh anvil instance.
+
+PORT="${ANVIL_PORT:-8545}"
+
+echo "Starting anvil on port $PORT..."
+anvil --port "$PORT" --chain-id 31337 --silent &
+ANVIL_PID=$!
+trap 'kill $ANVIL_PID' EXIT
+
+for _ in $(seq 1 30); do
+  if curl -s -X POST -H "Content-Type: application/json" \
+    --data '{"jsonrpc":"2.0","id":1,"method":"eth_chainId","params":[]}' \
+    "http://127.0.0.1:$PORT" > /dev/null; then
+    break
+  fi
+  sleep 1
+done
+
+DOP_TEST_RPC_URL="http://127.0.0.1:$PORT" cargo test --test provider_tests -- --ignored
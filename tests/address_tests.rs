@@ -0,0 +1,148 @@
+use anyhow::Result;
+use dop::dop::{Chain, DopAddress, DopClient};
+use serial_test::serial;
+
+const MOCK_DOP_ADDRESS: &str = "0zk1q8hxknrs97q8pjxaagwthzc0df99rzmhl2xnlxmgv9akv32sua0kfrv7j6fe3z53llhxknrs97q8pjxaagwthzc0df99rzmhl2xnlxmgv9akv32sua0kg0zpzts";
+const MOCK_KEY: &str = "ee6b4c702f8070c8ddea1cbb8b0f6a4a518b77fa8d3f9b68617b664550e75f64";
+
+fn key(hex: &str) -> [u8; 32] {
+    hex::decode(hex).unwrap().try_into().unwrap()
+}
+
+#[test]
+fn test_decode_mock_address() -> Result<()> {
+    let address: DopAddress = MOCK_DOP_ADDRESS.parse()?;
+
+    assert_eq!(address.version, 1);
+    assert_eq!(address.master_public_key, key(MOCK_KEY));
+    assert_eq!(address.viewing_public_key, key(MOCK_KEY));
+    assert_eq!(address.chain, None);
+    assert_eq!(address.to_string(), MOCK_DOP_ADDRESS);
+    Ok(())
+}
+
+#[test]
+fn test_chain_specific_address_round_trip() -> Result<()> {
+    let chain = Chain {
+        chain_type: 0,
+        id: 11155111,
+    };
+    let address = DopAddress::new(key(MOCK_KEY), [7u8; 32], Some(chain))?;
+    let encoded = address.to_string();
+
+    assert!(encoded.starts_with("0zk1"));
+    assert_ne!(encoded, MOCK_DOP_ADDRESS);
+    let decoded: DopAddress = encoded.parse()?;
+    assert_eq!(decoded, address);
+    assert_eq!(decoded.chain, Some(chain));
+
+    assert!(
+        DopAddress::new(
+            [0u8; 32],
+            [0u8; 32],
+            Some(Chain {
+                chain_type: 0,
+                id: 1 << 56
+            })
+        )
+        .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_invalid_addresses_rejected() {
+    // Bad checksum.
+    let mut corrupted = MOCK_DOP_ADDRESS.to_string();
+    corrupted.pop();
+    corrupted.push('q');
+    assert!(!DopAddress::is_valid(&corrupted));
+
+    // Wrong prefix, wrong checksum variant and garbage.
+    let wrong_prefix =
+        bech32::encode::<bech32::Bech32m>(bech32::Hrp::parse("1zk").unwrap(), &[1u8; 73]).unwrap();
+    assert!(!DopAddress::is_valid(&wrong_prefix));
+    let bech32_not_m = bech32::encode::<bech32::Bech32>(
+        bech32::Hrp::parse("0zk").unwrap(),
+        &hex::decode(format!("01{}{}{}", MOCK_KEY, "8d9e9693988a91ff", MOCK_KEY)).unwrap(),
+    )
+    .unwrap();
+    assert!(!DopAddress::is_valid(&bech32_not_m));
+    assert!(!DopAddress::is_valid("zkevm:0xabc...123"));
+    assert!(!DopAddress::is_valid(""));
+
+    // Unsupported version.
+    let mut payload =
+        hex::decode(format!("02{}{}{}", MOCK_KEY, "8d9e9693988a91ff", MOCK_KEY)).unwrap();
+    let v2 =
+        bech32::encode::<bech32::Bech32m>(bech32::Hrp::parse("0zk").unwrap(), &payload).unwrap();
+    assert!(!DopAddress::is_valid(&v2));
+
+    // Truncated payload.
+    payload.truncate(72);
+    let short =
+        bech32::encode::<bech32::Bech32m>(bech32::Hrp::parse("0zk").unwrap(), &payload).unwrap();
+    assert!(!DopAddress::is_valid(&short));
+}
+
+#[test]
+fn test_address_serde_and_address_data() -> Result<()> {
+    let address: DopAddress = MOCK_DOP_ADDRESS.parse()?;
+    let json = serde_json::to_string(&address)?;
+    assert_eq!(json, format!("\"{}\"", MOCK_DOP_ADDRESS));
+    assert_eq!(serde_json::from_str::<DopAddress>(&json)?, address);
+    assert!(serde_json::from_str::<DopAddress>("\"0zk1invalid\"").is_err());
+
+    let data = address.to_address_data();
+    assert_eq!(
+        data["masterPublicKey"],
+        "107840038155987585452015163184318756879563730268045790440630167370538141835108"
+    );
+    assert_eq!(data["viewingPublicKey"].as_array().unwrap().len(), 32);
+    assert_eq!(data["version"], 1);
+    assert!(data["chain"].is_null());
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_offline_address_matches_engine() -> Result<()> {
+    let mut engine = DopClient::new();
+    engine.start();
+    engine.wait_for_api_ready().await;
+    engine
+        .init_engine(
+            Some("database/test-address.db"),
+            Some("Address Engine"),
+            Some(false),
+            Some(true),
+            Some(false),
+        )
+        .await?;
+
+    let encryption_key = "0101010101010101010101010101010101010101010101010101010101010101";
+    let mnemonic = engine.generate_mnemonic(Some(12)).await?;
+    let wallet_info = engine
+        .create_wallet(&mnemonic, encryption_key, None)
+        .await?;
+    let id = wallet_info["id"].as_str().unwrap();
+    let dop_address = wallet_info["dopAddress"].as_str().unwrap();
+
+    // The wallet's keys come straight from the engine.
+    let wallet = engine.get_wallet(id).await?;
+    let address: DopAddress = dop_address.parse()?;
+    assert_eq!(
+        address.master_public_key_decimal(),
+        wallet["masterPublicKey"].as_str().unwrap()
+    );
+    let engine_viewing_key: Vec<u8> =
+        serde_json::from_value(wallet["viewingKeyPair"]["pubkey"].clone())?;
+    assert_eq!(address.viewing_public_key.to_vec(), engine_viewing_key);
+    assert_eq!(address.to_string(), dop_address);
+
+    assert!(engine.validate_dop_address(dop_address).await?);
+    engine.assert_valid_dop_address(dop_address).await?;
+
+    engine.close_engine().await?;
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use anyhow::Result;
+use dop::dop::{DopClient, WALLET_BACKUP_VERSION, WalletBackup};
+use serial_test::serial;
+use std::collections::HashMap;
+
+fn sample_backup() -> WalletBackup {
+    WalletBackup {
+        is_view_only: false,
+        mnemonic: Some("test test test test test test test test test test test junk".to_string()),
+        shareable_viewing_key: None,
+        derivation_index: Some(0),
+        creation_block_numbers: Some(HashMap::from([("Ethereum_Sepolia".to_string(), 5_944_700)])),
+        label: Some("treasury".to_string()),
+    }
+}
+
+#[test]
+fn test_backup_bytes_round_trip() -> Result<()> {
+    let backup = sample_backup();
+    let bytes = backup.to_encrypted_bytes("correct horse battery staple")?;
+
+    assert!(bytes.starts_with(b"DOPBAK"));
+    assert_eq!(bytes[6], WALLET_BACKUP_VERSION);
+
+    let restored = WalletBackup::from_encrypted_bytes(&bytes, "correct horse battery staple")?;
+    assert_eq!(restored, backup);
+    Ok(())
+}
+
+#[test]
+fn test_backup_rejects_wrong_passphrase() -> Result<()> {
+    let bytes = sample_backup().to_encrypted_bytes("passphrase")?;
+    assert!(WalletBackup::from_encrypted_bytes(&bytes, "not the passphrase").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_backup_rejects_tampering() -> Result<()> {
+    let bytes = sample_backup().to_encrypted_bytes("passphrase")?;
+
+    // Flipping a ciphertext byte breaks the tag.
+    let mut tampered = bytes.clone();
+    *tampered.last_mut().unwrap() ^= 0x01;
+    assert!(WalletBackup::from_encrypted_bytes(&tampered, "passphrase").is_err());
+
+    // The header is authenticated too, so a downgraded version is rejected.
+    let mut tampered = bytes.clone();
+    tampered[6] = WALLET_BACKUP_VERSION + 1;
+    assert!(WalletBackup::from_encrypted_bytes(&tampered, "passphrase").is_err());
+
+    assert!(WalletBackup::from_encrypted_bytes(b"not a backup", "passphrase").is_err());
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_wallet_backup_round_trip() -> Result<()> {
+    let mut engine = DopClient::new();
+    engine.start();
+    engine.wait_for_api_ready().await;
+    engine.init_engine(None, None, None, None, None).await?;
+
+    let encryption_key = "0101010101010101010101010101010101010101010101010101010101010101";
+    let new_encryption_key = "0202020202020202020202020202020202020202020202020202020202020202";
+    let mnemonic = engine.generate_mnemonic(Some(12)).await?;
+
+    let mut creation_blocks = HashMap::new();
+    creation_blocks.insert("Ethereum_Sepolia", 5_944_700);
+    let wallet_info = engine
+        .create_wallet(&mnemonic, encryption_key, Some(creation_blocks))
+        .await?;
+    let wallet_id = wallet_info["id"].as_str().unwrap().to_string();
+    let dop_address = wallet_info["dopAddress"].as_str().unwrap().to_string();
+
+    let backup = engine
+        .export_wallet_backup(
+            &wallet_id,
+            encryption_key,
+            "backup passphrase",
+            Some("main"),
+        )
+        .await?;
+    engine.delete_wallet_by_id(&wallet_id).await?;
+
+    let restored = engine
+        .restore_wallet_backup(&backup, "backup passphrase", new_encryption_key)
+        .await?;
+    assert_eq!(restored["dopAddress"].as_str(), Some(dop_address.as_str()));
+
+    let restored_id = restored["id"].as_str().unwrap();
+    let restored_mnemonic = engine
+        .get_wallet_mnemonic(restored_id, new_encryption_key)
+        .await?;
+    assert_eq!(restored_mnemonic, mnemonic);
+
+    // View-only wallets round-trip through their shareable viewing key.
+    let viewing_key = engine.get_shareable_viewing_key(restored_id).await?;
+    let view_only = engine
+        .create_view_only_wallet(encryption_key, &viewing_key, None)
+        .await?;
+    let view_only_id = view_only["id"].as_str().unwrap().to_string();
+
+    let view_only_backup = engine
+        .export_wallet_backup(&view_only_id, encryption_key, "backup passphrase", None)
+        .await?;
+    engine.delete_wallet_by_id(&view_only_id).await?;
+
+    let restored_view_only = engine
+        .restore_wallet_backup(&view_only_backup, "backup passphrase", encryption_key)
+        .await?;
+    assert_eq!(
+        restored_view_only["dopAddress"].as_str(),
+        Some(dop_address.as_str())
+    );
+
+    engine.close_engine().await?;
+    Ok(())
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use anyhow::Result;
+use common::MockBroadcaster;
+use dop::dop::{
+    BroadcastRequest, BroadcasterFeeQuote, BroadcasterTransport, Chain, ContractTransaction,
+    TransactionGasDetails, select_fee_quote,
+};
+use time::{Duration, OffsetDateTime};
+
+const USDC: &str = "0x5FbDB2315678afecb367f032d93F642f64180aa3";
+const BROADCASTER: &str = "0zk1q8hxknrs97q8pjxaagwthzc0df99rzmhl2xnlxmgv9akv32sua0kfrv7j6fe3z53llhxknrs97q8pjxaagwthzc0df99rzmhl2xnlxmgv9akv32sua0kg0zpzts";
+const SEPOLIA: Chain = Chain {
+    chain_type: 0,
+    id: 11155111,
+};
+
+/// USDC at 2000 USD per ETH: 2000 * 10^6 base units per 10^18 wei.
+fn quote(fees_id: &str, fee_per_unit_gas: &str, expires_in: Duration) -> BroadcasterFeeQuote {
+    BroadcasterFeeQuote {
+        token_address: USDC.parse().unwrap(),
+        fee_per_unit_gas: fee_per_unit_gas.to_string(),
+        expiration: OffsetDateTime::now_utc() + expires_in,
+        fees_id: fees_id.to_string(),
+        dop_address: BROADCASTER.parse().unwrap(),
+        available_wallets: 2,
+    }
+}
+
+#[test]
+fn test_fee_from_gas_estimate() -> Result<()> {
+    let quote = quote("fees-1", "2000000000", Duration::minutes(5));
+    // 0.002 ETH worst case, so 4 USDC.
+    let gas = TransactionGasDetails::type0(200_000, 10_000_000_000);
+
+    let fee = quote.fee_for(&gas)?;
+    assert_eq!(fee.amount, "4000000");
+    assert_eq!(fee.recipient_address, BROADCASTER);
+    assert_eq!(fee.token_address.to_string(), USDC);
+    assert_eq!(quote.fee_token_details(&gas)?.max_amount, "4000000");
+
+    // Fractions of a base unit round up in the broadcaster's favour.
+    let tiny = TransactionGasDetails::type0(1, 1);
+    assert_eq!(quote.fee_amount(&tiny)?, 1);
+    Ok(())
+}
+
+#[test]
+fn test_expired_quote_is_rejected() {
+    let expired = quote("fees-old", "2000000000", Duration::seconds(-1));
+    assert!(expired.is_expired());
+    let gas = TransactionGasDetails::type0(200_000, 10_000_000_000);
+    assert!(expired.fee_for(&gas).is_err());
+}
+
+#[test]
+fn test_select_cheapest_usable_quote() {
+    let mut busy = quote("fees-busy", "1000000000", Duration::minutes(5));
+    busy.available_wallets = 0;
+    let quotes = vec![
+        quote("fees-expired", "500000000", Duration::seconds(-1)),
+        busy,
+        quote("fees-pricey", "3000000000", Duration::minutes(5)),
+        quote("fees-best", "2000000000", Duration::minutes(5)),
+    ];
+    assert_eq!(select_fee_quote(&quotes).unwrap().fees_id, "fees-best");
+    assert!(select_fee_quote(&quotes[..2]).is_none());
+}
+
+#[test]
+fn test_quote_json() -> Result<()> {
+    let quote: BroadcasterFeeQuote = serde_json::from_value(serde_json::json!({
+        "tokenAddress": USDC,
+        "feePerUnitGas": "2000000000",
+        "expiration": 1_700_000_000_000u64,
+        "feesID": "fees-1",
+        "dopAddress": BROADCASTER,
+        "availableWallets": 1,
+    }))?;
+    assert_eq!(quote.expiration.unix_timestamp(), 1_700_000_000);
+    assert!(quote.is_expired());
+
+    let malformed = serde_json::from_value::<BroadcasterFeeQuote>(serde_json::json!({
+        "tokenAddress": USDC,
+        "feePerUnitGas": "2000000000",
+        "expiration": 1_700_000_000_000u64,
+        "feesID": "fees-1",
+        "dopAddress": "0zk1notanaddress",
+        "availableWallets": 1,
+    }));
+    assert!(malformed.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_submit_to_mock_broadcaster() -> Result<()> {
+    let broadcaster = MockBroadcaster::new()
+        .with_quote(SEPOLIA, quote("fees-1", "2000000000", Duration::minutes(5)))
+        .with_quote(SEPOLIA, quote("fees-2", "2500000000", Duration::minutes(5)));
+
+    let quotes = broadcaster.fee_quotes(SEPOLIA, USDC.parse()?).await?;
+    assert_eq!(quotes.len(), 2);
+    let other_chain = Chain {
+        chain_type: 0,
+        id: 1,
+    };
+    assert!(
+        broadcaster
+            .fee_quotes(other_chain, USDC.parse()?)
+            .await?
+            .is_empty()
+    );
+
+    let quote = select_fee_quote(&quotes).unwrap();
+    let transaction = ContractTransaction {
+        to: "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359".parse()?,
+        data: "0xdeadbeef".to_string(),
+        from: None,
+        value: None,
+    };
+    let request = BroadcastRequest::new(
+        SEPOLIA,
+        quote,
+        &transaction,
+        "10000000000",
+        vec!["0x01".to_string()],
+    );
+    let tx_hash = broadcaster.submit(&request).await?;
+    assert_eq!(tx_hash.len(), 66);
+
+    let submitted = broadcaster.submitted();
+    assert_eq!(submitted, vec![request.clone()]);
+    assert_eq!(submitted[0].fees_id, "fees-1");
+
+    let unknown = BroadcastRequest {
+        fees_id: "fees-unknown".to_string(),
+        ..request
+    };
+    assert!(broadcaster.submit(&unknown).await.is_err());
+    Ok(())
+}
@@ -77,3 +77,37 @@ async fn test_scan_callbacks_triggered() -> Result<(), Box<dyn std::error::Error
     engine.close_engine().await?;
     Ok(())
 }
+
+#[tokio::test]
+#[serial]
+async fn test_proof_progress_callback() -> Result<(), Box<dyn std::error::Error>> {
+    // The listener works without the Node engine; post to it directly.
+    let engine = DopClient::new();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let events_clone = events.clone();
+    engine.set_proof_progress_callback(move |event| {
+        events_clone.lock().unwrap().push(event);
+    });
+    engine.start_scan_listeners().await?;
+    sleep(Duration::from_millis(200)).await;
+
+    reqwest::Client::new()
+        .post("http://127.0.0.1:4000/proof-progress")
+        .json(&json!({
+            "proofJobId": "job-1",
+            "proofType": "transfer",
+            "progress": 42.5,
+            "status": "Proving",
+        }))
+        .send()
+        .await?
+        .error_for_status()?;
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].job_id, "job-1");
+    assert_eq!(events[0].proof_type, "transfer");
+    assert_eq!(events[0].progress, 42.5);
+    assert_eq!(events[0].status, "Proving");
+    Ok(())
+}
@@ -0,0 +1,182 @@
+// Each test crate uses only some of these helpers.
+#![allow(dead_code)]
+
+use anyhow::{Result, anyhow, bail};
+use dop::dop::{
+    BroadcastRequest, BroadcasterFeeQuote, BroadcasterTransport, Chain, EvmAddress, PoiStatus,
+};
+use serde_json::{Value, json};
+use sha3::{Digest, Keccak256};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+/// Local POI node for the integration tests. Serves the JSON-RPC methods the
+/// engine calls, reports a configurable status per blinded commitment and
+/// records submitted proofs instead of validating them.
+#[derive(Debug, Clone)]
+pub struct StubPoiNode {
+    list_keys: Vec<String>,
+    default_status: PoiStatus,
+    statuses: Arc<Mutex<HashMap<String, PoiStatus>>>,
+    submitted: Arc<Mutex<Vec<Value>>>,
+}
+
+impl StubPoiNode {
+    /// A node for `list_keys` that reports every commitment as valid.
+    pub fn new(list_keys: Vec<String>) -> Self {
+        Self {
+            list_keys,
+            default_status: PoiStatus::Valid,
+            statuses: Arc::default(),
+            submitted: Arc::default(),
+        }
+    }
+
+    pub fn with_default_status(mut self, status: PoiStatus) -> Self {
+        self.default_status = status;
+        self
+    }
+
+    /// Sets the status of `blinded_commitment` on every list.
+    pub fn set_status(&self, blinded_commitment: &str, status: PoiStatus) {
+        self.statuses
+            .lock()
+            .unwrap()
+            .insert(blinded_commitment.to_lowercase(), status);
+    }
+
+    /// Params of every proof submission, in order.
+    pub fn submitted_proofs(&self) -> Vec<Value> {
+        self.submitted.lock().unwrap().clone()
+    }
+
+    /// Serves the node on `127.0.0.1:port` and returns its URL, to be passed
+    /// to `DopClient::with_poi_node_urls`.
+    pub fn start(&self, port: u16) -> String {
+        use warp::Filter;
+
+        let node = self.clone();
+        let route = warp::post()
+            .and(warp::body::json())
+            .map(move |request: Value| warp::reply::json(&node.handle(&request)));
+        tokio::spawn(warp::serve(route).run(([127, 0, 0, 1], port)));
+        format!("http://127.0.0.1:{}", port)
+    }
+
+    /// Answers one JSON-RPC request.
+    pub fn handle(&self, request: &Value) -> Value {
+        let id = request["id"].clone();
+        let params = &request["params"];
+        let result = match request["method"].as_str().unwrap_or_default() {
+            "ppoi_node_status" => json!({ "listKeys": self.list_keys, "forNetwork": {} }),
+            "ppoi_pois_per_list" => self.pois_per_list(params),
+            "ppoi_submit_transact_proof"
+            | "ppoi_submit_legacy_transact_proofs"
+            | "ppoi_submit_single_commitment_proofs" => {
+                self.submitted.lock().unwrap().push(params.clone());
+                Value::Null
+            }
+            "ppoi_validate_txid_merkleroot" | "ppoi_validate_poi_merkleroots" => json!(true),
+            "ppoi_validated_txid" => {
+                json!({ "validatedTxidIndex": null, "validatedMerkleroot": null })
+            }
+            "ppoi_merkle_proofs" | "ppoi_blocked_shields" => json!([]),
+            method => {
+                return json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": -32601, "message": format!("Method not found: {}", method) },
+                });
+            }
+        };
+        json!({ "jsonrpc": "2.0", "id": id, "result": result })
+    }
+
+    fn pois_per_list(&self, params: &Value) -> Value {
+        let statuses = self.statuses.lock().unwrap();
+        let requested = params["blindedCommitmentDatas"]
+            .as_array()
+            .or_else(|| params["blindedCommitments"].as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        let mut result = serde_json::Map::new();
+        for entry in requested {
+            let Some(blinded_commitment) = entry["blindedCommitment"]
+                .as_str()
+                .or_else(|| entry.as_str())
+            else {
+                continue;
+            };
+            let status = statuses
+                .get(&blinded_commitment.to_lowercase())
+                .copied()
+                .unwrap_or(self.default_status);
+            let lists: serde_json::Map<_, _> = self
+                .list_keys
+                .iter()
+                .map(|key| (key.clone(), json!(status)))
+                .collect();
+            result.insert(blinded_commitment.to_string(), Value::Object(lists));
+        }
+        Value::Object(result)
+    }
+}
+
+/// In-process broadcaster for tests: serves fixed quotes and records
+/// submissions instead of sending them.
+#[derive(Debug, Clone, Default)]
+pub struct MockBroadcaster {
+    quotes: Vec<(Chain, BroadcasterFeeQuote)>,
+    submitted: Arc<Mutex<Vec<BroadcastRequest>>>,
+}
+
+impl MockBroadcaster {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_quote(mut self, chain: Chain, quote: BroadcasterFeeQuote) -> Self {
+        self.quotes.push((chain, quote));
+        self
+    }
+
+    pub fn submitted(&self) -> Vec<BroadcastRequest> {
+        self.submitted.lock().unwrap().clone()
+    }
+}
+
+impl BroadcasterTransport for MockBroadcaster {
+    async fn fee_quotes(
+        &self,
+        chain: Chain,
+        token_address: EvmAddress,
+    ) -> Result<Vec<BroadcasterFeeQuote>> {
+        Ok(self
+            .quotes
+            .iter()
+            .filter(|(c, q)| *c == chain && q.token_address == token_address)
+            .map(|(_, q)| q.clone())
+            .collect())
+    }
+
+    async fn submit(&self, request: &BroadcastRequest) -> Result<String> {
+        let quote = self
+            .quotes
+            .iter()
+            .find(|(c, q)| *c == request.chain && q.fees_id == request.fees_id)
+            .map(|(_, q)| q)
+            .ok_or_else(|| anyhow!("Unknown fees ID {}", request.fees_id))?;
+        if quote.is_expired() {
+            bail!("Broadcaster fee quote {} has expired", quote.fees_id);
+        }
+
+        self.submitted.lock().unwrap().push(request.clone());
+        Ok(format!(
+            "0x{}",
+            hex::encode(Keccak256::digest(request.data.as_bytes()))
+        ))
+    }
+}
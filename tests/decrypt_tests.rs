@@ -0,0 +1,329 @@
+use anyhow::Result;
+use dop::dop::{
+    DecryptMode, DopClient, DopERC20AmountRecipient, EncryptPoiStatus, EvmAddress, GasFeeOracle,
+    TransactionGasDetails,
+};
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use tokio::time::{Duration, sleep};
+use warp::Filter;
+
+const WETH: &str = "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359";
+const PUBLIC_WALLET: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+const RELAY_ADAPT: &str = "0x5FbDB2315678afecb367f032d93F642f64180aa3";
+
+type CallLog = Arc<Mutex<Vec<(String, Value)>>>;
+
+/// Stand-in for the Node engine that records every call and answers with
+/// fixed estimates, proofs and transactions.
+fn start_stub_engine(port: u16) -> CallLog {
+    let calls = CallLog::default();
+    let calls_clone = calls.clone();
+    let routes = warp::post()
+        .and(warp::path::param::<String>())
+        .and(warp::body::json())
+        .map(move |endpoint: String, body: Value| {
+            calls_clone
+                .lock()
+                .unwrap()
+                .push((endpoint.clone(), body.clone()));
+            let reply = if endpoint.starts_with("gas-estimate") {
+                // The broadcaster fee output costs gas in proportion to the
+                // fee, so the estimate only settles once the fee does.
+                let fee: u64 = body["feeTokenDetails"]["maxAmount"]
+                    .as_str()
+                    .map_or(0, |amount| amount.parse().unwrap());
+                json!({ "gasEstimate": (300_000 + fee).to_string() })
+            } else if endpoint.starts_with("generate") {
+                json!({
+                    "success": true,
+                    "provedTransaction": {
+                        "id": body["proofJobId"],
+                        "parametersHash": "ab12",
+                        "nullifiers": ["0x01"],
+                        "createdAt": 1_700_000_000_000u64,
+                    },
+                })
+            } else if endpoint.starts_with("populate") {
+                json!({
+                    "transaction": { "to": RELAY_ADAPT, "data": "0xd3", "from": null },
+                    "nullifiers": null,
+                })
+            } else if endpoint == "decrypt-to-origin-candidates" {
+                json!([{
+                    "txid": "0xabc",
+                    "blockNumber": 120,
+                    "timestamp": 1_700_000_000u64,
+                    "status": "blocked",
+                    "erc20AmountRecipients": [{
+                        "tokenAddress": WETH,
+                        "amount": "750",
+                        "recipientAddress": PUBLIC_WALLET,
+                    }],
+                    "nftAmountRecipients": [],
+                }])
+            } else {
+                json!({
+                    "erc20AmountRecipients": [{
+                        "tokenAddress": WETH,
+                        "amount": "750",
+                        "recipientAddress": PUBLIC_WALLET,
+                    }],
+                    "nftAmountRecipients": [],
+                })
+            };
+            warp::reply::json(&reply)
+        });
+    tokio::spawn(warp::serve(routes).run(([127, 0, 0, 1], port)));
+    calls
+}
+
+fn endpoints(calls: &CallLog) -> Vec<String> {
+    calls
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(e, _)| e.clone())
+        .collect()
+}
+
+fn weth_to_public_wallet(amount: &str) -> DopERC20AmountRecipient {
+    DopERC20AmountRecipient {
+        token_address: WETH.parse().unwrap(),
+        amount: amount.to_string(),
+        recipient_address: PUBLIC_WALLET.to_string(),
+    }
+}
+
+fn gas_prices() -> TransactionGasDetails {
+    TransactionGasDetails::type0(0, 1_000_000_000)
+}
+
+type NoFee = fn(&TransactionGasDetails) -> Result<DopERC20AmountRecipient>;
+
+/// Adds 20% to every estimate, like `ProviderGasOracle` does by default.
+struct MarginOracle;
+
+impl GasFeeOracle for MarginOracle {
+    async fn gas_details(
+        &self,
+        _network_name: &str,
+        gas_estimate: &str,
+    ) -> Result<TransactionGasDetails> {
+        let gas_estimate: u64 = gas_estimate.parse()?;
+        Ok(gas_prices().with_gas_estimate(&(gas_estimate * 6 / 5).to_string()))
+    }
+}
+
+#[tokio::test]
+async fn test_decrypt_modes_use_matching_endpoints() -> Result<()> {
+    let calls = start_stub_engine(3109);
+    sleep(Duration::from_millis(200)).await;
+    let engine = DopClient::with_port(3109);
+
+    let result = engine
+        .decrypt("Ethereum_Sepolia", "wallet-id", "encryption-key")
+        .erc20_recipient(weth_to_public_wallet("1000"))
+        .value("5")
+        .decrypt(&gas_prices(), None::<NoFee>)
+        .await?;
+    assert_eq!(
+        endpoints(&calls),
+        [
+            "gas-estimate-for-unproven-decrypt",
+            "generate-decrypt-proof",
+            "populate-proved-decrypt",
+        ]
+    );
+    assert_eq!(calls.lock().unwrap()[1].1["value"], "5");
+    assert_eq!(result.gas_details.gas_estimate(), "300000");
+    assert_eq!(result.nullifiers, ["0x01"]);
+    assert_eq!(result.transaction.to, RELAY_ADAPT.parse::<EvmAddress>()?);
+
+    calls.lock().unwrap().clear();
+    engine
+        .decrypt("Ethereum_Sepolia", "wallet-id", "encryption-key")
+        .mode(DecryptMode::BaseTokenToAddress)
+        .erc20_recipient(weth_to_public_wallet("1000"))
+        .decrypt(&gas_prices(), None::<NoFee>)
+        .await?;
+    assert_eq!(
+        endpoints(&calls),
+        [
+            "gas-estimate-for-unproven-decrypt-base-token",
+            "generate-decrypt-base-token-proof",
+            "populate-proved-decrypt-base-token",
+        ]
+    );
+    let populate = calls.lock().unwrap()[2].1.clone();
+    assert_eq!(populate["publicWalletAddress"], PUBLIC_WALLET);
+    assert_eq!(
+        populate["wrappedERC20Amount"],
+        json!({ "tokenAddress": WETH, "amount": "1000" })
+    );
+    assert_eq!(populate["gasDetails"]["gasEstimate"], "300000");
+
+    calls.lock().unwrap().clear();
+    let result = engine
+        .decrypt("Ethereum_Sepolia", "wallet-id", "encryption-key")
+        .mode(DecryptMode::ToOrigin("0xabc".to_string()))
+        .decrypt(&gas_prices(), None::<NoFee>)
+        .await?;
+    assert_eq!(
+        endpoints(&calls),
+        [
+            "get-recipients-for-decrypt-to-origin",
+            "gas-estimate-for-unproven-decrypt-to-origin",
+            "generate-decrypt-to-origin-proof",
+            "populate-proved-decrypt-to-origin",
+        ]
+    );
+    assert_eq!(result.erc20_recipients[0].amount, "750");
+    let populate = calls.lock().unwrap()[3].1.clone();
+    assert_eq!(populate["erc20AmountRecipients"][0]["amount"], "750");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_decrypt_rejects_invalid_parameters() -> Result<()> {
+    let engine = DopClient::new();
+    let decrypt = || engine.decrypt("Ethereum_Sepolia", "wallet-id", "encryption-key");
+
+    let err = decrypt()
+        .erc20_recipient(weth_to_public_wallet("1"))
+        .populate(gas_prices())
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("must be proved"));
+
+    let err = decrypt().prove().await.unwrap_err();
+    assert_eq!(err.to_string(), "Decrypt has no recipients");
+
+    let mut not_evm = weth_to_public_wallet("1");
+    not_evm.recipient_address = "0zk1qyk9".to_string();
+    let err = decrypt()
+        .erc20_recipient(not_evm)
+        .prove()
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("Invalid EVM recipient 0zk1qyk9")
+    );
+
+    let err = decrypt()
+        .erc20_recipient(weth_to_public_wallet("1"))
+        .value("-1")
+        .prove()
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "Invalid value: -1");
+
+    let err = decrypt()
+        .mode(DecryptMode::BaseTokenToAddress)
+        .erc20_recipients(vec![weth_to_public_wallet("1"), weth_to_public_wallet("2")])
+        .prove()
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("exactly one ERC20 recipient"));
+
+    let err = decrypt()
+        .mode(DecryptMode::ToOrigin("0xabc".to_string()))
+        .decrypt(
+            &gas_prices(),
+            Some(|_: &TransactionGasDetails| Ok(weth_to_public_wallet("1"))),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("cannot pay a broadcaster fee"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_decrypt_to_origin_candidate() -> Result<()> {
+    let calls = start_stub_engine(3110);
+    sleep(Duration::from_millis(200)).await;
+    let engine = DopClient::with_port(3110);
+
+    let candidates = engine
+        .decrypt_to_origin_candidates("Ethereum_Sepolia", "wallet-id")
+        .await?;
+    assert_eq!(candidates.len(), 1);
+    let candidate = &candidates[0];
+    assert_eq!(candidate.status, EncryptPoiStatus::Blocked);
+    assert!(candidate.is_stuck());
+    assert_eq!(candidate.block_number, Some(120));
+    assert_eq!(candidate.erc20_recipients[0].amount, "750");
+    assert_eq!(calls.lock().unwrap()[0].1["dopWalletID"], "wallet-id");
+
+    calls.lock().unwrap().clear();
+    let result = engine
+        .decrypt("Ethereum_Sepolia", "wallet-id", "encryption-key")
+        .to_origin(candidate)
+        .decrypt(&gas_prices(), None::<NoFee>)
+        .await?;
+    // The listed recipients are used as-is, without another lookup.
+    assert_eq!(
+        endpoints(&calls),
+        [
+            "gas-estimate-for-unproven-decrypt-to-origin",
+            "generate-decrypt-to-origin-proof",
+            "populate-proved-decrypt-to-origin",
+        ]
+    );
+    assert_eq!(calls.lock().unwrap()[1].1["originalEncryptTxid"], "0xabc");
+    assert_eq!(result.erc20_recipients[0].recipient_address, PUBLIC_WALLET);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_broadcaster_fee_settles_on_raw_estimate() -> Result<()> {
+    let calls = start_stub_engine(3116);
+    sleep(Duration::from_millis(200)).await;
+    let engine = DopClient::with_port(3116);
+
+    // A fixed fee settles on the second estimate that includes it, even
+    // though the oracle inflates every estimate it prices.
+    let result = engine
+        .decrypt("Ethereum_Sepolia", "wallet-id", "encryption-key")
+        .erc20_recipient(weth_to_public_wallet("1000"))
+        .decrypt(
+            &MarginOracle,
+            Some(|_: &TransactionGasDetails| Ok(weth_to_public_wallet("10"))),
+        )
+        .await?;
+    assert_eq!(
+        endpoints(&calls),
+        [
+            "gas-estimate-for-unproven-decrypt",
+            "gas-estimate-for-unproven-decrypt",
+            "gas-estimate-for-unproven-decrypt",
+            "generate-decrypt-proof",
+            "populate-proved-decrypt",
+        ]
+    );
+    assert_eq!(result.gas_details.gas_estimate(), "360012");
+    assert_eq!(result.broadcaster_fee.unwrap().amount, "10");
+
+    // A fee that grows with the estimate never settles, and nothing is
+    // proved with it.
+    calls.lock().unwrap().clear();
+    let err = engine
+        .decrypt("Ethereum_Sepolia", "wallet-id", "encryption-key")
+        .erc20_recipient(weth_to_public_wallet("1000"))
+        .decrypt(
+            &MarginOracle,
+            Some(|gas_details: &TransactionGasDetails| {
+                Ok(weth_to_public_wallet(gas_details.gas_estimate()))
+            }),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("did not settle"));
+    assert!(
+        endpoints(&calls)
+            .iter()
+            .all(|e| e == "gas-estimate-for-unproven-decrypt")
+    );
+    Ok(())
+}
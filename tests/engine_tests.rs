@@ -101,7 +101,7 @@ async fn test_load_provider() -> Result<()> {
 
     let polling_interval_ms = 10_000; // 10 seconds
 
-    let result = engine
+    let _result = engine
         .load_provider(config, network_name, Some(polling_interval_ms))
         .await?;
 
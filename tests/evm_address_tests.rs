@@ -0,0 +1,71 @@
+use anyhow::Result;
+use dop::dop::{DopERC20AmountRecipient, EvmAddress};
+
+// Test vectors from EIP-55.
+const CHECKSUMMED: [&str; 4] = [
+    "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+    "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+    "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+    "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+];
+
+#[test]
+fn test_evm_address_checksum_vectors() -> Result<()> {
+    for expected in CHECKSUMMED {
+        let address: EvmAddress = expected.parse()?;
+        assert_eq!(address.to_string(), expected);
+
+        let lower: EvmAddress = expected.to_lowercase().parse()?;
+        assert_eq!(lower, address);
+
+        let upper: EvmAddress = format!("0x{}", &expected[2..].to_uppercase()).parse()?;
+        assert_eq!(upper, address);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_evm_address_rejects_invalid() {
+    // Bad checksum: one letter's case flipped.
+    assert!(!EvmAddress::is_valid(
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD"
+    ));
+    assert!(!EvmAddress::is_valid(
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA"
+    ));
+    assert!(!EvmAddress::is_valid("0xToken"));
+    assert!(!EvmAddress::is_valid(
+        "0zk1qyk9nn28x0u3rwn5pknglda68wrn7gw6anjw8gg94mcj6eq5u48tlrv7j6fe3z53lama02nutwtcqc979wnce0qwly4y7w4rls5cq040g7z8eagshxrw5ajy990"
+    ));
+}
+
+#[test]
+fn test_evm_address_accepts_missing_prefix() -> Result<()> {
+    let address: EvmAddress = "5aaeb6053f3e94c9b9a09f33669435e7ef1beaed".parse()?;
+    assert_eq!(address.to_string(), CHECKSUMMED[0]);
+    assert!(EvmAddress::ZERO.is_zero());
+    assert!(!address.is_zero());
+    Ok(())
+}
+
+#[test]
+fn test_evm_address_serde() -> Result<()> {
+    let json = serde_json::json!({
+        "tokenAddress": "0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359",
+        "amount": "1000",
+        "recipientAddress": CHECKSUMMED[0],
+    });
+    let recipient: DopERC20AmountRecipient = serde_json::from_value(json)?;
+    assert_eq!(recipient.token_address.to_string(), CHECKSUMMED[1]);
+
+    let value = serde_json::to_value(&recipient)?;
+    assert_eq!(value["tokenAddress"], CHECKSUMMED[1]);
+
+    let bad = serde_json::json!({
+        "tokenAddress": "0xFB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "amount": "1000",
+        "recipientAddress": CHECKSUMMED[0],
+    });
+    assert!(serde_json::from_value::<DopERC20AmountRecipient>(bad).is_err());
+    Ok(())
+}
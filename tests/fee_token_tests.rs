@@ -0,0 +1,206 @@
+use anyhow::Result;
+use dop::dop::{
+    BroadcasterFeeQuote, DopClient, DopERC20Amount, DopERC20AmountRecipient, EvmAddress,
+    FeeTokenRejection, FeeTokenSelector, TransactionGasDetails,
+};
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use time::{Duration, OffsetDateTime};
+use warp::Filter;
+
+const USDC: &str = "0x5FbDB2315678afecb367f032d93F642f64180aa3";
+const DAI: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+const WETH: &str = "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359";
+const BROADCASTER: &str = "0zk1q8hxknrs97q8pjxaagwthzc0df99rzmhl2xnlxmgv9akv32sua0kfrv7j6fe3z53llhxknrs97q8pjxaagwthzc0df99rzmhl2xnlxmgv9akv32sua0kg0zpzts";
+
+fn token(address: &str) -> EvmAddress {
+    address.parse().unwrap()
+}
+
+fn quote(address: &str, fee_per_unit_gas: &str) -> BroadcasterFeeQuote {
+    BroadcasterFeeQuote {
+        token_address: token(address),
+        fee_per_unit_gas: fee_per_unit_gas.to_string(),
+        expiration: OffsetDateTime::now_utc() + Duration::minutes(5),
+        fees_id: format!("fees-{}", &address[2..6]),
+        dop_address: BROADCASTER.parse().unwrap(),
+        available_wallets: 1,
+    }
+}
+
+fn balance(address: &str, amount: &str) -> DopERC20Amount {
+    DopERC20Amount {
+        token_address: token(address),
+        amount: amount.to_string(),
+    }
+}
+
+/// 0.002 ETH worst case: 4 USDC, 4.2 DAI or 0.0022 WETH.
+fn quotes() -> Vec<BroadcasterFeeQuote> {
+    vec![
+        quote(USDC, "2000000000"),
+        quote(WETH, "1100000000000000000"),
+        quote(DAI, "2100000000000000000000"),
+    ]
+}
+
+fn gas() -> TransactionGasDetails {
+    TransactionGasDetails::type0(200_000, 10_000_000_000)
+}
+
+fn selector() -> Result<FeeTokenSelector> {
+    let selector = FeeTokenSelector::new([
+        balance(USDC, "10000000"),
+        balance(DAI, "100000000000000000000"),
+        balance(WETH, "1000000000000000000"),
+    ])?
+    .spending(&[DopERC20AmountRecipient {
+        token_address: token(USDC),
+        amount: "8000000".to_string(),
+        recipient_address: BROADCASTER.to_string(),
+    }])?;
+    Ok(selector)
+}
+
+#[test]
+fn test_chooses_cheapest_affordable_token() -> Result<()> {
+    let selection = selector()?
+        .token_value(token(USDC), 6, 1.0)
+        .token_value(token(DAI), 18, 1.0)
+        .token_value(token(WETH), 18, 2000.0)
+        .select(&quotes(), &gas());
+
+    let choice = selection.choice.as_ref().unwrap();
+    assert_eq!(choice.quote.token_address, token(DAI));
+    assert_eq!(choice.fee_token_details.max_amount, "4200000000000000000");
+    assert_eq!(choice.broadcaster_fee.amount, "4200000000000000000");
+    assert_eq!(choice.broadcaster_fee.recipient_address, BROADCASTER);
+
+    // WETH was affordable but cost 4.4, USDC is needed for the transfer.
+    assert_eq!(selection.passed_over.len(), 2);
+    assert_eq!(selection.passed_over[0].0.token_address, token(WETH));
+    assert!(matches!(
+        selection.passed_over[0].1,
+        FeeTokenRejection::NotCheapest { cost: Some(_), .. }
+    ));
+    assert_eq!(
+        selection.passed_over[1].1,
+        FeeTokenRejection::InsufficientBalance {
+            needed: 12_000_000,
+            available: 10_000_000,
+        }
+    );
+
+    let explanation = selection.explanation();
+    assert!(explanation.starts_with(&format!("chose {}", DAI)));
+    assert!(explanation.contains("only 10000000 is spendable"));
+    Ok(())
+}
+
+#[test]
+fn test_without_values_keeps_quote_order() -> Result<()> {
+    let selection = selector()?.select(&quotes(), &gas());
+    assert_eq!(selection.choice.unwrap().quote.token_address, token(WETH));
+    Ok(())
+}
+
+#[test]
+fn test_unusable_quotes_are_explained() -> Result<()> {
+    let mut expired = quote(DAI, "1");
+    expired.expiration = OffsetDateTime::now_utc() - Duration::seconds(1);
+    let mut busy = quote(WETH, "1");
+    busy.available_wallets = 0;
+    let broken = quote(WETH, "cheap");
+
+    let selection = selector()?.select(&[expired, busy, broken, quote(USDC, "2000000000")], &gas());
+    assert!(selection.choice.is_none());
+    let reasons: Vec<_> = selection
+        .passed_over
+        .iter()
+        .map(|(_, r)| r.clone())
+        .collect();
+    assert_eq!(reasons[0], FeeTokenRejection::Expired);
+    assert_eq!(reasons[1], FeeTokenRejection::NoAvailableWallets);
+    assert!(matches!(reasons[2], FeeTokenRejection::InvalidQuote(_)));
+
+    let err = selection.into_choice().unwrap_err().to_string();
+    assert!(err.starts_with("no quote can be paid from this wallet"));
+    assert!(err.contains("quote has expired"));
+    Ok(())
+}
+
+#[test]
+fn test_check_fee_against_balance() -> Result<()> {
+    let selector = selector()?;
+    let fee = |amount: &str| DopERC20AmountRecipient {
+        token_address: token(USDC),
+        amount: amount.to_string(),
+        recipient_address: BROADCASTER.to_string(),
+    };
+    assert!(selector.check_fee(&fee("2000000")).is_ok());
+    assert_eq!(
+        selector.check_fee(&fee("2000001")),
+        Err(FeeTokenRejection::InsufficientBalance {
+            needed: 10_000_001,
+            available: 10_000_000,
+        })
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_transfer_fails_before_proving_when_settled_fee_is_unaffordable() -> Result<()> {
+    // The estimate grows once the fee output is added, and the fee with it.
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let calls_clone = calls.clone();
+    let routes = warp::post()
+        .and(warp::path::param::<String>())
+        .and(warp::body::json())
+        .map(move |endpoint: String, body: Value| {
+            calls_clone.lock().unwrap().push(endpoint);
+            let gas_estimate = if body["feeTokenDetails"].is_null() {
+                "200000"
+            } else {
+                "300000"
+            };
+            warp::reply::json(&json!({ "gasEstimate": gas_estimate }))
+        });
+    tokio::spawn(warp::serve(routes).run(([127, 0, 0, 1], 3119)));
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    let engine = DopClient::with_port(3119);
+
+    // 4 USDC at the first estimate, 6 USDC once it settles.
+    let usdc = quote(USDC, "2000000000");
+    let budget = FeeTokenSelector::new([balance(USDC, "5000000")])?;
+    let prices = TransactionGasDetails::type0(0, 10_000_000_000);
+    assert!(
+        budget
+            .select(std::slice::from_ref(&usdc), &gas())
+            .choice
+            .is_some()
+    );
+
+    let err = engine
+        .private_transfer("Ethereum_Sepolia", "wallet-id", "encryption-key")
+        .erc20_recipient(DopERC20AmountRecipient {
+            token_address: token(DAI),
+            amount: "1000".to_string(),
+            recipient_address: BROADCASTER.to_string(),
+        })
+        .fee_budget(budget)
+        .transfer(
+            &prices,
+            Some(|gas: &TransactionGasDetails| usdc.fee_for(gas)),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("needs 6000000 but only 5000000"));
+    assert!(
+        calls
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|e| e == "gas-estimate-for-unproven-transfer")
+    );
+    Ok(())
+}
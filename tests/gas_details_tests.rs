@@ -0,0 +1,92 @@
+use anyhow::Result;
+use dop::dop::{DopTransactionGasEstimateResponse, TransactionGasDetails};
+use serde_json::json;
+
+#[test]
+fn test_numeric_constructors() -> Result<()> {
+    let legacy = TransactionGasDetails::type0(21_000, 2_000_000_000);
+    assert_eq!(
+        serde_json::to_value(&legacy)?,
+        json!({ "evmGasType": "0", "gasEstimate": "21000", "gasPrice": "2000000000" })
+    );
+    assert_eq!(TransactionGasDetails::type1(21_000, 1).evm_gas_type(), 1);
+
+    let eip1559 = TransactionGasDetails::type2(21_000, 30_000_000_000, 2_000_000_000)?;
+    assert_eq!(eip1559.evm_gas_type(), 2);
+    assert_eq!(eip1559.gas_estimate(), "21000");
+
+    let err = TransactionGasDetails::type2(21_000, 1_000, 2_000).unwrap_err();
+    assert!(err.to_string().contains("exceeds"));
+    Ok(())
+}
+
+#[test]
+fn test_max_cost() -> Result<()> {
+    assert_eq!(
+        TransactionGasDetails::type0(21_000, 2_000_000_000).max_cost()?,
+        42_000_000_000_000
+    );
+    assert_eq!(
+        TransactionGasDetails::type2(100_000, 30_000_000_000, 2_000_000_000)?.max_cost()?,
+        3_000_000_000_000_000
+    );
+    assert!(
+        TransactionGasDetails::type0(u128::MAX, 2)
+            .max_cost()
+            .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_with_gas_estimate() -> Result<()> {
+    let response: DopTransactionGasEstimateResponse = serde_json::from_value(json!({
+        "gasEstimate": "350000",
+        "broadcasterFeeCommitment": null,
+    }))?;
+    let details = TransactionGasDetails::type2(0, 30_000_000_000, 2_000_000_000)?
+        .with_gas_estimate(&response.gas_estimate);
+    assert_eq!(details.gas_estimate(), "350000");
+    assert_eq!(details.max_fee_per_gas()?, 30_000_000_000);
+    Ok(())
+}
+
+#[test]
+fn test_validate_string_fields() {
+    let bad_priority = TransactionGasDetails::Type2 {
+        gas_estimate: "21000".to_string(),
+        max_fee_per_gas: "10".to_string(),
+        max_priority_fee_per_gas: "11".to_string(),
+    };
+    assert!(bad_priority.validate().is_err());
+
+    let not_a_number = TransactionGasDetails::Type0 {
+        gas_estimate: "21000".to_string(),
+        gas_price: "0x77359400".to_string(),
+    };
+    assert!(not_a_number.validate().is_err());
+}
+
+#[test]
+fn test_convert_between_gas_types() -> Result<()> {
+    let eip1559 = TransactionGasDetails::type2(50_000, 30_000_000_000, 2_000_000_000)?;
+    let legacy = eip1559.clone().into_legacy();
+    assert_eq!(legacy.evm_gas_type(), 0);
+    assert_eq!(legacy.gas_estimate(), "50000");
+    assert_eq!(legacy.max_cost()?, eip1559.max_cost()?);
+
+    match TransactionGasDetails::type1(50_000, 5_000_000_000).into_gas_type(2)? {
+        TransactionGasDetails::Type2 {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            ..
+        } => {
+            assert_eq!(max_fee_per_gas, "5000000000");
+            assert_eq!(max_priority_fee_per_gas, "5000000000");
+        }
+        other => panic!("expected type 2, got {:?}", other),
+    }
+
+    assert!(legacy.into_gas_type(3).is_err());
+    Ok(())
+}
@@ -0,0 +1,160 @@
+use anyhow::Result;
+use dop::dop::{
+    DopClient, GasFeeData, GasFeeOracle, GasPriceOptions, GasSpeed, TransactionGasDetails,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::time::{Duration, sleep};
+use warp::Filter;
+
+fn fee_data(value: serde_json::Value) -> GasFeeData {
+    serde_json::from_value(value).unwrap()
+}
+
+#[test]
+fn test_eip1559_details_from_fee_history() -> Result<()> {
+    let data = fee_data(json!({
+        "evmGasType": 2,
+        "gasPrice": "12000000000",
+        "baseFeePerGas": "10000000000",
+        "maxPriorityFeePerGas": "1500000000",
+        "priorityFeePercentiles": ["1000000000", "2000000000", "3000000000"],
+    }));
+
+    let fast = GasPriceOptions::default().speed(GasSpeed::Fast);
+    match data.gas_details("100000", &fast)? {
+        TransactionGasDetails::Type2 {
+            gas_estimate,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        } => {
+            assert_eq!(gas_estimate, "120000");
+            assert_eq!(max_priority_fee_per_gas, "3000000000");
+            // Twice the base fee plus the priority fee.
+            assert_eq!(max_fee_per_gas, "23000000000");
+        }
+        other => panic!("expected type 2, got {:?}", other),
+    }
+
+    let tight = GasPriceOptions {
+        speed: GasSpeed::Slow,
+        gas_estimate_margin_percent: 0,
+        base_fee_margin_percent: 25,
+        ..Default::default()
+    };
+    match data.gas_details("100000", &tight)? {
+        TransactionGasDetails::Type2 {
+            gas_estimate,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        } => {
+            assert_eq!(gas_estimate, "100000");
+            assert_eq!(max_priority_fee_per_gas, "1000000000");
+            assert_eq!(max_fee_per_gas, "13500000000");
+        }
+        other => panic!("expected type 2, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_eip1559_without_fee_history() -> Result<()> {
+    let data = fee_data(json!({
+        "evmGasType": 2,
+        "gasPrice": null,
+        "baseFeePerGas": "10000000000",
+        "maxPriorityFeePerGas": "2000000000",
+        "priorityFeePercentiles": null,
+    }));
+    match data.gas_details("21000", &GasPriceOptions::default().speed(GasSpeed::Fast))? {
+        TransactionGasDetails::Type2 {
+            max_priority_fee_per_gas,
+            ..
+        } => assert_eq!(max_priority_fee_per_gas, "2500000000"),
+        other => panic!("expected type 2, got {:?}", other),
+    }
+    Ok(())
+}
+
+#[test]
+fn test_legacy_details_use_gas_price() -> Result<()> {
+    let data = fee_data(json!({
+        "evmGasType": 0,
+        "gasPrice": "5000000000",
+        "baseFeePerGas": null,
+        "maxPriorityFeePerGas": null,
+        "priorityFeePercentiles": null,
+    }));
+    match data.gas_details("50000", &GasPriceOptions::default().speed(GasSpeed::Slow))? {
+        TransactionGasDetails::Type0 {
+            gas_estimate,
+            gas_price,
+        } => {
+            assert_eq!(gas_estimate, "60000");
+            // 90% for slow, plus the 10% margin.
+            assert_eq!(gas_price, "4950000000");
+        }
+        other => panic!("expected type 0, got {:?}", other),
+    }
+
+    let no_price = fee_data(json!({
+        "evmGasType": 1,
+        "gasPrice": null,
+        "baseFeePerGas": null,
+        "maxPriorityFeePerGas": null,
+        "priorityFeePercentiles": null,
+    }));
+    assert!(
+        no_price
+            .gas_details("50000", &GasPriceOptions::default())
+            .is_err()
+    );
+    assert!(
+        data.gas_details("lots", &GasPriceOptions::default())
+            .is_err()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_provider_gas_oracle() -> Result<()> {
+    let seen = Arc::new(Mutex::new(HashMap::new()));
+    let seen_clone = seen.clone();
+    let route = warp::get()
+        .and(warp::path("gas-fee-data"))
+        .and(warp::query::<HashMap<String, String>>())
+        .map(move |query: HashMap<String, String>| {
+            *seen_clone.lock().unwrap() = query;
+            warp::reply::json(&json!({
+                "evmGasType": 1,
+                "gasPrice": "1000000000",
+                "baseFeePerGas": "900000000",
+                "maxPriorityFeePerGas": "100000000",
+                "priorityFeePercentiles": null,
+            }))
+        });
+    tokio::spawn(warp::serve(route).run(([127, 0, 0, 1], 3108)));
+    sleep(Duration::from_millis(200)).await;
+    let engine = DopClient::with_port(3108);
+
+    let oracle = engine.gas_oracle(GasPriceOptions {
+        send_with_public_wallet: false,
+        ..Default::default()
+    });
+    match oracle.gas_details("Ethereum_Sepolia", "200000").await? {
+        TransactionGasDetails::Type1 {
+            gas_estimate,
+            gas_price,
+        } => {
+            assert_eq!(gas_estimate, "240000");
+            assert_eq!(gas_price, "1100000000");
+        }
+        other => panic!("expected type 1, got {:?}", other),
+    }
+
+    let query = seen.lock().unwrap();
+    assert_eq!(query["networkName"], "Ethereum_Sepolia");
+    assert_eq!(query["sendWithPublicWallet"], "false");
+    Ok(())
+}
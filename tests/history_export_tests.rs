@@ -0,0 +1,295 @@
+use anyhow::Result;
+use dop::dop::{
+    Chain, DopClient, EvmAddress, ExportFormat, HistoryExportOptions, TransactionHistoryEntry,
+    format_token_amount, history_report_rows, write_history_report,
+};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use time::macros::datetime;
+use tokio::time::{Duration, sleep};
+use warp::{Filter, http::StatusCode};
+
+const USDC: &str = "0x5FbDB2315678afecb367f032d93F642f64180aa3";
+const WETH: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+const SEPOLIA: Chain = Chain {
+    chain_type: 0,
+    id: 11155111,
+};
+
+fn token(address: &str) -> EvmAddress {
+    address.parse().unwrap()
+}
+
+/// Newest first, as the engine returns them.
+fn history() -> Vec<Value> {
+    vec![
+        json!({
+            "txid": "0x03",
+            "blockNumber": 300,
+            "timestamp": 1_700_200_000u64,
+            "category": "decrypt",
+            "tokenAmounts": [{
+                "tokenAddress": WETH,
+                "amount": "500000000000000000",
+                "counterparty": "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+                "memoText": null,
+            }],
+            "broadcasterFee": null,
+        }),
+        json!({
+            "txid": "0x02",
+            "blockNumber": 200,
+            "timestamp": 1_700_100_000u64,
+            "category": "transfer-out",
+            "tokenAmounts": [{
+                "tokenAddress": USDC,
+                "amount": "1500000",
+                "counterparty": "0zk1qexample",
+                "memoText": "invoice 7, \"March\"",
+            }],
+            "broadcasterFee": {
+                "tokenAddress": USDC,
+                "amount": "2500",
+                "counterparty": null,
+                "memoText": null,
+            },
+        }),
+        json!({
+            "txid": "0x01",
+            "blockNumber": 100,
+            "timestamp": 1_700_000_000u64,
+            "category": "encrypt",
+            "tokenAmounts": [{
+                "tokenAddress": USDC,
+                "amount": "10000000",
+                "counterparty": null,
+                "memoText": null,
+            }],
+            "broadcasterFee": null,
+        }),
+    ]
+}
+
+/// Stand-in for the Node engine: serves `history()` a page at a time,
+/// knows the decimals of WETH only, lists `stored` as the stored wallets and
+/// records deleted wallets.
+fn start_stub_engine(port: u16, stored: &[&str]) -> Arc<Mutex<Vec<String>>> {
+    let deleted = Arc::new(Mutex::new(Vec::new()));
+
+    let history_route = warp::get()
+        .and(warp::path!("wallet" / String / "transaction-history"))
+        .and(warp::query::<HashMap<String, String>>())
+        .map(|_id: String, query: HashMap<String, String>| {
+            assert_eq!(
+                serde_json::from_str::<Value>(&query["chain"]).unwrap(),
+                json!(SEPOLIA)
+            );
+            let all = history();
+            let offset: usize = query["offset"].parse().unwrap();
+            let limit: usize = query.get("limit").map_or(all.len(), |l| l.parse().unwrap());
+            let items: Vec<Value> = all.iter().skip(offset).take(limit).cloned().collect();
+            warp::reply::json(&json!({ "total": all.len(), "items": items }))
+        });
+    let decimals_route = warp::get()
+        .and(warp::path("token-decimals"))
+        .and(warp::query::<HashMap<String, String>>())
+        .map(|query: HashMap<String, String>| {
+            if query["tokenAddress"] == WETH {
+                warp::reply::with_status(
+                    warp::reply::json(&json!({ "decimals": 18 })),
+                    StatusCode::OK,
+                )
+            } else {
+                warp::reply::with_status(
+                    warp::reply::json(&"Failed to read token decimals"),
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                )
+            }
+        });
+    let chain_route = warp::get()
+        .and(warp::path("network-chain"))
+        .and(warp::query::<HashMap<String, String>>())
+        .map(|query: HashMap<String, String>| {
+            assert_eq!(query["networkName"], "Ethereum_Sepolia");
+            warp::reply::json(&SEPOLIA)
+        });
+    let view_only_route = warp::post()
+        .and(warp::path!("wallet" / "view-only"))
+        .map(|| warp::reply::json(&json!({ "id": "view-only-id" })));
+    let refresh_route = warp::post()
+        .and(warp::path("refresh-balances"))
+        .map(warp::reply);
+    let stored: Vec<String> = stored.iter().map(|id| id.to_string()).collect();
+    let stored_route = warp::get()
+        .and(warp::path!("wallets" / "stored"))
+        .map(move || warp::reply::json(&json!({ "walletIds": stored })));
+    let deleted_clone = deleted.clone();
+    let delete_route = warp::delete()
+        .and(warp::path!("wallet" / String / "delete"))
+        .map(move |id: String| {
+            deleted_clone.lock().unwrap().push(id);
+            StatusCode::NO_CONTENT
+        });
+
+    let routes = history_route
+        .or(decimals_route)
+        .or(chain_route)
+        .or(view_only_route)
+        .or(refresh_route)
+        .or(stored_route)
+        .or(delete_route);
+    tokio::spawn(warp::serve(routes).run(([127, 0, 0, 1], port)));
+    deleted
+}
+
+#[test]
+fn test_format_token_amount() -> Result<()> {
+    assert_eq!(format_token_amount("1500000", 6)?, "1.500000");
+    assert_eq!(format_token_amount("2500", 6)?, "0.002500");
+    assert_eq!(format_token_amount("42", 0)?, "42");
+    assert_eq!(format_token_amount("0", 2)?, "0.00");
+    assert!(format_token_amount("-1", 6).is_err());
+    assert!(format_token_amount("1.5", 6).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_csv_report_separates_fees() -> Result<()> {
+    let entries: Vec<TransactionHistoryEntry> = serde_json::from_value(json!(history()))?;
+    let decimals = HashMap::from([(token(USDC), 6), (token(WETH), 18)]);
+    let rows = history_report_rows(&entries[1..2], &decimals)?;
+
+    let mut out = Vec::new();
+    write_history_report(&rows, ExportFormat::Csv, &mut out)?;
+    let csv = String::from_utf8(out)?;
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(
+        lines[0],
+        "txid,block_number,timestamp,category,entry_type,token_address,decimals,amount,raw_amount,counterparty,memo"
+    );
+    assert_eq!(
+        lines[1],
+        format!(
+            "0x02,200,2023-11-16T02:00:00Z,transfer-out,amount,{},6,1.500000,1500000,0zk1qexample,\"invoice 7, \"\"March\"\"\"",
+            USDC
+        )
+    );
+    assert_eq!(
+        lines[2],
+        format!(
+            "0x02,200,2023-11-16T02:00:00Z,transfer-out,broadcaster_fee,{},6,0.002500,2500,,",
+            USDC
+        )
+    );
+    assert_eq!(lines.len(), 3);
+    Ok(())
+}
+
+#[test]
+fn test_report_requires_decimals() -> Result<()> {
+    let entries: Vec<TransactionHistoryEntry> = serde_json::from_value(json!(history()))?;
+    let err = history_report_rows(&entries, &HashMap::new()).unwrap_err();
+    assert!(err.to_string().contains("Missing decimals"));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_export_json_lines_for_period() -> Result<()> {
+    start_stub_engine(3105, &[]);
+    sleep(Duration::from_millis(200)).await;
+    let engine = DopClient::with_port(3105);
+
+    let options = HistoryExportOptions::new(ExportFormat::JsonLines)
+        .period(
+            datetime!(2023-11-16 0:00 UTC),
+            datetime!(2023-11-18 0:00 UTC),
+        )
+        .token_decimals(token(USDC), 6);
+
+    let mut out = Vec::new();
+    let written = engine
+        .export_transaction_history("wallet-id", "Ethereum_Sepolia", &options, &mut out)
+        .await?;
+
+    // The encrypt on 2023-11-14 is outside the period.
+    assert_eq!(written, 3);
+    let rows: Vec<Value> = String::from_utf8(out)?
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(rows.len(), 3);
+
+    // Oldest first, fee right after the amounts of its transaction.
+    assert_eq!(rows[0]["txid"], "0x02");
+    assert_eq!(rows[1]["entry_type"], "broadcaster_fee");
+    assert_eq!(rows[2]["txid"], "0x03");
+    assert_eq!(rows[2]["category"], "decrypt");
+    assert_eq!(rows[2]["decimals"], 18);
+    assert_eq!(rows[2]["amount"], "0.500000000000000000");
+    assert_eq!(rows[2]["timestamp"], "2023-11-17T05:46:40Z");
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_view_only_export_deletes_its_wallet() -> Result<()> {
+    let deleted = start_stub_engine(3117, &[]);
+    sleep(Duration::from_millis(200)).await;
+    let engine = DopClient::with_port(3117);
+
+    let options = HistoryExportOptions::new(ExportFormat::Csv).token_decimals(token(USDC), 6);
+    let mut out = Vec::new();
+    let written = engine
+        .export_view_only_transaction_history(
+            "viewing-key",
+            "encryption-key",
+            None,
+            "Ethereum_Sepolia",
+            &options,
+            &mut out,
+        )
+        .await?;
+    assert_eq!(written, 4);
+    assert_eq!(*deleted.lock().unwrap(), ["view-only-id"]);
+
+    // USDC decimals are unknown to the stub, so this export fails, and the
+    // wallet is deleted all the same.
+    let options = HistoryExportOptions::new(ExportFormat::Csv);
+    let result = engine
+        .export_view_only_transaction_history(
+            "viewing-key",
+            "encryption-key",
+            None,
+            "Ethereum_Sepolia",
+            &options,
+            Vec::new(),
+        )
+        .await;
+    assert!(result.unwrap_err().to_string().contains("token decimals"));
+    assert_eq!(*deleted.lock().unwrap(), ["view-only-id", "view-only-id"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_view_only_export_keeps_an_existing_wallet() -> Result<()> {
+    // The caller already stores a view-only wallet for this viewing key.
+    let deleted = start_stub_engine(3118, &["view-only-id"]);
+    sleep(Duration::from_millis(200)).await;
+    let engine = DopClient::with_port(3118);
+
+    let options = HistoryExportOptions::new(ExportFormat::Csv).token_decimals(token(USDC), 6);
+    let written = engine
+        .export_view_only_transaction_history(
+            "viewing-key",
+            "encryption-key",
+            None,
+            "Ethereum_Sepolia",
+            &options,
+            Vec::new(),
+        )
+        .await?;
+    assert_eq!(written, 4);
+    assert!(deleted.lock().unwrap().is_empty());
+    Ok(())
+}
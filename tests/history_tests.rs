@@ -0,0 +1,132 @@
+use anyhow::Result;
+use dop::dop::{
+    Chain, DopClient, TransactionCategory, TransactionHistoryFilter, TransactionHistoryPage,
+};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::time::{Duration, sleep};
+use warp::Filter;
+
+const TOKEN: &str = "0x5FbDB2315678afecb367f032d93F642f64180aa3";
+const SEPOLIA: Chain = Chain {
+    chain_type: 0,
+    id: 11155111,
+};
+
+/// Stand-in for the Node engine: returns one page of history and records
+/// the query it was asked.
+fn start_stub_engine(port: u16) -> Arc<Mutex<HashMap<String, String>>> {
+    let seen = Arc::new(Mutex::new(HashMap::new()));
+    let seen_clone = seen.clone();
+    let route = warp::get()
+        .and(warp::path!("wallet" / String / "transaction-history"))
+        .and(warp::query::<HashMap<String, String>>())
+        .map(move |_id: String, query: HashMap<String, String>| {
+            *seen_clone.lock().unwrap() = query;
+            warp::reply::json(&json!({
+                "total": 3,
+                "items": [
+                    {
+                        "txid": "0xabc",
+                        "blockNumber": 120,
+                        "timestamp": 1_700_000_100u64,
+                        "category": "transfer-out",
+                        "tokenAmounts": [{
+                            "tokenAddress": TOKEN.to_lowercase(),
+                            "amount": "1000",
+                            "counterparty": "0zk1qexample",
+                            "memoText": "rent",
+                        }],
+                        "broadcasterFee": {
+                            "tokenAddress": TOKEN,
+                            "amount": "25",
+                            "counterparty": null,
+                            "memoText": null,
+                        },
+                    },
+                    {
+                        "txid": "0xdef",
+                        "blockNumber": null,
+                        "timestamp": null,
+                        "category": "encrypt",
+                        "tokenAmounts": [],
+                        "broadcasterFee": null,
+                    },
+                ],
+            }))
+        });
+    tokio::spawn(warp::serve(route).run(([127, 0, 0, 1], port)));
+    seen
+}
+
+#[tokio::test]
+async fn test_transaction_history_page() -> Result<()> {
+    let seen = start_stub_engine(3104);
+    sleep(Duration::from_millis(200)).await;
+    let engine = DopClient::with_port(3104);
+
+    let filter = TransactionHistoryFilter {
+        categories: vec![
+            TransactionCategory::TransferOut,
+            TransactionCategory::Encrypt,
+        ],
+        token_address: Some(TOKEN.parse()?),
+        limit: Some(2),
+        ..Default::default()
+    };
+    let page = engine
+        .transaction_history("wallet-id", SEPOLIA, &filter)
+        .await?;
+
+    {
+        let query = seen.lock().unwrap();
+        assert_eq!(query["categories"], "transfer-out,encrypt");
+        assert_eq!(query["tokenAddress"], TOKEN);
+        assert_eq!(query["offset"], "0");
+        assert_eq!(query["limit"], "2");
+        assert!(!query.contains_key("startingBlock"));
+        assert_eq!(query["chain"], r#"{"type":0,"id":11155111}"#);
+    }
+
+    assert_eq!(page.total, 3);
+    assert_eq!(page.items.len(), 2);
+    let sent = &page.items[0];
+    assert_eq!(sent.category, TransactionCategory::TransferOut);
+    assert_eq!(sent.block_number, Some(120));
+    assert_eq!(sent.token_amounts[0].token_address.to_string(), TOKEN);
+    assert_eq!(sent.token_amounts[0].memo_text.as_deref(), Some("rent"));
+    assert_eq!(sent.broadcaster_fee.as_ref().unwrap().amount, "25");
+    assert!(page.items[1].broadcaster_fee.is_none());
+
+    let next = filter.next_page(&page).unwrap();
+    assert_eq!(next.offset, 2);
+    assert_eq!(next.limit, Some(2));
+    Ok(())
+}
+
+#[test]
+fn test_next_page_stops_at_total() {
+    let filter = TransactionHistoryFilter {
+        offset: 2,
+        limit: Some(2),
+        ..Default::default()
+    };
+    let last: TransactionHistoryPage = serde_json::from_value(json!({
+        "total": 3,
+        "items": [{
+            "txid": "0x1",
+            "blockNumber": 1,
+            "timestamp": 1,
+            "category": "decrypt",
+            "tokenAmounts": [],
+            "broadcasterFee": null,
+        }],
+    }))
+    .unwrap();
+    assert!(filter.next_page(&last).is_none());
+
+    let empty: TransactionHistoryPage =
+        serde_json::from_value(json!({ "total": 10, "items": [] })).unwrap();
+    assert!(filter.next_page(&empty).is_none());
+}
@@ -0,0 +1,48 @@
+use anyhow::Result;
+use dop::dop::{
+    DopClient, MAX_MEMO_ENCODED_BYTES, TransactionGasDetails, encoded_memo_len, validate_memo_text,
+};
+
+#[test]
+fn test_memo_text_within_limit() -> Result<()> {
+    validate_memo_text("")?;
+    validate_memo_text("Thanks for lunch!\nSee you soon\t🍜")?;
+    validate_memo_text(&"a".repeat(MAX_MEMO_ENCODED_BYTES))?;
+    Ok(())
+}
+
+#[test]
+fn test_memo_text_limit_counts_bytes() {
+    let err = validate_memo_text(&"a".repeat(MAX_MEMO_ENCODED_BYTES + 1)).unwrap_err();
+    assert!(err.to_string().contains("maximum"));
+
+    // Four bytes per character in UTF-8.
+    let emoji = "🍜".repeat(MAX_MEMO_ENCODED_BYTES / 4 + 1);
+    assert!(validate_memo_text(&emoji).is_err());
+}
+
+#[test]
+fn test_memo_text_allows_control_characters() -> Result<()> {
+    // The engine encodes any UTF-8 text, so only the size is checked.
+    validate_memo_text("hello\0world\u{7}")?;
+    assert_eq!(encoded_memo_len("🍜"), 4);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_oversized_memo_rejected_before_engine_call() -> Result<()> {
+    // No engine is running, so any request that got through would fail to
+    // connect rather than with the memo error.
+    let engine = DopClient::new();
+    let transfer = engine
+        .private_transfer("Ethereum_Sepolia", "wallet-id", "encryption-key")
+        .memo_text(&"a".repeat(MAX_MEMO_ENCODED_BYTES + 1));
+
+    let gas_details = TransactionGasDetails::Type0 {
+        gas_estimate: "0".to_string(),
+        gas_price: "1000000000".to_string(),
+    };
+    let err = transfer.estimate_gas(gas_details, None).await.unwrap_err();
+    assert!(err.to_string().contains("Memo text"));
+    Ok(())
+}
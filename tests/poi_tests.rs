@@ -0,0 +1,230 @@
+mod common;
+
+use anyhow::Result;
+use common::StubPoiNode;
+use dop::dop::{CommitmentPoiStatus, DopClient, PoiStatus};
+use serde_json::{Value, json};
+use serial_test::serial;
+use std::sync::{Arc, Mutex};
+use tokio::time::{Duration, sleep};
+use warp::Filter;
+
+const LIST_KEY: &str = "efc6ddb59c098a13fb2b618fdae94c1c3a807abc8fb1837c93620c9143ee9e88";
+
+type CallLog = Arc<Mutex<Vec<(String, Value)>>>;
+
+/// Stand-in for the Node engine's POI routes.
+fn start_stub_engine(port: u16) -> CallLog {
+    let calls = CallLog::default();
+    let calls_clone = calls.clone();
+    let post_routes = warp::post()
+        .and(warp::path("poi"))
+        .and(warp::path::param::<String>())
+        .and(warp::body::json())
+        .map(move |endpoint: String, body: Value| {
+            calls_clone
+                .lock()
+                .unwrap()
+                .push((endpoint.clone(), body.clone()));
+            match endpoint.as_str() {
+                "received-statuses" => warp::reply::with_status(
+                    warp::reply::json(&json!([
+                        {
+                            "tree": 0,
+                            "position": 12,
+                            "txid": "0xAB01",
+                            "commitment": "0x11",
+                            "blindedCommitment": "0x21",
+                            "poisPerList": { LIST_KEY: "ShieldBlocked" },
+                        },
+                        {
+                            "tree": 0,
+                            "position": 13,
+                            "txid": "ab02",
+                            "commitment": "0x12",
+                            "blindedCommitment": "0x22",
+                            "poisPerList": null,
+                        },
+                    ])),
+                    warp::http::StatusCode::OK,
+                ),
+                "pending-transactions" => warp::reply::with_status(
+                    warp::reply::json(&json!({ "txids": ["0xab02"] })),
+                    warp::http::StatusCode::OK,
+                ),
+                "generate" => warp::reply::with_status(
+                    warp::reply::json(&json!({ "error": "Failed to generate POIs" })),
+                    warp::http::StatusCode::INTERNAL_SERVER_ERROR,
+                ),
+                _ => warp::reply::with_status(
+                    warp::reply::json(&json!({})),
+                    warp::http::StatusCode::NO_CONTENT,
+                ),
+            }
+        });
+    let required_route = warp::get()
+        .and(warp::path!("poi" / "required"))
+        .and(warp::query::<Value>())
+        .map(|query: Value| {
+            warp::reply::json(&json!({ "required": query["networkName"] == "Ethereum" }))
+        });
+    tokio::spawn(warp::serve(post_routes.or(required_route)).run(([127, 0, 0, 1], port)));
+    calls
+}
+
+fn commitment(pois_per_list: Option<Value>) -> CommitmentPoiStatus {
+    serde_json::from_value(json!({
+        "tree": 0,
+        "position": 1,
+        "txid": "0x01",
+        "commitment": "0x02",
+        "blindedCommitment": "0x03",
+        "poisPerList": pois_per_list,
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_commitment_status_is_worst_over_lists() {
+    let valid = commitment(Some(json!({ "a": "Valid", "b": "Valid" })));
+    assert_eq!(valid.status(), PoiStatus::Valid);
+    assert!(valid.is_spendable());
+
+    let submitted = commitment(Some(json!({ "a": "Valid", "b": "ProofSubmitted" })));
+    assert_eq!(submitted.status(), PoiStatus::ProofSubmitted);
+    assert!(!submitted.is_spendable());
+
+    let blocked = commitment(Some(json!({ "a": "Missing", "b": "ShieldBlocked" })));
+    assert_eq!(blocked.status(), PoiStatus::ShieldBlocked);
+
+    assert_eq!(commitment(None).status(), PoiStatus::Missing);
+    assert_eq!(commitment(Some(json!({}))).status(), PoiStatus::Missing);
+}
+
+#[tokio::test]
+async fn test_poi_queries() -> Result<()> {
+    let calls = start_stub_engine(3111);
+    sleep(Duration::from_millis(200)).await;
+    let engine = DopClient::with_port(3111);
+
+    let statuses = engine
+        .received_poi_statuses("Ethereum_Sepolia", "wallet-id")
+        .await?;
+    assert_eq!(statuses.len(), 2);
+    assert_eq!(statuses[0].status(), PoiStatus::ShieldBlocked);
+    assert_eq!(statuses[1].position, 13);
+    assert_eq!(statuses[1].status(), PoiStatus::Missing);
+
+    // Txids match regardless of prefix and case.
+    let statuses = engine
+        .transaction_poi_statuses("Ethereum_Sepolia", "wallet-id", "ab01")
+        .await?;
+    assert_eq!(statuses.len(), 1);
+    assert_eq!(statuses[0].blinded_commitment, "0x21");
+
+    let pending = engine
+        .transactions_pending_poi("Ethereum_Sepolia", "wallet-id")
+        .await?;
+    assert_eq!(pending, ["0xab02"]);
+
+    engine
+        .refresh_received_pois("Ethereum_Sepolia", "wallet-id")
+        .await?;
+    let err = engine
+        .generate_pois("Ethereum_Sepolia", "wallet-id")
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("status 500"));
+
+    assert!(engine.poi_required("Ethereum").await?);
+    assert!(!engine.poi_required("Ethereum_Sepolia").await?);
+
+    let calls = calls.lock().unwrap();
+    assert_eq!(calls[0].1["networkName"], "Ethereum_Sepolia");
+    assert_eq!(calls[0].1["dopWalletID"], "wallet-id");
+    assert_eq!(calls[0].1["txidVersion"], "V2_PoseidonMerkle");
+    Ok(())
+}
+
+async fn rpc(url: &str, method: &str, params: Value) -> Result<Value> {
+    Ok(reqwest::Client::new()
+        .post(url)
+        .json(&json!({ "jsonrpc": "2.0", "id": 7, "method": method, "params": params }))
+        .send()
+        .await?
+        .json()
+        .await?)
+}
+
+#[tokio::test]
+async fn test_stub_poi_node() -> Result<()> {
+    let node = StubPoiNode::new(vec![LIST_KEY.to_string()]);
+    node.set_status("0xAA", PoiStatus::ShieldBlocked);
+    let url = node.start(3112);
+    sleep(Duration::from_millis(200)).await;
+
+    let status = rpc(&url, "ppoi_node_status", json!({})).await?;
+    assert_eq!(status["id"], 7);
+    assert_eq!(status["result"]["listKeys"], json!([LIST_KEY]));
+
+    let pois = rpc(
+        &url,
+        "ppoi_pois_per_list",
+        json!({
+            "listKeys": [LIST_KEY],
+            "blindedCommitmentDatas": [
+                { "blindedCommitment": "0xaa", "type": "Shield" },
+                { "blindedCommitment": "0xbb", "type": "Transact" },
+            ],
+        }),
+    )
+    .await?;
+    assert_eq!(pois["result"]["0xaa"][LIST_KEY], "ShieldBlocked");
+    assert_eq!(pois["result"]["0xbb"][LIST_KEY], "Valid");
+
+    rpc(
+        &url,
+        "ppoi_submit_transact_proof",
+        json!({ "listKey": LIST_KEY, "transactProofData": { "txidIndex": 3 } }),
+    )
+    .await?;
+    assert_eq!(
+        node.submitted_proofs()[0]["transactProofData"]["txidIndex"],
+        3
+    );
+
+    let unknown = rpc(&url, "ppoi_unknown", json!({})).await?;
+    assert_eq!(unknown["error"]["code"], -32601);
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_engine_with_stub_poi_node() -> Result<()> {
+    let node = StubPoiNode::new(vec![LIST_KEY.to_string()]).with_default_status(PoiStatus::Missing);
+    let url = node.start(3113);
+
+    let mut engine = DopClient::new().with_poi_node_urls(vec![url]);
+    engine.start();
+    engine.wait_for_api_ready().await;
+    engine.init_engine(None, None, None, None, None).await?;
+
+    let mnemonic = engine.generate_mnemonic(Some(12)).await?;
+    let encryption_key = "0101010101010101010101010101010101010101010101010101010101010101";
+    let wallet_info = engine
+        .create_wallet(&mnemonic, encryption_key, None)
+        .await?;
+    let wallet_id = wallet_info["id"].as_str().unwrap();
+
+    println!(
+        "POI required: {}",
+        engine.poi_required("Ethereum_Sepolia").await?
+    );
+    let statuses = engine
+        .received_poi_statuses("Ethereum_Sepolia", wallet_id)
+        .await?;
+    assert!(statuses.is_empty(), "a new wallet has no notes");
+
+    engine.close_engine().await?;
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use anyhow::Result;
+use dop::dop::{DopClient, ProvedTransaction};
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use time::macros::datetime;
+use tokio::time::{Duration, sleep, timeout};
+use warp::Filter;
+
+type JobLog = Arc<Mutex<Vec<String>>>;
+
+/// Stand-in for the Node engine: proofs never finish, cancellations are
+/// recorded.
+fn start_stub_engine(port: u16) -> (JobLog, JobLog) {
+    let started = JobLog::default();
+    let cancelled = JobLog::default();
+
+    let started_clone = started.clone();
+    let proof_route = warp::post()
+        .and(warp::path("generate-transfer-proof"))
+        .and(warp::body::json())
+        .and_then(move |body: Value| {
+            started_clone
+                .lock()
+                .unwrap()
+                .push(body["proofJobId"].as_str().unwrap().to_string());
+            async move {
+                sleep(Duration::from_secs(3600)).await;
+                Ok::<_, warp::Rejection>(warp::reply::json(&json!({})))
+            }
+        });
+
+    let cancelled_clone = cancelled.clone();
+    let cancel_route = warp::post()
+        .and(warp::path("cancel-proof"))
+        .and(warp::body::json())
+        .map(move |body: Value| {
+            cancelled_clone
+                .lock()
+                .unwrap()
+                .push(body["proofJobId"].as_str().unwrap().to_string());
+            warp::reply::json(&json!({ "cancelled": true }))
+        });
+
+    let decrypt_route = warp::post()
+        .and(warp::path("generate-decrypt-proof"))
+        .and(warp::body::json())
+        .map(|body: Value| {
+            warp::reply::json(&json!({
+                "success": true,
+                "provedTransaction": {
+                    "id": body["proofJobId"],
+                    "parametersHash": "ab12",
+                    "nullifiers": ["0x01", "0x02"],
+                    "createdAt": 1_700_000_000_000u64,
+                },
+            }))
+        });
+
+    let routes = proof_route.or(cancel_route).or(decrypt_route);
+    tokio::spawn(warp::serve(routes).run(([127, 0, 0, 1], port)));
+    (started, cancelled)
+}
+
+#[tokio::test]
+async fn test_dropping_proof_future_cancels_job() -> Result<()> {
+    let (started, cancelled) = start_stub_engine(3101);
+    sleep(Duration::from_millis(200)).await;
+    let engine = DopClient::with_port(3101);
+
+    let proof = engine.generate_transfer_proof(
+        "V2_PoseidonMerkle".to_string(),
+        "Ethereum_Sepolia".to_string(),
+        "wallet-id".to_string(),
+        "encryption-key".to_string(),
+        false,
+        None,
+        vec![],
+        vec![],
+        None,
+        false,
+        None,
+    );
+    assert!(timeout(Duration::from_millis(500), proof).await.is_err());
+    assert!(engine.active_proof_jobs().is_empty());
+
+    sleep(Duration::from_millis(200)).await;
+    let started = started.lock().unwrap();
+    assert_eq!(started.len(), 1);
+    assert_eq!(*cancelled.lock().unwrap(), *started);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cancel_proof() -> Result<()> {
+    let (_, cancelled) = start_stub_engine(3102);
+    sleep(Duration::from_millis(200)).await;
+    let engine = DopClient::with_port(3102);
+
+    assert!(engine.cancel_proof("job-1").await?);
+    assert_eq!(*cancelled.lock().unwrap(), vec!["job-1".to_string()]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_proof_returns_proved_transaction() -> Result<()> {
+    start_stub_engine(3103);
+    sleep(Duration::from_millis(200)).await;
+    let engine = DopClient::with_port(3103);
+
+    let proved = engine
+        .generate_decrypt_proof(
+            "V2_PoseidonMerkle".to_string(),
+            "Ethereum_Sepolia".to_string(),
+            "wallet-id".to_string(),
+            "encryption-key".to_string(),
+            vec![],
+            vec![],
+            None,
+            false,
+            None,
+            "0".to_string(),
+        )
+        .await?;
+
+    assert_eq!(proved.id.len(), 32);
+    assert_eq!(proved.parameters_hash, "ab12");
+    assert_eq!(proved.nullifiers, vec!["0x01", "0x02"]);
+    assert_eq!(proved.created_at, datetime!(2023-11-14 22:13:20 UTC));
+
+    // The handle is sent back to populate in the same shape.
+    let value = serde_json::to_value(&proved)?;
+    assert_eq!(value["createdAt"], 1_700_000_000_000u64);
+    assert_eq!(serde_json::from_value::<ProvedTransaction>(value)?, proved);
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use anyhow::Result;
+use dop::dop::DopClient;
+use serde_json::{Value, json};
+use std::sync::{Arc, Mutex};
+use tokio::time::{Duration, sleep};
+use warp::Filter;
+use warp::http::StatusCode;
+
+const TX_HASH: &str = "0x9fc76417374aa880d4449a1f7f31ec597f00b1f6f3dd2d66f4c9c6c445836d8b";
+const DEV_ADDRESS: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+
+/// Stand-in for the Node engine's provider routes. Waiting for more than
+/// 10 confirmations times out.
+fn start_stub_engine(port: u16) -> Arc<Mutex<Vec<Value>>> {
+    let payloads = Arc::new(Mutex::new(Vec::new()));
+
+    let send_payloads = payloads.clone();
+    let send_route = warp::post()
+        .and(warp::path("send-raw-transaction"))
+        .and(warp::body::json())
+        .map(move |body: Value| {
+            send_payloads.lock().unwrap().push(body);
+            warp::reply::json(&json!({ "txHash": TX_HASH }))
+        });
+
+    let wait_payloads = payloads.clone();
+    let wait_route = warp::post()
+        .and(warp::path("wait-for-confirmation"))
+        .and(warp::body::json())
+        .map(move |body: Value| {
+            wait_payloads.lock().unwrap().push(body.clone());
+            if body["confirmations"].as_u64().unwrap() > 10 {
+                return warp::reply::with_status(
+                    warp::reply::json(&"Timed out waiting for confirmation"),
+                    StatusCode::REQUEST_TIMEOUT,
+                );
+            }
+            warp::reply::with_status(
+                warp::reply::json(&json!({
+                    "transactionHash": TX_HASH,
+                    "blockNumber": 4_200_000,
+                    "blockHash": "0x88df016429689c079f3b2f6ad39fa052532c56795b733da78a91ebe6a713944b",
+                    "from": DEV_ADDRESS,
+                    "to": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+                    "contractAddress": null,
+                    "status": 1,
+                    "gasUsed": "21000",
+                    "effectiveGasPrice": "1000000000",
+                    "confirmations": body["confirmations"],
+                })),
+                StatusCode::OK,
+            )
+        });
+
+    tokio::spawn(warp::serve(send_route.or(wait_route)).run(([127, 0, 0, 1], port)));
+    payloads
+}
+
+#[tokio::test]
+async fn test_send_and_wait_for_confirmation() -> Result<()> {
+    let payloads = start_stub_engine(3107);
+    sleep(Duration::from_millis(200)).await;
+    let engine = DopClient::with_port(3107);
+
+    let tx_hash = engine
+        .send_raw_transaction("Ethereum_Sepolia", &[0x02, 0xf8, 0x01])
+        .await?;
+    assert_eq!(tx_hash, TX_HASH);
+
+    let receipt = engine
+        .wait_for_confirmation("Ethereum_Sepolia", &tx_hash, 3, Duration::from_secs(60))
+        .await?;
+    assert!(receipt.succeeded());
+    assert_eq!(receipt.block_number, 4_200_000);
+    assert_eq!(receipt.confirmations, 3);
+    assert_eq!(receipt.from.to_string(), DEV_ADDRESS);
+    assert!(receipt.contract_address.is_none());
+
+    let err = engine
+        .wait_for_confirmation("Ethereum_Sepolia", &tx_hash, 12, Duration::from_secs(5))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Timed out"));
+
+    let payloads = payloads.lock().unwrap();
+    assert_eq!(payloads[0]["signedTransaction"], "0x02f801");
+    assert_eq!(payloads[1]["txHash"], TX_HASH);
+    assert_eq!(payloads[1]["timeoutMs"], 60_000);
+    Ok(())
+}
+
+/// Sends a transaction to a local anvil or hardhat node at
+/// `DOP_TEST_RPC_URL`, default `http://127.0.0.1:8545`. Ignored unless run
+/// with `--ignored`.
+// Not `#[serial]`: serial_test 0.5 drops `#[ignore]`, and this is the only
+// test in the file that starts the engine.
+#[cfg(feature = "signer")]
+#[tokio::test]
+#[ignore = "needs a local anvil or hardhat node"]
+async fn test_send_raw_transaction_to_local_node() -> Result<()> {
+    use dop::dop::{ContractTransaction, LocalSigner, TransactionGasDetails, TransactionSigner};
+
+    // First Hardhat/Anvil development account.
+    const DEV_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    let rpc_url =
+        std::env::var("DOP_TEST_RPC_URL").unwrap_or_else(|_| "http://127.0.0.1:8545".to_string());
+
+    let mut engine = DopClient::new();
+    engine.start();
+    engine.wait_for_api_ready().await;
+    engine.init_engine(None, None, None, None, None).await?;
+    let providers = json!({
+        "chainId": 31337,
+        "providers": [{ "provider": rpc_url, "priority": 1, "weight": 2 }]
+    });
+    engine.load_provider(providers, "Hardhat", None).await?;
+
+    let signer = LocalSigner::from_private_key(DEV_KEY)?;
+    let nonce = engine
+        .transaction_count("Hardhat", signer.address())
+        .await?;
+    let transaction = ContractTransaction {
+        to: signer.address(),
+        data: "0x".to_string(),
+        from: Some(signer.address()),
+        value: Some("1".to_string()),
+    };
+    let gas_details = TransactionGasDetails::Type2 {
+        gas_estimate: "21000".to_string(),
+        max_fee_per_gas: "100000000000".to_string(),
+        max_priority_fee_per_gas: "1000000000".to_string(),
+    };
+    let raw = signer
+        .sign_transaction(&transaction, &gas_details, nonce, 31337)
+        .await?;
+
+    let tx_hash = engine.send_raw_transaction("Hardhat", &raw).await?;
+    let receipt = engine
+        .wait_for_confirmation("Hardhat", &tx_hash, 1, Duration::from_secs(30))
+        .await?;
+    assert!(receipt.succeeded());
+    assert_eq!(receipt.transaction_hash, tx_hash);
+    assert_eq!(
+        engine
+            .transaction_count("Hardhat", signer.address())
+            .await?,
+        nonce + 1
+    );
+
+    engine.close_engine().await?;
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use anyhow::Result;
+use dop::dop::{
+    Chain, CircuitLimits, DopAddress, DopERC20AmountRecipient, EvmAddress, RecipientKind,
+    RecipientSet,
+};
+
+const MOCK_DOP_ADDRESS: &str = "0zk1q8hxknrs97q8pjxaagwthzc0df99rzmhl2xnlxmgv9akv32sua0kfrv7j6fe3z53llhxknrs97q8pjxaagwthzc0df99rzmhl2xnlxmgv9akv32sua0kg0zpzts";
+const TOKEN_A: &str = "0x5FbDB2315678afecb367f032d93F642f64180aa3";
+const TOKEN_B: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+const EVM_RECIPIENT: &str = "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359";
+const LIMITS: CircuitLimits = CircuitLimits {
+    max_outputs: 5,
+    max_decrypt_outputs_per_token: 1,
+};
+
+fn token(address: &str) -> EvmAddress {
+    address.parse().unwrap()
+}
+
+fn dop_address(seed: u8, chain: Option<Chain>) -> String {
+    DopAddress::new([seed; 32], [seed; 32], chain)
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn test_merges_duplicate_recipients() -> Result<()> {
+    let mut set = RecipientSet::for_transfer();
+    set.add(token(TOKEN_A), "1000", MOCK_DOP_ADDRESS)?;
+    set.add(token(TOKEN_B), "5", MOCK_DOP_ADDRESS)?;
+    set.add(token(TOKEN_A), "234", MOCK_DOP_ADDRESS)?;
+
+    let recipients = set.recipients();
+    assert_eq!(recipients.len(), 2);
+    assert_eq!(recipients[0].token_address, token(TOKEN_A));
+    assert_eq!(recipients[0].amount, "1234");
+    assert_eq!(recipients[1].amount, "5");
+    Ok(())
+}
+
+#[test]
+fn test_rejects_invalid_amounts() {
+    let mut set = RecipientSet::for_transfer();
+    assert!(set.add(token(TOKEN_A), "0", MOCK_DOP_ADDRESS).is_err());
+    assert!(set.add(token(TOKEN_A), "-10", MOCK_DOP_ADDRESS).is_err());
+    assert!(set.add(token(TOKEN_A), "1.5", MOCK_DOP_ADDRESS).is_err());
+    assert!(set.is_empty());
+}
+
+#[test]
+fn test_checks_recipient_addresses() -> Result<()> {
+    let mut transfer = RecipientSet::for_transfer();
+    assert!(transfer.add(token(TOKEN_A), "1", EVM_RECIPIENT).is_err());
+    transfer.add(token(TOKEN_A), "1", MOCK_DOP_ADDRESS)?;
+
+    let mut decrypt = RecipientSet::for_decrypt();
+    assert!(decrypt.add(token(TOKEN_A), "1", MOCK_DOP_ADDRESS).is_err());
+    decrypt.add(token(TOKEN_A), "1", &EVM_RECIPIENT.to_lowercase())?;
+    decrypt.add(token(TOKEN_A), "2", EVM_RECIPIENT)?;
+
+    // Lowercase and checksummed forms are the same recipient.
+    let recipients = decrypt.recipients();
+    assert_eq!(recipients.len(), 1);
+    assert_eq!(recipients[0].recipient_address, EVM_RECIPIENT);
+    assert_eq!(recipients[0].amount, "3");
+    Ok(())
+}
+
+#[test]
+fn test_rejects_mixed_networks() -> Result<()> {
+    let sepolia = Chain {
+        chain_type: 0,
+        id: 11155111,
+    };
+    let mainnet = Chain {
+        chain_type: 0,
+        id: 1,
+    };
+
+    let mut set = RecipientSet::for_transfer();
+    set.add(token(TOKEN_A), "1", MOCK_DOP_ADDRESS)?;
+    set.add(token(TOKEN_A), "1", &dop_address(1, Some(sepolia)))?;
+    assert!(
+        set.add(token(TOKEN_A), "1", &dop_address(2, Some(mainnet)))
+            .is_err()
+    );
+
+    let mut pinned = RecipientSet::for_transfer().with_chain(mainnet);
+    assert!(
+        pinned
+            .add(token(TOKEN_A), "1", &dop_address(1, Some(sepolia)))
+            .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_splits_oversized_batches() -> Result<()> {
+    let recipients: Vec<DopERC20AmountRecipient> = (1..=9)
+        .map(|seed| DopERC20AmountRecipient {
+            token_address: token(TOKEN_A),
+            amount: "10".to_string(),
+            recipient_address: dop_address(seed, None),
+        })
+        .collect();
+    let set = RecipientSet::from_recipients(RecipientKind::Transfer, recipients)?;
+
+    // Four recipients plus one change output per transaction.
+    let sizes: Vec<usize> = set.into_batches(&LIMITS)?.iter().map(Vec::len).collect();
+    assert_eq!(sizes, vec![4, 4, 1]);
+
+    // A second token needs its own change output.
+    let mut set = RecipientSet::for_transfer();
+    for seed in 1..=3 {
+        set.add(token(TOKEN_A), "10", &dop_address(seed, None))?;
+    }
+    set.add(token(TOKEN_B), "10", &dop_address(4, None))?;
+    let sizes: Vec<usize> = set.into_batches(&LIMITS)?.iter().map(Vec::len).collect();
+    assert_eq!(sizes, vec![3, 1]);
+
+    let limits = CircuitLimits {
+        max_outputs: 1,
+        ..LIMITS
+    };
+    assert!(RecipientSet::for_transfer().into_batches(&limits).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_splits_decrypts_per_token() -> Result<()> {
+    let mut set = RecipientSet::for_decrypt();
+    set.add(token(TOKEN_A), "10", EVM_RECIPIENT)?;
+    set.add(token(TOKEN_B), "10", EVM_RECIPIENT)?;
+    set.add(
+        token(TOKEN_A),
+        "10",
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+    )?;
+
+    // Only one decrypt per token fits in a transaction.
+    let batches = set.into_batches(&LIMITS)?;
+    let sizes: Vec<usize> = batches.iter().map(Vec::len).collect();
+    assert_eq!(sizes, vec![2, 1]);
+    assert_eq!(batches[1][0].token_address, token(TOKEN_A));
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use anyhow::Result;
+use dop::dop::{DopClient, DopSignature, SignInWithDop};
+use serial_test::serial;
+use time::{Duration, OffsetDateTime, macros::datetime};
+
+const MOCK_DOP_ADDRESS: &str = "0zk1q8hxknrs97q8pjxaagwthzc0df99rzmhl2xnlxmgv9akv32sua0kfrv7j6fe3z53llhxknrs97q8pjxaagwthzc0df99rzmhl2xnlxmgv9akv32sua0kg0zpzts";
+
+#[test]
+fn test_dop_signature_hex_round_trip() -> Result<()> {
+    let hex = "ab".repeat(192);
+    let signature: DopSignature = hex.parse()?;
+    assert_eq!(signature.to_string(), hex);
+    assert_eq!(format!("0x{}", hex).parse::<DopSignature>()?, signature);
+
+    let json = serde_json::to_string(&signature)?;
+    assert_eq!(serde_json::from_str::<DopSignature>(&json)?, signature);
+
+    assert!("abcd".parse::<DopSignature>().is_err());
+    assert!("zz".repeat(192).parse::<DopSignature>().is_err());
+    Ok(())
+}
+
+#[test]
+fn test_sign_in_with_dop_message_format() -> Result<()> {
+    let mut message = SignInWithDop::new(
+        "app.example.com",
+        MOCK_DOP_ADDRESS,
+        "https://app.example.com/login",
+        "32891756",
+    )
+    .with_statement("Sign in to Example")
+    .with_chain_id(11155111)
+    .with_expiration_time(datetime!(2030-01-01 0:00 UTC));
+    message.issued_at = datetime!(2025-06-01 12:30 UTC);
+
+    let text = message.to_string();
+    assert_eq!(
+        text,
+        format!(
+            "app.example.com wants you to sign in with your DOP account:\n\
+             {}\n\
+             \n\
+             Sign in to Example\n\
+             \n\
+             URI: https://app.example.com/login\n\
+             Version: 1\n\
+             Chain ID: 11155111\n\
+             Nonce: 32891756\n\
+             Issued At: 2025-06-01T12:30:00Z\n\
+             Expiration Time: 2030-01-01T00:00:00Z",
+            MOCK_DOP_ADDRESS
+        )
+    );
+    assert_eq!(text.parse::<SignInWithDop>()?, message);
+
+    // Optional fields can be omitted.
+    let minimal = SignInWithDop::new("a.com", MOCK_DOP_ADDRESS, "https://a.com", "n");
+    assert_eq!(minimal.to_string().parse::<SignInWithDop>()?, minimal);
+
+    assert!("not a sign-in message".parse::<SignInWithDop>().is_err());
+    assert!(
+        format!("{}\nExtra: line", text)
+            .parse::<SignInWithDop>()
+            .is_err()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_sign_in_with_dop_time_window() {
+    let now = OffsetDateTime::now_utc();
+    let message = SignInWithDop::new("a.com", MOCK_DOP_ADDRESS, "https://a.com", "n")
+        .with_not_before(now + Duration::minutes(5))
+        .with_expiration_time(now + Duration::minutes(10));
+
+    assert!(message.check_time(now).is_err());
+    assert!(message.check_time(now + Duration::minutes(6)).is_ok());
+    assert!(message.check_time(now + Duration::minutes(10)).is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_sign_and_verify_dop_signature() -> Result<()> {
+    let mut engine = DopClient::new();
+    engine.start();
+    engine.wait_for_api_ready().await;
+    engine
+        .init_engine(
+            Some("database/test-signature.db"),
+            Some("Signature Engine"),
+            Some(false),
+            Some(true),
+            Some(false),
+        )
+        .await?;
+
+    let encryption_key = "0101010101010101010101010101010101010101010101010101010101010101";
+    let mnemonic = engine.generate_mnemonic(Some(12)).await?;
+    let wallet_info = engine
+        .create_wallet(&mnemonic, encryption_key, None)
+        .await?;
+    let id = wallet_info["id"].as_str().unwrap();
+    let dop_address = wallet_info["dopAddress"].as_str().unwrap();
+
+    let signature = engine
+        .sign_message_with_wallet(id, encryption_key, "Hello DOP!")
+        .await?;
+    assert!(
+        engine
+            .verify_dop_signature(dop_address, "Hello DOP!", &signature)
+            .await?
+    );
+    assert!(
+        !engine
+            .verify_dop_signature(dop_address, "Goodbye DOP!", &signature)
+            .await?
+    );
+
+    // Raw bytes that are not valid UTF-8.
+    let bytes = [0xff, 0x00, 0xfe, 0x01];
+    let signature = engine
+        .sign_bytes_with_wallet(id, encryption_key, &bytes)
+        .await?;
+    assert!(
+        engine
+            .verify_dop_signature(dop_address, bytes, &signature)
+            .await?
+    );
+    assert!(
+        !engine
+            .verify_dop_signature(MOCK_DOP_ADDRESS, bytes, &signature)
+            .await?
+    );
+
+    // Sign-in flow.
+    let sign_in = SignInWithDop::new(
+        "app.example.com",
+        dop_address,
+        "https://app.example.com",
+        "abc123",
+    )
+    .with_expiration_time(OffsetDateTime::now_utc() + Duration::minutes(5));
+    let signature = engine
+        .sign_in_with_dop(id, encryption_key, &sign_in)
+        .await?;
+    let verified = engine
+        .verify_sign_in_with_dop(&sign_in.to_string(), &signature)
+        .await?;
+    assert_eq!(verified, sign_in);
+
+    engine.close_engine().await?;
+    Ok(())
+}
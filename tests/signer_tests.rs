@@ -0,0 +1,271 @@
+#![cfg(feature = "signer")]
+
+use anyhow::Result;
+use dop::dop::{
+    ContractTransaction, DopClient, DopERC20AmountRecipient, LocalSigner, MessageSigner,
+    TransactionGasDetails, TransactionSigner, encrypt_private_key_from_signature,
+};
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use serde_json::{Value, json};
+use sha3::{Digest, Keccak256};
+use std::sync::{Arc, Mutex};
+use tokio::time::{Duration, sleep};
+use warp::Filter;
+
+// First Hardhat/Anvil development account.
+const DEV_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+const DEV_ADDRESS: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+const SIGNATURE_MESSAGE: &str = "DOP encrypt private key";
+// DEV_KEY's personal_sign signature over SIGNATURE_MESSAGE, and its keccak256
+// as the engine derives it (`keccak256(await wallet.signMessage(message))`).
+const DEV_SIGNATURE: &str = "0xb5214fc1bc2d049068247e6fef1a000277158ec79ae53b73183f4e34ef1f95bc776c03a0cc7432ecf3bf172de60b9c5099d37401137cd88b5d6b4e95dfb54f7a1b";
+const DEV_ENCRYPT_KEY: &str = "0xd716fbc3daccc1212913bf515488ecddc6c1878228aad3b0a45e2cc31f860435";
+
+#[test]
+fn test_local_signer_address() -> Result<()> {
+    let signer = LocalSigner::from_private_key(DEV_KEY)?;
+    assert_eq!(signer.address().to_string(), DEV_ADDRESS);
+
+    let unprefixed = LocalSigner::from_private_key(&DEV_KEY[2..])?;
+    assert_eq!(unprefixed.address(), signer.address());
+
+    assert!(LocalSigner::from_private_key("0x1234").is_err());
+    assert!(LocalSigner::from_private_key(&format!("0x{}", "00".repeat(32))).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_personal_signature_recovers_to_signer() -> Result<()> {
+    let signer = LocalSigner::from_private_key(DEV_KEY)?;
+    let message = b"hello world";
+    let signature = signer.sign_personal_message(message)?;
+    assert!(signature[64] == 27 || signature[64] == 28);
+
+    // Deterministic, so the derived encrypt key is stable across calls.
+    assert_eq!(signer.sign_personal_message(message)?, signature);
+
+    let mut hasher = Keccak256::new();
+    hasher.update(b"\x19Ethereum Signed Message:\n11hello world");
+    let digest = hasher.finalize();
+    let recovered = VerifyingKey::recover_from_prehash(
+        &digest,
+        &Signature::from_slice(&signature[..64])?,
+        RecoveryId::from_byte(signature[64] - 27).unwrap(),
+    )?;
+    let point = recovered.to_encoded_point(false);
+    let address = &Keccak256::digest(&point.as_bytes()[1..])[12..];
+    assert_eq!(address, signer.address().as_bytes());
+    Ok(())
+}
+
+#[test]
+fn test_encrypt_private_key_from_signature() {
+    assert_eq!(
+        encrypt_private_key_from_signature(&[]),
+        "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+    );
+}
+
+#[tokio::test]
+async fn test_encrypt_private_key_fixed_vector() -> Result<()> {
+    let signature = hex::decode(&DEV_SIGNATURE[2..])?;
+    assert_eq!(
+        encrypt_private_key_from_signature(&signature),
+        DEV_ENCRYPT_KEY
+    );
+
+    let signer = LocalSigner::from_private_key(DEV_KEY)?;
+    assert_eq!(
+        signer.sign_message(SIGNATURE_MESSAGE.as_bytes()).await?,
+        signature
+    );
+    Ok(())
+}
+
+fn contract_transaction(to: &str, data: &str, value: Option<&str>) -> ContractTransaction {
+    ContractTransaction {
+        to: to.parse().unwrap(),
+        data: data.to_string(),
+        from: None,
+        value: value.map(str::to_string),
+    }
+}
+
+fn recover_address(hash: &[u8], y_parity: u8, r: &[u8], s: &[u8]) -> Result<[u8; 20]> {
+    let mut rs = [0u8; 64];
+    rs[32 - r.len()..32].copy_from_slice(r);
+    rs[64 - s.len()..].copy_from_slice(s);
+    let key = VerifyingKey::recover_from_prehash(
+        hash,
+        &Signature::from_slice(&rs)?,
+        RecoveryId::from_byte(y_parity).unwrap(),
+    )?;
+    let point = key.to_encoded_point(false);
+    Ok(Keccak256::digest(&point.as_bytes()[1..])[12..].try_into()?)
+}
+
+#[tokio::test]
+async fn test_sign_legacy_transaction_eip155_vector() -> Result<()> {
+    // Example transaction from the EIP-155 specification.
+    let signer = LocalSigner::from_private_key(&format!("0x{}", "46".repeat(32)))?;
+    let tx = contract_transaction(
+        "0x3535353535353535353535353535353535353535",
+        "0x",
+        Some("1000000000000000000"),
+    );
+    let gas = TransactionGasDetails::Type0 {
+        gas_estimate: "21000".to_string(),
+        gas_price: "20000000000".to_string(),
+    };
+
+    let raw = signer.sign_transaction(&tx, &gas, 9, 1).await?;
+    assert_eq!(
+        hex::encode(raw),
+        "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sign_eip1559_transaction() -> Result<()> {
+    let signer = LocalSigner::from_private_key(DEV_KEY)?;
+    let data = format!("0x{}", "ab".repeat(100));
+    let tx = contract_transaction("0x5FbDB2315678afecb367f032d93F642f64180aa3", &data, None);
+    let gas = TransactionGasDetails::Type2 {
+        gas_estimate: "250000".to_string(),
+        max_fee_per_gas: "30000000000".to_string(),
+        max_priority_fee_per_gas: "1500000000".to_string(),
+    };
+
+    let raw = signer.sign_transaction(&tx, &gas, 7, 11155111).await?;
+    assert_eq!(raw[0], 0x02);
+
+    let fields = rlp::Rlp::new(&raw[1..]);
+    assert_eq!(fields.item_count()?, 12);
+    assert_eq!(fields.val_at::<u64>(0)?, 11155111);
+    assert_eq!(fields.val_at::<u64>(1)?, 7);
+    assert_eq!(fields.val_at::<u64>(2)?, 1_500_000_000);
+    assert_eq!(fields.val_at::<u64>(3)?, 30_000_000_000);
+    assert_eq!(fields.val_at::<u64>(4)?, 250_000);
+    assert_eq!(fields.at(5)?.data()?, tx.to.as_bytes());
+    assert!(fields.at(6)?.data()?.is_empty());
+    assert_eq!(fields.at(7)?.data()?, [0xab; 100]);
+    assert_eq!(fields.at(8)?.item_count()?, 0);
+
+    // Rebuild the unsigned payload with a reference RLP encoder and check
+    // the signature recovers to the signer.
+    let mut unsigned = rlp::RlpStream::new_list(9);
+    for i in 0..9 {
+        unsigned.append_raw(fields.at(i)?.as_raw(), 1);
+    }
+    let mut payload = vec![0x02];
+    payload.extend_from_slice(&unsigned.out());
+    let address = recover_address(
+        &Keccak256::digest(&payload),
+        fields.val_at::<u8>(9)?,
+        fields.at(10)?.data()?,
+        fields.at(11)?.data()?,
+    )?;
+    assert_eq!(&address, signer.address().as_bytes());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sign_transaction_checks_sender() -> Result<()> {
+    let signer = LocalSigner::from_private_key(DEV_KEY)?;
+    let mut tx = contract_transaction("0x5FbDB2315678afecb367f032d93F642f64180aa3", "0x", None);
+    tx.from = Some("0x9E9F988356f46744Ee0374A17a5Fa1a3A3cC3777".parse()?);
+    let gas = TransactionGasDetails::Type1 {
+        gas_estimate: "21000".to_string(),
+        gas_price: "1000000000".to_string(),
+    };
+
+    let err = signer.sign_transaction(&tx, &gas, 0, 1).await.unwrap_err();
+    assert!(err.to_string().contains("signer is"));
+
+    tx.from = Some(signer.address());
+    let raw = signer.sign_transaction(&tx, &gas, 0, 1).await?;
+    assert_eq!(raw[0], 0x01);
+    Ok(())
+}
+
+/// Stand-in for the Node engine's encrypt routes; records the payloads.
+fn start_stub_engine(port: u16) -> Arc<Mutex<Vec<Value>>> {
+    let payloads = Arc::new(Mutex::new(Vec::new()));
+
+    let message_route = warp::post()
+        .and(warp::path("get-encrypt-private-key-signature-message"))
+        .map(|| warp::reply::json(&json!({ "message": SIGNATURE_MESSAGE })));
+
+    let estimate_payloads = payloads.clone();
+    let estimate_route = warp::post()
+        .and(warp::path("gas-estimate-for-encrypt"))
+        .and(warp::body::json())
+        .map(move |body: Value| {
+            estimate_payloads.lock().unwrap().push(body);
+            warp::reply::json(&json!({ "gasEstimate": "210000" }))
+        });
+
+    let populate_payloads = payloads.clone();
+    let populate_route = warp::post()
+        .and(warp::path("populate-encrypt"))
+        .and(warp::body::json())
+        .map(move |body: Value| {
+            populate_payloads.lock().unwrap().push(body);
+            warp::reply::json(&json!({
+                "transaction": {
+                    "to": "0x5FbDB2315678afecb367f032d93F642f64180aa3",
+                    "data": "0x1234",
+                    "from": DEV_ADDRESS,
+                },
+            }))
+        });
+
+    let routes = message_route.or(estimate_route).or(populate_route);
+    tokio::spawn(warp::serve(routes).run(([127, 0, 0, 1], port)));
+    payloads
+}
+
+#[tokio::test]
+async fn test_encrypt_uses_derived_key() -> Result<()> {
+    let payloads = start_stub_engine(3106);
+    sleep(Duration::from_millis(200)).await;
+    let engine = DopClient::with_port(3106);
+    let signer = LocalSigner::from_private_key(DEV_KEY)?;
+
+    assert_eq!(
+        engine.derive_encrypt_private_key(&signer).await?,
+        DEV_ENCRYPT_KEY
+    );
+
+    let gas_prices = TransactionGasDetails::Type0 {
+        gas_estimate: "0".to_string(),
+        gas_price: "1000000000".to_string(),
+    };
+    let result = engine
+        .encrypt(
+            "Ethereum_Sepolia",
+            &signer,
+            &gas_prices,
+            vec![DopERC20AmountRecipient {
+                token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".parse()?,
+                amount: "1000".to_string(),
+                recipient_address: "0zk1qexample".to_string(),
+            }],
+            vec![],
+        )
+        .await?;
+    assert_eq!(result.transaction.from.unwrap(), signer.address());
+    match result.gas_details {
+        TransactionGasDetails::Type0 { gas_estimate, .. } => assert_eq!(gas_estimate, "210000"),
+        other => panic!("unexpected gas details: {:?}", other),
+    }
+
+    let payloads = payloads.lock().unwrap();
+    assert_eq!(payloads.len(), 2);
+    assert_eq!(payloads[0]["encryptPrivateKey"], DEV_ENCRYPT_KEY);
+    assert_eq!(payloads[0]["fromWalletAddress"], DEV_ADDRESS);
+    assert_eq!(payloads[1]["encryptPrivateKey"], DEV_ENCRYPT_KEY);
+    assert_eq!(payloads[1]["gasDetails"]["gasEstimate"], "210000");
+    Ok(())
+}
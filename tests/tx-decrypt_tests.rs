@@ -1,10 +1,8 @@
 use anyhow::Result;
-use dop::dop::{
-    DopClient, DopERC20Amount, DopERC20AmountRecipient, DopNFTAmountRecipient, FeeTokenDetails,
-    TransactionGasDetails,
-};
+use dop::dop::{DopClient, DopERC20Amount, ProvedTransaction, TransactionGasDetails};
 use serde_json::json;
 use serial_test::serial;
+use time::OffsetDateTime;
 
 async fn setup_client() -> Result<DopClient> {
     let mut engine = DopClient::new();
@@ -51,6 +49,16 @@ async fn setup_wallet(client: &DopClient) -> Result<(String, String)> {
     Ok((dop_wallet_id, encryption_key.to_owned()))
 }
 
+// A handle the engine never issued, so populate must reject it.
+fn unknown_proof() -> ProvedTransaction {
+    ProvedTransaction {
+        id: "unknown".into(),
+        parameters_hash: String::new(),
+        nullifiers: vec![],
+        created_at: OffsetDateTime::now_utc(),
+    }
+}
+
 #[tokio::test]
 #[serial]
 async fn test_generate_decrypt_proof() -> Result<()> {
@@ -112,7 +120,7 @@ async fn test_generate_decrypt_base_token_proof() -> Result<()> {
     let (wallet_id, encryption_key) = setup_wallet(&client).await?;
 
     let wrapped_amount = DopERC20Amount {
-        token_address: "0xToken".into(),
+        token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".parse()?,
         amount: "1000".into(),
     };
 
@@ -150,6 +158,7 @@ async fn test_populate_proved_decrypt() -> Result<()> {
 
     match client
         .populate_proved_decrypt(
+            &unknown_proof(),
             "V2_PoseidonMerkle".into(),
             "Ethereum_Sepolia".into(),
             wallet_id,
@@ -185,12 +194,13 @@ async fn test_populate_proved_decrypt_base_token() -> Result<()> {
     };
 
     let wrapped_amount = DopERC20Amount {
-        token_address: "0xToken".into(),
+        token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".parse()?,
         amount: "1000".into(),
     };
 
     match client
         .populate_proved_decrypt_base_token(
+            &unknown_proof(),
             "V2_PoseidonMerkle".into(),
             "Ethereum_Sepolia".into(),
             "0xPublicWallet".into(),
@@ -259,7 +269,7 @@ async fn test_gas_estimate_for_unproven_decrypt_base_token() -> Result<()> {
     };
 
     let wrapped_amount = DopERC20Amount {
-        token_address: "0xToken".into(),
+        token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".parse()?,
         amount: "1000".into(),
     };
 
@@ -325,6 +335,7 @@ async fn test_populate_proved_decrypt_to_origin() -> Result<()> {
 
     match client
         .populate_proved_decrypt_to_origin(
+            &unknown_proof(),
             "V2_PoseidonMerkle".into(),
             "Ethereum_Sepolia".into(),
             wallet_id,
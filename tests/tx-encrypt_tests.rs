@@ -1,5 +1,5 @@
 use anyhow::Result;
-use dop::dop::{DopClient, DopERC20Amount, DopERC20AmountRecipient, DopNFTAmountRecipient};
+use dop::dop::{DopClient, DopERC20Amount, DopERC20AmountRecipient};
 use serde_json::json;
 use serial_test::serial;
 
@@ -54,7 +54,7 @@ async fn create_wallet(engine: &DopClient) -> Result<(String, String)> {
 #[tokio::test]
 #[serial]
 async fn test_gas_estimate_for_encrypt_base_token() -> Result<()> {
-    let mut engine = setup_engine().await?;
+    let engine = setup_engine().await?;
     let (dop_address, _) = create_wallet(&engine).await?;
 
     let result = engine
@@ -64,7 +64,7 @@ async fn test_gas_estimate_for_encrypt_base_token() -> Result<()> {
             dop_address.clone(),
             "0x0101010101010101010101010101010101010101010101010101010101010101".to_string(),
             DopERC20Amount {
-                token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".to_string(),
+                token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".parse()?,
                 amount: "1000".to_string(),
             },
             "0x9E9F988356f46744Ee0374A17a5Fa1a3A3cC3777".to_string(), // still EVM address for fromWallet
@@ -92,7 +92,7 @@ async fn test_gas_estimate_for_encrypt_base_token() -> Result<()> {
 #[tokio::test]
 #[serial]
 async fn test_populate_encrypt_base_token() -> Result<()> {
-    let mut engine = setup_engine().await?;
+    let engine = setup_engine().await?;
     let (dop_address, _) = create_wallet(&engine).await?;
 
     let result = engine
@@ -102,7 +102,7 @@ async fn test_populate_encrypt_base_token() -> Result<()> {
             dop_address.clone(),
             "0x0101010101010101010101010101010101010101010101010101010101010101".to_string(),
             DopERC20Amount {
-                token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".to_string(),
+                token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".parse()?,
                 amount: "1000".to_string(),
             },
             "0x9E9F988356f46744Ee0374A17a5Fa1a3A3cC3777".to_string(),
@@ -135,11 +135,11 @@ async fn test_populate_encrypt_base_token() -> Result<()> {
 #[tokio::test]
 #[serial]
 async fn test_gas_estimate_for_encrypt() -> Result<()> {
-    let mut engine = setup_engine().await?;
+    let engine = setup_engine().await?;
     let (dop_address, encryption_key) = create_wallet(&engine).await?;
 
     let recipients = vec![DopERC20AmountRecipient {
-        token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".to_string(),
+        token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".parse()?,
         amount: "1000".to_string(),
         recipient_address: dop_address.clone(), // ✅ DOP Address
     }];
@@ -177,11 +177,11 @@ async fn test_gas_estimate_for_encrypt() -> Result<()> {
 #[tokio::test]
 #[serial]
 async fn test_populate_encrypt() -> Result<()> {
-    let mut engine = setup_engine().await?;
+    let engine = setup_engine().await?;
     let (dop_address, encryption_key) = create_wallet(&engine).await?;
 
     let recipients = vec![DopERC20AmountRecipient {
-        token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".to_string(),
+        token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".parse()?,
         amount: "1000".to_string(),
         recipient_address: dop_address.clone(), // ✅ DOP Address
     }];
@@ -222,7 +222,7 @@ async fn test_populate_encrypt() -> Result<()> {
 #[tokio::test]
 #[serial]
 async fn test_get_encrypt_private_key_signature_message() -> Result<()> {
-    let mut engine = setup_engine().await?;
+    let engine = setup_engine().await?;
 
     let message = engine.get_encrypt_private_key_signature_message().await?;
     println!("✅ Signature Message: {}", message);
@@ -235,11 +235,11 @@ async fn test_get_encrypt_private_key_signature_message() -> Result<()> {
 #[tokio::test]
 #[serial]
 async fn test_generate_encrypt_transaction() -> Result<()> {
-    let mut engine = setup_engine().await?;
+    let engine = setup_engine().await?;
     let (dop_address, encryption_key) = create_wallet(&engine).await?;
 
     let recipients = vec![DopERC20AmountRecipient {
-        token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".to_string(),
+        token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".parse()?,
         amount: "1000".to_string(),
         recipient_address: dop_address.clone(), // ✅ DOP Address
     }];
@@ -260,7 +260,7 @@ async fn test_generate_encrypt_transaction() -> Result<()> {
         tx.data.len()
     );
 
-    assert!(!tx.to.is_empty());
+    assert!(!tx.to.is_zero());
     assert!(!tx.data.is_empty());
     engine.close_engine().await?;
     Ok(())
@@ -1,10 +1,11 @@
 use anyhow::Result;
 use dop::dop::{
-    DopClient, DopERC20AmountRecipient, DopNFTAmountRecipient, FeeTokenDetails,
+    DopClient, DopERC20AmountRecipient, FeeTokenDetails, GasFeeOracle, ProvedTransaction,
     TransactionGasDetails,
 };
 use serde_json::json;
 use serial_test::serial;
+use time::OffsetDateTime;
 
 async fn setup_engine() -> Result<DopClient> {
     let mut engine = DopClient::new();
@@ -46,7 +47,7 @@ async fn setup_engine() -> Result<DopClient> {
 #[tokio::test]
 #[serial]
 async fn test_gas_estimate_for_unproven_transfer() -> Result<()> {
-    let mut engine = setup_engine().await?;
+    let engine = setup_engine().await?;
     let mnemonic = engine.generate_mnemonic(Some(12)).await?;
     let encryption_key = "0101010101010101010101010101010101010101010101010101010101010101";
     let wallet_info = engine
@@ -59,7 +60,7 @@ async fn test_gas_estimate_for_unproven_transfer() -> Result<()> {
     println!("✅ Wallet: {:?}", wallet);
 
     let recipients = vec![DopERC20AmountRecipient {
-        token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".to_string(),
+        token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".parse()?,
         amount: "1000".to_string(),
         recipient_address: dop_address.clone(),
     }];
@@ -101,7 +102,7 @@ async fn test_gas_estimate_for_unproven_transfer() -> Result<()> {
 #[tokio::test]
 #[serial]
 async fn test_populate_proved_transfer() -> Result<()> {
-    let mut engine = setup_engine().await?;
+    let engine = setup_engine().await?;
     let mnemonic = engine.generate_mnemonic(Some(12)).await?;
     let encryption_key = "0101010101010101010101010101010101010101010101010101010101010101";
     let wallet_info = engine
@@ -111,7 +112,7 @@ async fn test_populate_proved_transfer() -> Result<()> {
     let dop_address = wallet_info["dopAddress"].as_str().unwrap().to_string();
 
     let recipients = vec![DopERC20AmountRecipient {
-        token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".to_string(),
+        token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".parse()?,
         amount: "1000".to_string(),
         recipient_address: dop_address.clone(),
     }];
@@ -124,6 +125,12 @@ async fn test_populate_proved_transfer() -> Result<()> {
 
     let result = engine
         .populate_proved_transfer(
+            &ProvedTransaction {
+                id: "unknown".to_string(),
+                parameters_hash: String::new(),
+                nullifiers: vec![],
+                created_at: OffsetDateTime::now_utc(),
+            },
             "V2_PoseidonMerkle".to_string(),
             "Ethereum_Sepolia".to_string(),
             dop_wallet_id,
@@ -158,7 +165,7 @@ async fn test_populate_proved_transfer() -> Result<()> {
 #[tokio::test]
 #[serial]
 async fn test_generate_transfer_proof() -> Result<()> {
-    let mut engine = setup_engine().await?;
+    let engine = setup_engine().await?;
     let mnemonic = engine.generate_mnemonic(Some(12)).await?;
     let encryption_key = "0101010101010101010101010101010101010101010101010101010101010101";
     let wallet_info = engine
@@ -168,7 +175,7 @@ async fn test_generate_transfer_proof() -> Result<()> {
     let dop_address = wallet_info["dopAddress"].as_str().unwrap().to_string();
 
     let recipients = vec![DopERC20AmountRecipient {
-        token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".to_string(),
+        token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".parse()?,
         amount: "1000".to_string(),
         recipient_address: dop_address.clone(),
     }];
@@ -197,3 +204,146 @@ async fn test_generate_transfer_proof() -> Result<()> {
     engine.close_engine().await?;
     Ok(())
 }
+
+#[tokio::test]
+async fn test_private_transfer_requires_proof_before_populate() -> Result<()> {
+    let engine = DopClient::new();
+    let transfer = engine
+        .private_transfer("Ethereum_Sepolia", "wallet-id", "encryption-key")
+        .memo_text("unused");
+    assert!(!transfer.is_proved());
+
+    let gas_details = TransactionGasDetails::Type0 {
+        gas_estimate: "500000".to_string(),
+        gas_price: "1000000000".to_string(),
+    };
+    let err = transfer.populate(gas_details).await.unwrap_err();
+    assert!(err.to_string().contains("must be proved"));
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_private_transfer_builder() -> Result<()> {
+    let engine = setup_engine().await?;
+    let mnemonic = engine.generate_mnemonic(Some(12)).await?;
+    let encryption_key = "0101010101010101010101010101010101010101010101010101010101010101";
+    let wallet_info = engine
+        .create_wallet(&mnemonic, encryption_key, None)
+        .await?;
+    let dop_wallet_id = wallet_info["id"].as_str().unwrap().to_string();
+    let dop_address = wallet_info["dopAddress"].as_str().unwrap().to_string();
+
+    let gas_details = TransactionGasDetails::Type2 {
+        gas_estimate: "500000".to_string(),
+        max_fee_per_gas: "10000000000".to_string(),
+        max_priority_fee_per_gas: "1000000000".to_string(),
+    };
+
+    let mut transfer = engine
+        .private_transfer("Ethereum_Sepolia", &dop_wallet_id, encryption_key)
+        .erc20_recipient(DopERC20AmountRecipient {
+            token_address: "0x5FbDB2315678afecb367f032d93F642f64180aa3".parse()?,
+            amount: "1000".to_string(),
+            recipient_address: dop_address.clone(),
+        })
+        .memo_text("builder test");
+
+    match transfer.estimate_gas(gas_details.clone(), None).await {
+        Ok(estimate) => println!("✅ Gas Estimate: {:?}", estimate.gas_estimate),
+        Err(err) => println!("⚠️ Expected error: {:?}", err),
+    }
+
+    // The new wallet has no balance, so proving fails and populate must refuse.
+    match transfer.prove().await {
+        Ok(_) => {
+            let tx = transfer.populate(gas_details).await?;
+            println!("✅ Populated proved transfer: to = {}", tx.transaction.to);
+        }
+        Err(err) => {
+            println!("⚠️ Expected error: {:?}", err);
+            assert!(transfer.populate(gas_details).await.is_err());
+        }
+    }
+
+    engine.close_engine().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_fixed_gas_fee_oracle() -> Result<()> {
+    let prices = TransactionGasDetails::Type2 {
+        gas_estimate: "0".to_string(),
+        max_fee_per_gas: "10000000000".to_string(),
+        max_priority_fee_per_gas: "1000000000".to_string(),
+    };
+
+    match prices.gas_details("Ethereum_Sepolia", "123456").await? {
+        TransactionGasDetails::Type2 {
+            gas_estimate,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        } => {
+            assert_eq!(gas_estimate, "123456");
+            assert_eq!(max_fee_per_gas, "10000000000");
+            assert_eq!(max_priority_fee_per_gas, "1000000000");
+        }
+        other => panic!("unexpected gas details: {:?}", other),
+    }
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_private_transfer_workflow() -> Result<()> {
+    let engine = setup_engine().await?;
+    let mnemonic = engine.generate_mnemonic(Some(12)).await?;
+    let encryption_key = "0101010101010101010101010101010101010101010101010101010101010101";
+    let wallet_info = engine
+        .create_wallet(&mnemonic, encryption_key, None)
+        .await?;
+    let dop_wallet_id = wallet_info["id"].as_str().unwrap().to_string();
+    let dop_address = wallet_info["dopAddress"].as_str().unwrap().to_string();
+
+    let oracle = TransactionGasDetails::Type2 {
+        gas_estimate: "0".to_string(),
+        max_fee_per_gas: "10000000000".to_string(),
+        max_priority_fee_per_gas: "1000000000".to_string(),
+    };
+    let token_address = "0x5FbDB2315678afecb367f032d93F642f64180aa3".parse()?;
+
+    let result = engine
+        .private_transfer("Ethereum_Sepolia", &dop_wallet_id, encryption_key)
+        .erc20_recipient(DopERC20AmountRecipient {
+            token_address,
+            amount: "1000".to_string(),
+            recipient_address: dop_address.clone(),
+        })
+        .transfer(
+            &oracle,
+            Some(|_: &TransactionGasDetails| {
+                Ok(DopERC20AmountRecipient {
+                    token_address,
+                    amount: "10".to_string(),
+                    recipient_address: dop_address.clone(),
+                })
+            }),
+        )
+        .await;
+
+    match result {
+        Ok(result) => {
+            println!(
+                "✅ Transfer ready: to = {}, nullifiers = {}",
+                result.transaction.to,
+                result.nullifiers.len()
+            );
+        }
+        Err(err) => {
+            println!("⚠️ Expected error: {:?}", err);
+        }
+    }
+
+    engine.close_engine().await?;
+    Ok(())
+}
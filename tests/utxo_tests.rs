@@ -0,0 +1,221 @@
+use anyhow::Result;
+use dop::dop::{
+    Chain, DecryptMode, DopClient, DopERC20AmountRecipient, PoiStatus, TransactionGasDetails,
+    UtxoRef, UtxoSelection,
+};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::time::{Duration, sleep};
+use warp::Filter;
+
+const WETH: &str = "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359";
+const PUBLIC_WALLET: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+const RECIPIENT: &str = "0zk1q8hxknrs97q8pjxaagwthzc0df99rzmhl2xnlxmgv9akv32sua0kfrv7j6fe3z53llhxknrs97q8pjxaagwthzc0df99rzmhl2xnlxmgv9akv32sua0kg0zpzts";
+
+type CallLog = Arc<Mutex<Vec<(String, Value)>>>;
+
+/// Stand-in for the Node engine: serves a fixed UTXO list and records the
+/// body of every estimate and proof request.
+fn start_stub_engine(port: u16) -> (CallLog, Arc<Mutex<HashMap<String, String>>>) {
+    let calls = CallLog::default();
+    let query_log = Arc::new(Mutex::new(HashMap::new()));
+
+    let query_clone = query_log.clone();
+    let utxos = warp::get()
+        .and(warp::path!("wallet" / String / "utxos"))
+        .and(warp::query::<HashMap<String, String>>())
+        .map(move |_id: String, query: HashMap<String, String>| {
+            *query_clone.lock().unwrap() = query;
+            warp::reply::json(&json!([
+                {
+                    "commitmentHash": "0x01",
+                    "tree": 0,
+                    "position": 4,
+                    "txid": "0xaa",
+                    "blockNumber": 100,
+                    "timestamp": 1_700_000_000u64,
+                    "tokenAddress": WETH,
+                    "value": "1000",
+                    "spent": false,
+                    "spendTxid": null,
+                    "poisPerList": { "list-a": "Valid", "list-b": "ShieldBlocked" },
+                },
+                {
+                    "commitmentHash": "0x02",
+                    "tree": 1,
+                    "position": 0,
+                    "txid": "0xbb",
+                    "blockNumber": null,
+                    "timestamp": null,
+                    "tokenAddress": WETH,
+                    "value": "250",
+                    "spent": true,
+                    "spendTxid": "0xcc",
+                    "poisPerList": null,
+                },
+            ]))
+        });
+
+    let calls_clone = calls.clone();
+    let post = warp::post()
+        .and(warp::path::param::<String>())
+        .and(warp::body::json())
+        .map(move |endpoint: String, body: Value| {
+            calls_clone
+                .lock()
+                .unwrap()
+                .push((endpoint.clone(), body.clone()));
+            let reply = if endpoint.starts_with("gas-estimate") {
+                json!({ "gasEstimate": "300000" })
+            } else {
+                json!({
+                    "success": true,
+                    "provedTransaction": {
+                        "id": body["proofJobId"],
+                        "parametersHash": "ab12",
+                        "nullifiers": ["0x01"],
+                        "createdAt": 1_700_000_000_000u64,
+                    },
+                })
+            };
+            warp::reply::json(&reply)
+        });
+
+    tokio::spawn(warp::serve(utxos.or(post)).run(([127, 0, 0, 1], port)));
+    (calls, query_log)
+}
+
+fn gas_prices() -> TransactionGasDetails {
+    TransactionGasDetails::type0(0, 1_000_000_000)
+}
+
+fn utxo(tree: u32, position: u64) -> UtxoRef {
+    UtxoRef { tree, position }
+}
+
+#[tokio::test]
+async fn test_list_utxos() -> Result<()> {
+    let (_, query) = start_stub_engine(3114);
+    sleep(Duration::from_millis(200)).await;
+    let engine = DopClient::with_port(3114);
+
+    let chain = Chain {
+        chain_type: 0,
+        id: 11155111,
+    };
+    let utxos = engine
+        .list_utxos("wallet-id", chain, Some(WETH.parse()?))
+        .await?;
+
+    let query = query.lock().unwrap().clone();
+    assert_eq!(
+        serde_json::from_str::<Value>(&query["chain"])?,
+        json!({ "type": 0, "id": 11155111 })
+    );
+    assert_eq!(query["tokenAddress"].to_lowercase(), WETH.to_lowercase());
+
+    assert_eq!(utxos.len(), 2);
+    assert_eq!(utxos[0].utxo_ref(), utxo(0, 4));
+    assert_eq!(utxos[0].amount()?, 1000);
+    assert_eq!(utxos[0].poi_status(), PoiStatus::ShieldBlocked);
+    assert!(!utxos[0].spent);
+    assert_eq!(utxos[1].poi_status(), PoiStatus::Missing);
+    assert_eq!(utxos[1].spend_txid.as_deref(), Some("0xcc"));
+    Ok(())
+}
+
+#[test]
+fn test_utxo_selection_rejects_overlap() {
+    assert!(UtxoSelection::default().is_empty());
+    assert!(UtxoSelection::only([utxo(0, 4)]).validate().is_ok());
+
+    let selection = UtxoSelection {
+        include: vec![utxo(0, 4), utxo(0, 5)],
+        exclude: vec![utxo(0, 5)],
+    };
+    let err = selection.validate().unwrap_err();
+    assert!(err.to_string().contains("0:5"));
+}
+
+#[tokio::test]
+async fn test_selection_is_sent_with_estimate_and_proof() -> Result<()> {
+    let (calls, _) = start_stub_engine(3115);
+    sleep(Duration::from_millis(200)).await;
+    let engine = DopClient::with_port(3115);
+
+    let mut transfer = engine
+        .private_transfer("Ethereum_Sepolia", "wallet-id", "encryption-key")
+        .erc20_recipient(DopERC20AmountRecipient {
+            token_address: WETH.parse()?,
+            amount: "500".to_string(),
+            recipient_address: RECIPIENT.to_string(),
+        })
+        .utxos(UtxoSelection::only([utxo(0, 4)]));
+    transfer.estimate_gas(gas_prices(), None).await?;
+    transfer.prove().await?;
+
+    let decrypt = engine
+        .decrypt("Ethereum_Sepolia", "wallet-id", "encryption-key")
+        .erc20_recipient(DopERC20AmountRecipient {
+            token_address: WETH.parse()?,
+            amount: "500".to_string(),
+            recipient_address: PUBLIC_WALLET.to_string(),
+        })
+        .utxos(UtxoSelection::excluding([utxo(1, 0)]));
+    decrypt.estimate_gas(gas_prices(), None).await?;
+
+    let calls = calls.lock().unwrap().clone();
+    let endpoints: Vec<_> = calls.iter().map(|(e, _)| e.as_str()).collect();
+    assert_eq!(
+        endpoints,
+        [
+            "gas-estimate-for-unproven-transfer",
+            "generate-transfer-proof",
+            "gas-estimate-for-unproven-decrypt",
+        ]
+    );
+    let only = json!({ "include": [{ "tree": 0, "position": 4 }], "exclude": [] });
+    assert_eq!(calls[0].1["utxoSelection"], only);
+    assert_eq!(calls[1].1["utxoSelection"], only);
+    assert_eq!(
+        calls[2].1["utxoSelection"],
+        json!({ "include": [], "exclude": [{ "tree": 1, "position": 0 }] })
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_invalid_selection_is_rejected_before_any_call() -> Result<()> {
+    let engine = DopClient::new();
+
+    let overlapping = UtxoSelection {
+        include: vec![utxo(0, 4)],
+        exclude: vec![utxo(0, 4)],
+    };
+    let result = engine
+        .private_transfer("Ethereum_Sepolia", "wallet-id", "encryption-key")
+        .erc20_recipient(DopERC20AmountRecipient {
+            token_address: WETH.parse()?,
+            amount: "500".to_string(),
+            recipient_address: RECIPIENT.to_string(),
+        })
+        .utxos(overlapping)
+        .estimate_gas(gas_prices(), None)
+        .await;
+    assert!(result.is_err());
+
+    let result = engine
+        .decrypt("Ethereum_Sepolia", "wallet-id", "encryption-key")
+        .mode(DecryptMode::ToOrigin("0xabc".to_string()))
+        .utxos(UtxoSelection::only([utxo(0, 4)]))
+        .estimate_gas(gas_prices(), None)
+        .await;
+    assert!(
+        result
+            .unwrap_err()
+            .to_string()
+            .contains("takes no UTXO selection")
+    );
+    Ok(())
+}
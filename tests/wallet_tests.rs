@@ -1,8 +1,8 @@
 use anyhow::Result;
-use dop::dop::DopClient;
-use serde_json::json;
+use dop::dop::{DopClient, KeyRotationProgress, KeyRotationStatus};
 use serial_test::serial;
 use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 #[tokio::test]
 #[serial]
@@ -131,7 +131,7 @@ async fn test_get_wallet_mnemonic() -> Result<()> {
 
 #[tokio::test]
 #[serial]
-async fn test_sign_with_wallet_viewing_key() -> Result<()> {
+async fn test_sign_with_wallet_spending_key() -> Result<()> {
     let mut engine = DopClient::new();
     engine.start();
     engine.wait_for_api_ready().await;
@@ -155,10 +155,15 @@ async fn test_sign_with_wallet_viewing_key() -> Result<()> {
     let id = wallet_info["id"].as_str().expect("Missing wallet ID");
     println!("Wallet ID: {}", id);
     let message = "Hello DOP!";
-    let signature = engine.sign_message_with_wallet(id, message).await?;
+    let signature = engine
+        .sign_message_with_wallet(id, encryption_key, message)
+        .await?;
 
     println!("Signature: {}", signature);
-    assert!(!signature.is_empty(), "Signature should not be empty");
+    assert!(
+        !signature.to_string().is_empty(),
+        "Signature should not be empty"
+    );
 
     engine.close_engine().await?;
     Ok(())
@@ -375,3 +380,92 @@ async fn test_assert_dop_address() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+#[serial]
+async fn test_rotate_encryption_key() -> Result<()> {
+    let mut engine = DopClient::new();
+    engine.start();
+    engine.wait_for_api_ready().await;
+
+    engine
+        .init_engine(
+            Some("database/test-key-rotation.db"),
+            Some("Key Rotation Engine"),
+            Some(false),
+            Some(true),
+            Some(false),
+        )
+        .await?;
+
+    let old_key = "0101010101010101010101010101010101010101010101010101010101010101";
+    let new_key = "0303030303030303030303030303030303030303030303030303030303030303";
+    let newest_key = "0404040404040404040404040404040404040404040404040404040404040404";
+
+    let mnemonic = engine.generate_mnemonic(Some(12)).await?;
+    let wallet_info = engine.create_wallet(&mnemonic, old_key, None).await?;
+    let id = wallet_info["id"].as_str().expect("Missing wallet ID");
+
+    engine.rotate_encryption_key(id, old_key, new_key).await?;
+    assert_eq!(engine.get_wallet_mnemonic(id, new_key).await?, mnemonic);
+    assert!(engine.get_wallet_mnemonic(id, old_key).await.is_err());
+
+    // Rotating with the wrong old key must leave the wallet untouched.
+    assert!(
+        engine
+            .rotate_encryption_key(id, old_key, newest_key)
+            .await
+            .is_err()
+    );
+    assert_eq!(engine.get_wallet_mnemonic(id, new_key).await?, mnemonic);
+
+    // A dry run verifies without writing anything.
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let seen = progress.clone();
+    let report = engine
+        .rotate_encryption_key_for_all_wallets(
+            new_key,
+            newest_key,
+            true,
+            Some(move |p: KeyRotationProgress| seen.lock().unwrap().push(p)),
+        )
+        .await?;
+    assert!(report.dry_run);
+    assert!(
+        report
+            .results
+            .iter()
+            .any(|(wallet_id, status)| wallet_id == id && *status == KeyRotationStatus::Verified)
+    );
+    assert_eq!(progress.lock().unwrap().len(), report.results.len());
+    assert_eq!(engine.get_wallet_mnemonic(id, new_key).await?, mnemonic);
+
+    // Stored wallets are rotated even when they are not loaded.
+    engine.unload_wallet_by_id(id).await?;
+    assert!(
+        engine
+            .list_stored_wallet_ids()
+            .await?
+            .iter()
+            .any(|wallet_id| wallet_id == id)
+    );
+
+    let report = engine
+        .rotate_encryption_key_for_all_wallets(
+            new_key,
+            newest_key,
+            false,
+            None::<fn(KeyRotationProgress)>,
+        )
+        .await?;
+    assert!(
+        report
+            .results
+            .iter()
+            .any(|(wallet_id, status)| wallet_id == id && *status == KeyRotationStatus::Rotated)
+    );
+    assert_eq!(engine.get_wallet_mnemonic(id, newest_key).await?, mnemonic);
+
+    engine.close_engine().await?;
+    Ok(())
+}
@@ -1,6 +1,8 @@
 import express from "express";
-import { refreshBalances, rescanFullUTXOMerkletreesAndWallets, resetFullTXIDMerkletreesV2, } from "dop-wallet-v3";
+import { NETWORK_CONFIG } from "dop-sharedmodels-v3";
+import { getFallbackProviderForNetwork, refreshBalances, rescanFullUTXOMerkletreesAndWallets, resetFullTXIDMerkletreesV2, } from "dop-wallet-v3";
 import { parseChain } from "../utils/json.js"; // your helper
+import { Contract } from "ethers";
 export const balanceRouter = express.Router();
 // POST /wallet/refresh-balances
 balanceRouter.post("/refresh-balances", async (req, res) => {
@@ -50,3 +52,31 @@ balanceRouter.post("/reset-full-txid-merkletrees", async (req, res) => {
         res.status(500).send("Failed to reset TXID merkletrees");
     }
 });
+// GET /token-decimals?networkName=...&tokenAddress=...
+balanceRouter.get("/token-decimals", async (req, res) => {
+    try {
+        const { networkName, tokenAddress } = req.query;
+        if (!networkName || !tokenAddress) {
+            res.status(400).send("Missing networkName or tokenAddress");
+            return;
+        }
+        const provider = getFallbackProviderForNetwork(networkName);
+        const token = new Contract(tokenAddress, ["function decimals() view returns (uint8)"], provider);
+        const decimals = await token.decimals();
+        res.json({ decimals: Number(decimals) });
+    }
+    catch (err) {
+        console.error("❌ Failed to read token decimals:", err);
+        res.status(500).send("Failed to read token decimals");
+    }
+});
+// GET /network-chain?networkName=...
+balanceRouter.get("/network-chain", (req, res) => {
+    const networkName = req.query.networkName;
+    const config = networkName ? NETWORK_CONFIG[networkName] : undefined;
+    if (!config) {
+        res.status(400).send("Unknown networkName");
+        return;
+    }
+    res.json(config.chain);
+});
@@ -1,6 +1,26 @@
 import express from "express";
 import { setOnUTXOMerkletreeScanCallback, setOnTXIDMerkletreeScanCallback, } from "dop-wallet-v3";
+import { throwIfProofCancelled } from "../core/proof-jobs.js";
 export const callbackRouter = express.Router();
+let forwardProofProgress = false;
+// Progress callback for the prover, forwarded once the Rust side asks for it.
+// Throwing here is how a cancelled job stops the prover early.
+export const proofProgressCallback = (proofType, job) => (progress, status) => {
+    throwIfProofCancelled(job);
+    if (!forwardProofProgress) {
+        return;
+    }
+    fetch("http://localhost:4000/proof-progress", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({
+            proofJobId: job.id,
+            proofType,
+            progress,
+            status: status ?? "",
+        }),
+    }).catch(console.error);
+};
 // Setup UTXO Scan Callback
 callbackRouter.post("/setup-utxo-scan-callback", (req, res) => {
     setOnUTXOMerkletreeScanCallback(async (scanData) => {
@@ -23,3 +43,8 @@ callbackRouter.post("/setup-txid-scan-callback", (req, res) => {
     });
     res.sendStatus(204);
 });
+// Setup Proof Progress Callback
+callbackRouter.post("/setup-proof-progress-callback", (req, res) => {
+    forwardProofProgress = true;
+    res.sendStatus(204);
+});
@@ -1,12 +1,13 @@
 import express from "express";
 import { initEngine, closeEngine, getEngineInstanceInfo, scanContractHistory, } from "../core/engine.js";
 import { loadProvider, setLoggers, } from "dop-wallet-v3";
+import { CIRCUIT_LIMITS } from "../core/circuits.js";
 import { parseChain } from "../utils/json.js";
 // engine-api.ts
 export const engineRouter = express.Router();
 // api/engine-api.ts
 engineRouter.post("/init", async (req, res) => {
-    const { engineName, dbPath, shouldDebug, useNativeArtifacts, skipMerkletreeScans, } = req.body;
+    const { engineName, dbPath, shouldDebug, useNativeArtifacts, skipMerkletreeScans, poiNodeURLs, } = req.body;
     try {
         await initEngine({
             engineName,
@@ -14,6 +15,7 @@ engineRouter.post("/init", async (req, res) => {
             shouldDebug,
             useNativeArtifacts,
             skipMerkletreeScans,
+            poiNodeURLs,
         });
         res.send({
             message: "Engine initialized successfully",
@@ -22,6 +24,7 @@ engineRouter.post("/init", async (req, res) => {
             debug: shouldDebug ?? false,
             nativeArtifacts: useNativeArtifacts ?? false,
             skipMerkletreeScans: skipMerkletreeScans ?? false,
+            poiNodeURLs: poiNodeURLs ?? [],
         });
     }
     catch (err) {
@@ -38,6 +41,9 @@ engineRouter.get("/engine", (req, res) => {
         res.json(info);
     }
 });
+engineRouter.get("/circuit-limits", (req, res) => {
+    res.json(CIRCUIT_LIMITS);
+});
 engineRouter.get("/close", async (req, res) => {
     try {
         await closeEngine();
@@ -6,6 +6,9 @@ import { callbackRouter } from "./callback.js";
 import { txEncyptRouter } from "./tx-encrypt-api.js";
 import { txTransferRouter } from "./tx-transfer.js";
 import { txDecryptRouter } from "./tx-decrypt.js";
+import { proofRouter } from "./proof-api.js";
+import { providerRouter } from "./provider-api.js";
+import { poiRouter } from "./poi-api.js";
 export const app = express();
 app.use(express.json());
 app.get("/health", (req, res) => {
@@ -19,3 +22,6 @@ app.use(callbackRouter);
 app.use(txEncyptRouter);
 app.use(txTransferRouter);
 app.use(txDecryptRouter);
+app.use(proofRouter);
+app.use(providerRouter);
+app.use(poiRouter);
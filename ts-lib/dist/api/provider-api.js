@@ -0,0 +1,83 @@
+import express from "express";
+import { isError } from "ethers";
+import { getGasFeeData, getTransactionCount, sendRawTransaction, waitForConfirmation, } from "../core/provider.js";
+export const providerRouter = express.Router();
+// POST /send-raw-transaction
+providerRouter.post("/send-raw-transaction", async (req, res) => {
+    const { networkName, signedTransaction } = req.body;
+    if (!networkName || !signedTransaction) {
+        res.status(400).send("Missing networkName or signedTransaction");
+        return;
+    }
+    try {
+        const txHash = await sendRawTransaction(networkName, signedTransaction);
+        res.json({ txHash });
+    }
+    catch (err) {
+        console.error("❌ Failed to send transaction:", err);
+        res.status(500).json({
+            error: "Failed to send transaction",
+            details: err?.message ?? String(err),
+        });
+    }
+});
+// GET /transaction-count?networkName=...&address=...
+providerRouter.get("/transaction-count", async (req, res) => {
+    const { networkName, address } = req.query;
+    if (!networkName || !address) {
+        res.status(400).send("Missing networkName or address");
+        return;
+    }
+    try {
+        const nonce = await getTransactionCount(networkName, address);
+        res.json({ nonce });
+    }
+    catch (err) {
+        console.error("❌ Failed to get transaction count:", err);
+        res.status(500).send("Failed to get transaction count");
+    }
+});
+// POST /wait-for-confirmation
+providerRouter.post("/wait-for-confirmation", async (req, res) => {
+    const { networkName, txHash, confirmations, timeoutMs } = req.body;
+    if (!networkName || !txHash) {
+        res.status(400).send("Missing networkName or txHash");
+        return;
+    }
+    try {
+        const receipt = await waitForConfirmation(networkName, txHash, confirmations ?? 1, timeoutMs);
+        if (!receipt) {
+            res.status(404).send("Transaction not mined");
+            return;
+        }
+        res.json(receipt);
+    }
+    catch (err) {
+        if (isError(err, "TIMEOUT")) {
+            res.status(408).send("Timed out waiting for confirmation");
+            return;
+        }
+        console.error("❌ Failed to wait for confirmation:", err);
+        res.status(500).json({
+            error: "Failed to wait for confirmation",
+            details: err?.message ?? String(err),
+        });
+    }
+});
+
+// GET /gas-fee-data?networkName=...&sendWithPublicWallet=...
+providerRouter.get("/gas-fee-data", async (req, res) => {
+    const { networkName, sendWithPublicWallet } = req.query;
+    if (!networkName) {
+        res.status(400).send("Missing networkName");
+        return;
+    }
+    try {
+        const feeData = await getGasFeeData(networkName, sendWithPublicWallet !== "false");
+        res.json(feeData);
+    }
+    catch (err) {
+        console.error("❌ Failed to get gas fee data:", err);
+        res.status(500).send("Failed to get gas fee data");
+    }
+});
@@ -1,18 +1,51 @@
 import express from "express";
 import { populateProvedDecrypt, populateProvedDecryptBaseToken, gasEstimateForUnprovenDecrypt, gasEstimateForUnprovenDecryptBaseToken, getERC20AndNFTAmountRecipientsForDecryptToOrigin, populateProvedDecryptToOrigin, gasEstimateForUnprovenDecryptToOrigin, generateDecryptProof, generateDecryptToOriginProof, generateDecryptBaseTokenProof, } from "dop-wallet-v3";
+import { proofProgressCallback } from "./callback.js";
+import { assertProofUsable, completeProofJob, endProofJob, proofParametersHash, startProofJob, } from "../core/proof-jobs.js";
+import { withUtxoSelection } from "../core/utxos.js";
+import { getDecryptToOriginCandidates } from "../core/wallet.js";
 export const txDecryptRouter = express.Router();
+// Parameters shared by each decrypt proof and its populate call
+const decryptParametersHash = (body) => proofParametersHash("decrypt", [
+    body.txidVersion,
+    body.networkName,
+    body.dopWalletID,
+    body.erc20AmountRecipients,
+    body.nftAmountRecipients,
+    body.broadcasterFeeERC20AmountRecipient,
+    body.sendWithPublicWallet,
+    body.overallBatchMinGasPrice,
+]);
+const decryptToOriginParametersHash = (body) => proofParametersHash("decrypt-to-origin", [
+    body.txidVersion,
+    body.networkName,
+    body.dopWalletID,
+    body.erc20AmountRecipients,
+    body.nftAmountRecipients,
+]);
+const decryptBaseTokenParametersHash = (body) => proofParametersHash("decrypt-base-token", [
+    body.txidVersion,
+    body.networkName,
+    body.publicWalletAddress,
+    body.dopWalletID,
+    body.wrappedERC20Amount,
+    body.broadcasterFeeERC20AmountRecipient,
+    body.sendWithPublicWallet,
+    body.overallBatchMinGasPrice,
+]);
 function safeJsonResponse(res, data) {
     res.setHeader("Content-Type", "application/json");
     res.send(JSON.stringify(data, (_, v) => (typeof v === "bigint" ? v.toString() : v)));
 }
 // POST /wallet/generate-decrypt-proof
 txDecryptRouter.post("/generate-decrypt-proof", async (req, res) => {
+    const job = startProofJob(req.body.proofJobId);
     try {
-        await generateDecryptProof(req.body.txidVersion, req.body.networkName, req.body.dopWalletID, req.body.encryptionKey, req.body.erc20AmountRecipients, req.body.nftAmountRecipients, req.body.broadcasterFeeERC20AmountRecipient, req.body.sendWithPublicWallet, req.body.overallBatchMinGasPrice
+        await withUtxoSelection(req.body.dopWalletID, req.body.utxoSelection, () => generateDecryptProof(req.body.txidVersion, req.body.networkName, req.body.dopWalletID, req.body.encryptionKey, req.body.erc20AmountRecipients, req.body.nftAmountRecipients, req.body.broadcasterFeeERC20AmountRecipient, req.body.sendWithPublicWallet, req.body.overallBatchMinGasPrice
             ? BigInt(req.body.overallBatchMinGasPrice)
-            : null, () => { }, // Empty callback
-        BigInt(req.body.value));
-        res.json({ success: true });
+            : null, proofProgressCallback("decrypt", job), BigInt(req.body.value)));
+        const proved = completeProofJob(job, decryptParametersHash(req.body));
+        res.json({ success: true, provedTransaction: proved });
     }
     catch (err) {
         res.status(500).json({
@@ -20,13 +53,17 @@ txDecryptRouter.post("/generate-decrypt-proof", async (req, res) => {
             details: String(err),
         });
     }
+    finally {
+        endProofJob(job);
+    }
 });
 // POST /wallet/generate-decrypt-to-origin-proof
 txDecryptRouter.post("/generate-decrypt-to-origin-proof", async (req, res) => {
+    const job = startProofJob(req.body.proofJobId);
     try {
-        await generateDecryptToOriginProof(req.body.originalEncryptTxid, req.body.txidVersion, req.body.networkName, req.body.dopWalletID, req.body.encryptionKey, req.body.erc20AmountRecipients, req.body.nftAmountRecipients, () => { }, // Empty callback
-        BigInt(req.body.value));
-        res.json({ success: true });
+        await generateDecryptToOriginProof(req.body.originalEncryptTxid, req.body.txidVersion, req.body.networkName, req.body.dopWalletID, req.body.encryptionKey, req.body.erc20AmountRecipients, req.body.nftAmountRecipients, proofProgressCallback("decrypt-to-origin", job), BigInt(req.body.value));
+        const proved = completeProofJob(job, decryptToOriginParametersHash(req.body));
+        res.json({ success: true, provedTransaction: proved });
     }
     catch (err) {
         res.status(500).json({
@@ -34,15 +71,19 @@ txDecryptRouter.post("/generate-decrypt-to-origin-proof", async (req, res) => {
             details: String(err),
         });
     }
+    finally {
+        endProofJob(job);
+    }
 });
 // POST /wallet/generate-decrypt-base-token-proof
 txDecryptRouter.post("/generate-decrypt-base-token-proof", async (req, res) => {
+    const job = startProofJob(req.body.proofJobId);
     try {
-        await generateDecryptBaseTokenProof(req.body.txidVersion, req.body.networkName, req.body.publicWalletAddress, req.body.dopWalletID, req.body.encryptionKey, req.body.wrappedERC20Amount, req.body.broadcasterFeeERC20AmountRecipient, req.body.sendWithPublicWallet, req.body.overallBatchMinGasPrice
+        await withUtxoSelection(req.body.dopWalletID, req.body.utxoSelection, () => generateDecryptBaseTokenProof(req.body.txidVersion, req.body.networkName, req.body.publicWalletAddress, req.body.dopWalletID, req.body.encryptionKey, req.body.wrappedERC20Amount, req.body.broadcasterFeeERC20AmountRecipient, req.body.sendWithPublicWallet, req.body.overallBatchMinGasPrice
             ? BigInt(req.body.overallBatchMinGasPrice)
-            : null, () => { } // Empty callback
-        );
-        res.json({ success: true });
+            : null, proofProgressCallback("decrypt-base-token", job)));
+        const proved = completeProofJob(job, decryptBaseTokenParametersHash(req.body));
+        res.json({ success: true, provedTransaction: proved });
     }
     catch (err) {
         res.status(500).json({
@@ -50,10 +91,14 @@ txDecryptRouter.post("/generate-decrypt-base-token-proof", async (req, res) => {
             details: String(err),
         });
     }
+    finally {
+        endProofJob(job);
+    }
 });
 // POST /wallet/populate-proved-decrypt
 txDecryptRouter.post("/populate-proved-decrypt", async (req, res) => {
     try {
+        assertProofUsable(req.body.provedTransaction, decryptParametersHash(req.body));
         const tx = await populateProvedDecrypt(req.body.txidVersion, req.body.networkName, req.body.dopWalletID, req.body.erc20AmountRecipients, req.body.nftAmountRecipients, req.body.broadcasterFeeERC20AmountRecipient, req.body.sendWithPublicWallet, req.body.overallBatchMinGasPrice
             ? BigInt(req.body.overallBatchMinGasPrice)
             : null, req.body.gasDetails);
@@ -68,6 +113,7 @@ txDecryptRouter.post("/populate-proved-decrypt", async (req, res) => {
 // POST /wallet/populate-proved-decrypt-base-token
 txDecryptRouter.post("/populate-proved-decrypt-base-token", async (req, res) => {
     try {
+        assertProofUsable(req.body.provedTransaction, decryptBaseTokenParametersHash(req.body));
         const tx = await populateProvedDecryptBaseToken(req.body.txidVersion, req.body.networkName, req.body.publicWalletAddress, req.body.dopWalletID, req.body.wrappedERC20Amount, req.body.broadcasterFeeERC20AmountRecipient, req.body.sendWithPublicWallet, req.body.overallBatchMinGasPrice
             ? BigInt(req.body.overallBatchMinGasPrice)
             : null, req.body.gasDetails);
@@ -83,7 +129,7 @@ txDecryptRouter.post("/populate-proved-decrypt-base-token", async (req, res) =>
 // POST /wallet/gas-estimate-for-unproven-decrypt
 txDecryptRouter.post("/gas-estimate-for-unproven-decrypt", async (req, res) => {
     try {
-        const estimate = await gasEstimateForUnprovenDecrypt(req.body.txidVersion, req.body.networkName, req.body.dopWalletID, req.body.encryptionKey, req.body.erc20AmountRecipients, req.body.nftAmountRecipients, req.body.originalGasDetails, req.body.feeTokenDetails, req.body.sendWithPublicWallet, BigInt(req.body.value));
+        const estimate = await withUtxoSelection(req.body.dopWalletID, req.body.utxoSelection, () => gasEstimateForUnprovenDecrypt(req.body.txidVersion, req.body.networkName, req.body.dopWalletID, req.body.encryptionKey, req.body.erc20AmountRecipients, req.body.nftAmountRecipients, req.body.originalGasDetails, req.body.feeTokenDetails, req.body.sendWithPublicWallet, BigInt(req.body.value)));
         safeJsonResponse(res, estimate);
     }
     catch (err) {
@@ -96,7 +142,7 @@ txDecryptRouter.post("/gas-estimate-for-unproven-decrypt", async (req, res) => {
 // POST /wallet/gas-estimate-for-unproven-decrypt-base-token
 txDecryptRouter.post("/gas-estimate-for-unproven-decrypt-base-token", async (req, res) => {
     try {
-        const estimate = await gasEstimateForUnprovenDecryptBaseToken(req.body.txidVersion, req.body.networkName, req.body.publicWalletAddress, req.body.dopWalletID, req.body.encryptionKey, req.body.wrappedERC20Amount, req.body.originalGasDetails, req.body.feeTokenDetails, req.body.sendWithPublicWallet);
+        const estimate = await withUtxoSelection(req.body.dopWalletID, req.body.utxoSelection, () => gasEstimateForUnprovenDecryptBaseToken(req.body.txidVersion, req.body.networkName, req.body.publicWalletAddress, req.body.dopWalletID, req.body.encryptionKey, req.body.wrappedERC20Amount, req.body.originalGasDetails, req.body.feeTokenDetails, req.body.sendWithPublicWallet));
         safeJsonResponse(res, estimate);
     }
     catch (err) {
@@ -119,9 +165,23 @@ txDecryptRouter.post("/get-recipients-for-decrypt-to-origin", async (req, res) =
         });
     }
 });
+// POST /wallet/decrypt-to-origin-candidates
+txDecryptRouter.post("/decrypt-to-origin-candidates", async (req, res) => {
+    try {
+        const candidates = await getDecryptToOriginCandidates(req.body.txidVersion, req.body.networkName, req.body.dopWalletID);
+        safeJsonResponse(res, candidates);
+    }
+    catch (err) {
+        res.status(500).json({
+            error: "getDecryptToOriginCandidates failed",
+            details: String(err),
+        });
+    }
+});
 // POST /wallet/populate-proved-decrypt-to-origin
 txDecryptRouter.post("/populate-proved-decrypt-to-origin", async (req, res) => {
     try {
+        assertProofUsable(req.body.provedTransaction, decryptToOriginParametersHash(req.body));
         const tx = await populateProvedDecryptToOrigin(req.body.txidVersion, req.body.networkName, req.body.dopWalletID, req.body.erc20AmountRecipients, req.body.nftAmountRecipients, req.body.gasDetails);
         safeJsonResponse(res, tx);
     }
@@ -1,6 +1,23 @@
 import express from "express";
 import { populateProvedTransfer, gasEstimateForUnprovenTransfer, generateTransferProof, } from "dop-wallet-v3";
+import { proofProgressCallback } from "./callback.js";
+import { assertProofUsable, completeProofJob, endProofJob, proofParametersHash, startProofJob, } from "../core/proof-jobs.js";
+import { assertValidMemoText } from "../core/memo.js";
+import { withUtxoSelection } from "../core/utxos.js";
 export const txTransferRouter = express.Router();
+// Parameters shared by generate-transfer-proof and populate-proved-transfer
+const transferParametersHash = (body) => proofParametersHash("transfer", [
+    body.txidVersion,
+    body.networkName,
+    body.dopWalletID,
+    body.showSenderAddressToRecipient ?? false,
+    body.memoText,
+    body.erc20AmountRecipients ?? [],
+    body.nftAmountRecipients ?? [],
+    body.broadcasterFeeERC20AmountRecipient,
+    body.sendWithPublicWallet ?? false,
+    body.overallBatchMinGasPrice,
+]);
 function safeJsonResponse(res, data) {
     res.setHeader("Content-Type", "application/json");
     res.send(JSON.stringify(data, (key, value) => typeof value === "bigint" ? value.toString() : value));
@@ -8,11 +25,13 @@ function safeJsonResponse(res, data) {
 // POST /wallet/populate-proved-transfer
 txTransferRouter.post("/populate-proved-transfer", async (req, res) => {
     try {
-        const { txidVersion, networkName, dopWalletID, showSenderAddressToRecipient, memoText, erc20AmountRecipients, nftAmountRecipients, broadcasterFeeERC20AmountRecipient, sendWithPublicWallet, overallBatchMinGasPrice, gasDetails, } = req.body;
+        const { txidVersion, networkName, dopWalletID, showSenderAddressToRecipient, memoText, erc20AmountRecipients, nftAmountRecipients, broadcasterFeeERC20AmountRecipient, sendWithPublicWallet, overallBatchMinGasPrice, gasDetails, provedTransaction, } = req.body;
         if (!txidVersion || !networkName || !dopWalletID || !gasDetails) {
             res.status(400).send("Missing required fields");
             return;
         }
+        assertValidMemoText(memoText);
+        assertProofUsable(provedTransaction, transferParametersHash(req.body));
         const tx = await populateProvedTransfer(txidVersion, networkName, dopWalletID, showSenderAddressToRecipient ?? false, memoText, erc20AmountRecipients ?? [], nftAmountRecipients ?? [], broadcasterFeeERC20AmountRecipient, sendWithPublicWallet ?? false, overallBatchMinGasPrice, gasDetails);
         safeJsonResponse(res, tx);
     }
@@ -26,7 +45,7 @@ txTransferRouter.post("/populate-proved-transfer", async (req, res) => {
 // POST /wallet/gas-estimate-for-unproven-transfer
 txTransferRouter.post("/gas-estimate-for-unproven-transfer", async (req, res) => {
     try {
-        const { txidVersion, networkName, dopWalletID, encryptionKey, memoText, erc20AmountRecipients, nftAmountRecipients, originalGasDetails, feeTokenDetails, sendWithPublicWallet, } = req.body;
+        const { txidVersion, networkName, dopWalletID, encryptionKey, memoText, erc20AmountRecipients, nftAmountRecipients, originalGasDetails, feeTokenDetails, sendWithPublicWallet, utxoSelection, } = req.body;
         if (!txidVersion ||
             !networkName ||
             !dopWalletID ||
@@ -35,7 +54,8 @@ txTransferRouter.post("/gas-estimate-for-unproven-transfer", async (req, res) =>
             res.status(400).send("Missing required fields");
             return;
         }
-        const estimate = await gasEstimateForUnprovenTransfer(txidVersion, networkName, dopWalletID, encryptionKey, memoText, erc20AmountRecipients ?? [], nftAmountRecipients ?? [], originalGasDetails, feeTokenDetails, sendWithPublicWallet ?? false);
+        assertValidMemoText(memoText);
+        const estimate = await withUtxoSelection(dopWalletID, utxoSelection, () => gasEstimateForUnprovenTransfer(txidVersion, networkName, dopWalletID, encryptionKey, memoText, erc20AmountRecipients ?? [], nftAmountRecipients ?? [], originalGasDetails, feeTokenDetails, sendWithPublicWallet ?? false));
         safeJsonResponse(res, estimate);
     }
     catch (err) {
@@ -46,16 +66,20 @@ txTransferRouter.post("/gas-estimate-for-unproven-transfer", async (req, res) =>
     }
 });
 txTransferRouter.post("/generate-transfer-proof", async (req, res) => {
+    const job = startProofJob(req.body.proofJobId);
     try {
-        const { txidVersion, networkName, dopWalletID, encryptionKey, showSenderAddressToRecipient, memoText, erc20AmountRecipients, nftAmountRecipients, broadcasterFeeERC20AmountRecipient, sendWithPublicWallet, overallBatchMinGasPrice, } = req.body;
+        const { txidVersion, networkName, dopWalletID, encryptionKey, showSenderAddressToRecipient, memoText, erc20AmountRecipients, nftAmountRecipients, broadcasterFeeERC20AmountRecipient, sendWithPublicWallet, overallBatchMinGasPrice, utxoSelection, } = req.body;
         if (!txidVersion || !networkName || !dopWalletID || !encryptionKey) {
             res.status(400).send("Missing required fields");
             return;
         }
-        await generateTransferProof(txidVersion, networkName, dopWalletID, encryptionKey, showSenderAddressToRecipient ?? false, memoText, erc20AmountRecipients ?? [], nftAmountRecipients ?? [], broadcasterFeeERC20AmountRecipient, sendWithPublicWallet ?? false, overallBatchMinGasPrice, (_progress) => {
-            console.log("🔄 Generating transfer proof...");
+        assertValidMemoText(memoText);
+        await withUtxoSelection(dopWalletID, utxoSelection, () => generateTransferProof(txidVersion, networkName, dopWalletID, encryptionKey, showSenderAddressToRecipient ?? false, memoText, erc20AmountRecipients ?? [], nftAmountRecipients ?? [], broadcasterFeeERC20AmountRecipient, sendWithPublicWallet ?? false, overallBatchMinGasPrice, proofProgressCallback("transfer", job)));
+        const proved = completeProofJob(job, transferParametersHash(req.body));
+        res.status(200).json({
+            message: "Transfer proof generation complete",
+            provedTransaction: proved,
         });
-        res.status(200).json({ message: "Transfer proof generation complete" });
     }
     catch (err) {
         res.status(500).json({
@@ -63,4 +87,7 @@ txTransferRouter.post("/generate-transfer-proof", async (req, res) => {
             details: err?.message ?? String(err),
         });
     }
+    finally {
+        endProofJob(job);
+    }
 });
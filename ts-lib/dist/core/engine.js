@@ -15,9 +15,9 @@ const artifactStore = new ArtifactStore(fs.promises.readFile, async (dir, path,
     await fs.promises.writeFile(path, data);
 }, fileExists);
 // core/engine.ts
-export const initEngine = async ({ engineName = "DOP Engine", dbPath = "database/DOP.db", shouldDebug = false, useNativeArtifacts = false, skipMerkletreeScans = false, }) => {
+export const initEngine = async ({ engineName = "DOP Engine", dbPath = "database/DOP.db", shouldDebug = false, useNativeArtifacts = false, skipMerkletreeScans = false, poiNodeURLs, }) => {
     const db = new LevelDOWN(dbPath);
-    return await startDopEngine(engineName, db, shouldDebug, artifactStore, useNativeArtifacts, skipMerkletreeScans);
+    return await startDopEngine(engineName, db, shouldDebug, artifactStore, useNativeArtifacts, skipMerkletreeScans, poiNodeURLs);
 };
 export const getEngineInstance = () => getEngine();
 export const getEngineInstanceInfo = () => {
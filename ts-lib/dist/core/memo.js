@@ -0,0 +1,16 @@
+import { Memo } from "dop-engine-v3";
+// Largest memo the transfer routes accept, measured on the engine's encoding
+// of the text. The memo is encrypted into every output note, so it is capped
+// here rather than left to fail at gas estimation. Mirrored in Rust as
+// MAX_MEMO_ENCODED_BYTES.
+export const MAX_MEMO_ENCODED_BYTES = 1024;
+export const encodedMemoSize = (memoText) => memoText
+    ? Buffer.from(Memo.encodeMemoText(memoText).replace(/^0x/, ""), "hex")
+        .length
+    : 0;
+export const assertValidMemoText = (memoText) => {
+    const size = encodedMemoSize(memoText);
+    if (size > MAX_MEMO_ENCODED_BYTES) {
+        throw new Error(`Memo text encodes to ${size} bytes, the maximum is ${MAX_MEMO_ENCODED_BYTES}`);
+    }
+};
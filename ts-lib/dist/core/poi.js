@@ -0,0 +1,35 @@
+// core/poi.ts
+import { generatePOIsForWallet, getChainTxidsStillPendingSpentPOIs, getPOIRequiredForNetwork, getTXOsReceivedPOIStatusInfoForWallet, refreshReceivePOIsForWallet, } from "dop-wallet-v3";
+// Statuses per list come either as an object or as its JSON string, and
+// are missing until the engine has asked a POI node.
+export const parsePOIsPerList = (poisPerList) => {
+    if (typeof poisPerList === "string") {
+        return poisPerList.startsWith("{") ? JSON.parse(poisPerList) : null;
+    }
+    return poisPerList ?? null;
+};
+// One entry per note the wallet has received.
+export const getReceivedPOIStatuses = async (txidVersion, networkName, id) => {
+    const infos = await getTXOsReceivedPOIStatusInfoForWallet(txidVersion, networkName, id);
+    return infos.map(({ strings }) => ({
+        tree: Number(strings.tree),
+        position: Number(strings.position),
+        txid: strings.txid,
+        commitment: strings.commitment,
+        blindedCommitment: strings.blindedCommitment,
+        poisPerList: parsePOIsPerList(strings.poisPerList),
+    }));
+};
+// Transactions sent from the wallet whose spent POIs are not yet valid.
+export const getTxidsPendingPOIs = async (txidVersion, networkName, id) => {
+    return await getChainTxidsStillPendingSpentPOIs(txidVersion, networkName, id);
+};
+export const generateWalletPOIs = async (networkName, id) => {
+    await generatePOIsForWallet(networkName, id);
+};
+export const refreshReceivedPOIs = async (txidVersion, networkName, id) => {
+    await refreshReceivePOIsForWallet(txidVersion, networkName, id);
+};
+export const isPOIRequired = async (networkName) => {
+    return await getPOIRequiredForNetwork(networkName);
+};
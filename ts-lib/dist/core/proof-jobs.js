@@ -0,0 +1,76 @@
+import { createHash, randomUUID } from "crypto";
+import { getCachedProvedTransaction } from "dop-wallet-v3";
+// Proofs older than this are rejected: the merkle root they commit to may
+// no longer be accepted on chain.
+const MAX_PROOF_AGE_MS = 15 * 60 * 1000;
+const runningJobs = new Map();
+// The proof dop-wallet-v3 currently holds in its proof cache.
+// Cleared whenever that proof must not be populated.
+let provedTransaction;
+// Hash of the parameters shared by a proof and its populate call, in a fixed
+// order, so populate can check it uses the parameters that were proved.
+export const proofParametersHash = (proofType, params) => createHash("sha256")
+    .update(JSON.stringify([proofType, ...params], (_, v) => typeof v === "bigint" ? v.toString() : v))
+    .digest("hex");
+// Nullifiers of the proof dop-wallet-v3 just cached. Spent and double-spend
+// checks rely on them, so a proof without nullifiers is an error.
+const cachedNullifiers = () => {
+    const nullifiers = getCachedProvedTransaction()?.nullifiers;
+    if (!nullifiers?.length) {
+        throw new Error("Proof cache holds no nullifiers for the generated proof");
+    }
+    return nullifiers;
+};
+export const startProofJob = (id) => {
+    const job = { id: id || randomUUID(), cancelled: false };
+    runningJobs.set(job.id, job);
+    // A new proof replaces the cached one, whether or not it succeeds
+    provedTransaction = undefined;
+    return job;
+};
+export const throwIfProofCancelled = (job) => {
+    if (job.cancelled) {
+        throw new Error(`Proof job ${job.id} was cancelled`);
+    }
+};
+export const completeProofJob = (job, parametersHash) => {
+    throwIfProofCancelled(job);
+    provedTransaction = {
+        id: job.id,
+        parametersHash,
+        nullifiers: cachedNullifiers(),
+        createdAt: Date.now(),
+    };
+    return provedTransaction;
+};
+export const endProofJob = (job) => {
+    runningJobs.delete(job.id);
+};
+export const cancelProofJob = (id) => {
+    let found = false;
+    const job = runningJobs.get(id);
+    if (job) {
+        job.cancelled = true;
+        found = true;
+    }
+    if (provedTransaction?.id === id) {
+        provedTransaction = undefined;
+        found = true;
+    }
+    return found;
+};
+export const assertProofUsable = (handle, parametersHash) => {
+    if (!provedTransaction) {
+        throw new Error("No usable proof: the last proof was cancelled, failed or is still running");
+    }
+    if (!handle || handle.id !== provedTransaction.id) {
+        throw new Error("Proved transaction has been replaced by a newer proof");
+    }
+    if (handle.parametersHash !== provedTransaction.parametersHash ||
+        parametersHash !== provedTransaction.parametersHash) {
+        throw new Error("Transaction parameters do not match the proof");
+    }
+    if (Date.now() - provedTransaction.createdAt > MAX_PROOF_AGE_MS) {
+        throw new Error("Proof is stale, generate a new one");
+    }
+};
@@ -0,0 +1,89 @@
+// core/utxos.ts
+import { AsyncLocalStorage } from "async_hooks";
+import { TXIDVersion } from "dop-sharedmodels-v3";
+import { walletForID } from "dop-wallet-v3";
+import { parsePOIsPerList } from "./poi.js";
+const utxoKey = (utxo) => `${utxo.tree}:${utxo.position}`;
+const toHex32 = (value) => "0x" + BigInt(value).toString(16).padStart(64, "0");
+// The selection of the request being served. Only code running inside
+// `withUtxoSelection` sees it, so scans and other requests on the same
+// wallet keep seeing every note.
+const selectionContext = new AsyncLocalStorage();
+// Last selection queued per wallet. Each one wraps the wallet's TXOs method
+// while it runs, so selections on one wallet run one after another.
+const selectionQueues = new Map();
+const runQueued = async (id, fn) => {
+    const previous = selectionQueues.get(id) ?? Promise.resolve();
+    const run = previous.then(fn);
+    const done = run.catch(() => undefined);
+    selectionQueues.set(id, done);
+    try {
+        return await run;
+    }
+    finally {
+        if (selectionQueues.get(id) === done) {
+            selectionQueues.delete(id);
+        }
+    }
+};
+// Every note the wallet has received on `chain`, spent ones included,
+// ordered by tree and position.
+export const listUtxos = async (id, chain, tokenAddress) => {
+    const wallet = walletForID(id);
+    const txos = await wallet.TXOs(TXIDVersion.V2_PoseidonMerkle, chain);
+    const token = tokenAddress?.toLowerCase();
+    return txos
+        .filter((txo) => !token || txo.note.tokenData.tokenAddress.toLowerCase() === token)
+        .map((txo) => ({
+        commitmentHash: toHex32(txo.note.hash),
+        tree: txo.tree,
+        position: txo.position,
+        txid: txo.txid,
+        blockNumber: txo.blockNumber ?? null,
+        timestamp: txo.timestamp ?? null,
+        tokenAddress: txo.note.tokenData.tokenAddress,
+        value: String(txo.note.value),
+        spent: Boolean(txo.spendtxid),
+        spendTxid: txo.spendtxid || null,
+        poisPerList: parsePOIsPerList(txo.poisPerList),
+    }))
+        .sort((a, b) => a.tree - b.tree || a.position - b.position);
+};
+// Runs `fn` while the engine only sees the selected notes of wallet `id`
+// when choosing what to spend.
+export const withUtxoSelection = async (id, selection, fn) => {
+    if (!selection?.include?.length && !selection?.exclude?.length) {
+        return await fn();
+    }
+    const active = {
+        include: selection.include?.length
+            ? new Set(selection.include.map(utxoKey))
+            : undefined,
+        exclude: new Set((selection.exclude ?? []).map(utxoKey)),
+    };
+    return await runQueued(id, async () => {
+        const wallet = walletForID(id);
+        const originalTXOs = wallet.TXOs;
+        wallet.TXOs = async (txidVersion, chain) => {
+            const all = await originalTXOs.call(wallet, txidVersion, chain);
+            if (selectionContext.getStore() !== active) {
+                return all;
+            }
+            const { include, exclude } = active;
+            if (include) {
+                const unspent = new Set(all.filter((txo) => !txo.spendtxid).map(utxoKey));
+                const missing = [...include].filter((key) => !unspent.has(key));
+                if (missing.length) {
+                    throw new Error(`Selected UTXOs not found or spent: ${missing}`);
+                }
+            }
+            return all.filter((txo) => (!include || include.has(utxoKey(txo))) && !exclude.has(utxoKey(txo)));
+        };
+        try {
+            return await selectionContext.run(active, fn);
+        }
+        finally {
+            wallet.TXOs = originalTXOs;
+        }
+    });
+};
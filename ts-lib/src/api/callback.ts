@@ -3,9 +3,32 @@ import {
   setOnUTXOMerkletreeScanCallback,
   setOnTXIDMerkletreeScanCallback,
 } from "dop-wallet-v3";
+import { ProofJob, throwIfProofCancelled } from "../core/proof-jobs.js";
 
 export const callbackRouter = express.Router();
 
+let forwardProofProgress = false;
+
+// Progress callback for the prover, forwarded once the Rust side asks for it.
+// Throwing here is how a cancelled job stops the prover early.
+export const proofProgressCallback =
+  (proofType: string, job: ProofJob) => (progress: number, status?: string) => {
+    throwIfProofCancelled(job);
+    if (!forwardProofProgress) {
+      return;
+    }
+    fetch("http://localhost:4000/proof-progress", {
+      method: "POST",
+      headers: { "Content-Type": "application/json" },
+      body: JSON.stringify({
+        proofJobId: job.id,
+        proofType,
+        progress,
+        status: status ?? "",
+      }),
+    }).catch(console.error);
+  };
+
 // Setup UTXO Scan Callback
 callbackRouter.post("/setup-utxo-scan-callback", (req, res) => {
   setOnUTXOMerkletreeScanCallback(async (scanData) => {
@@ -29,3 +52,9 @@ callbackRouter.post("/setup-txid-scan-callback", (req, res) => {
   });
   res.sendStatus(204);
 });
+
+// Setup Proof Progress Callback
+callbackRouter.post("/setup-proof-progress-callback", (req, res) => {
+  forwardProofProgress = true;
+  res.sendStatus(204);
+});
@@ -13,6 +13,7 @@ import {
   populateProvedTransfer,
   setLoggers,
 } from "dop-wallet-v3";
+import { CIRCUIT_LIMITS } from "../core/circuits.js";
 import { parseChain } from "../utils/json.js";
 // engine-api.ts
 
@@ -26,6 +27,7 @@ engineRouter.post("/init", async (req, res) => {
     shouldDebug,
     useNativeArtifacts,
     skipMerkletreeScans,
+    poiNodeURLs,
   } = req.body;
 
   try {
@@ -35,6 +37,7 @@ engineRouter.post("/init", async (req, res) => {
       shouldDebug,
       useNativeArtifacts,
       skipMerkletreeScans,
+      poiNodeURLs,
     });
 
     res.send({
@@ -44,6 +47,7 @@ engineRouter.post("/init", async (req, res) => {
       debug: shouldDebug ?? false,
       nativeArtifacts: useNativeArtifacts ?? false,
       skipMerkletreeScans: skipMerkletreeScans ?? false,
+      poiNodeURLs: poiNodeURLs ?? [],
     });
   } catch (err) {
     console.error("Engine init failed:", err);
@@ -60,6 +64,10 @@ engineRouter.get("/engine", (req, res) => {
   }
 });
 
+engineRouter.get("/circuit-limits", (req, res) => {
+  res.json(CIRCUIT_LIMITS);
+});
+
 engineRouter.get("/close", async (req, res) => {
   try {
     await closeEngine();
@@ -0,0 +1,90 @@
+import express from "express";
+import { NetworkName, TXIDVersion } from "dop-sharedmodels-v3";
+import {
+  generateWalletPOIs,
+  getReceivedPOIStatuses,
+  getTxidsPendingPOIs,
+  isPOIRequired,
+  refreshReceivedPOIs,
+} from "../core/poi.js";
+
+export const poiRouter = express.Router();
+
+const poiError = (res: express.Response, error: string, err: unknown) => {
+  console.error(`❌ ${error}:`, err);
+  res.status(500).json({
+    error,
+    details: (err as Error)?.message ?? String(err),
+  });
+};
+
+// POST /poi/received-statuses
+poiRouter.post("/poi/received-statuses", async (req, res) => {
+  const { txidVersion, networkName, dopWalletID } = req.body;
+  try {
+    const statuses = await getReceivedPOIStatuses(
+      txidVersion as TXIDVersion,
+      networkName as NetworkName,
+      dopWalletID
+    );
+    res.json(statuses);
+  } catch (err) {
+    poiError(res, "Failed to get POI statuses", err);
+  }
+});
+
+// POST /poi/pending-transactions
+poiRouter.post("/poi/pending-transactions", async (req, res) => {
+  const { txidVersion, networkName, dopWalletID } = req.body;
+  try {
+    const txids = await getTxidsPendingPOIs(
+      txidVersion as TXIDVersion,
+      networkName as NetworkName,
+      dopWalletID
+    );
+    res.json({ txids });
+  } catch (err) {
+    poiError(res, "Failed to get transactions pending POIs", err);
+  }
+});
+
+// POST /poi/generate
+poiRouter.post("/poi/generate", async (req, res) => {
+  const { networkName, dopWalletID } = req.body;
+  try {
+    await generateWalletPOIs(networkName as NetworkName, dopWalletID);
+    res.sendStatus(204);
+  } catch (err) {
+    poiError(res, "Failed to generate POIs", err);
+  }
+});
+
+// POST /poi/refresh-received
+poiRouter.post("/poi/refresh-received", async (req, res) => {
+  const { txidVersion, networkName, dopWalletID } = req.body;
+  try {
+    await refreshReceivedPOIs(
+      txidVersion as TXIDVersion,
+      networkName as NetworkName,
+      dopWalletID
+    );
+    res.sendStatus(204);
+  } catch (err) {
+    poiError(res, "Failed to refresh received POIs", err);
+  }
+});
+
+// GET /poi/required?networkName=...
+poiRouter.get("/poi/required", async (req, res) => {
+  const { networkName } = req.query;
+  if (!networkName) {
+    res.status(400).send("Missing networkName");
+    return;
+  }
+  try {
+    const required = await isPOIRequired(networkName as NetworkName);
+    res.json({ required });
+  } catch (err) {
+    poiError(res, "Failed to check whether POIs are required", err);
+  }
+});
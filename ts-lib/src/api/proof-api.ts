@@ -0,0 +1,14 @@
+import express from "express";
+import { cancelProofJob } from "../core/proof-jobs.js";
+
+export const proofRouter = express.Router();
+
+// POST /cancel-proof
+proofRouter.post("/cancel-proof", (req, res) => {
+  const { proofJobId } = req.body;
+  if (!proofJobId) {
+    res.status(400).send("Missing proofJobId");
+    return;
+  }
+  res.json({ cancelled: cancelProofJob(proofJobId) });
+});
@@ -11,9 +11,53 @@ import {
   generateDecryptToOriginProof,
   generateDecryptBaseTokenProof,
 } from "dop-wallet-v3";
+import { proofProgressCallback } from "./callback.js";
+import {
+  assertProofUsable,
+  completeProofJob,
+  endProofJob,
+  proofParametersHash,
+  startProofJob,
+} from "../core/proof-jobs.js";
+import { withUtxoSelection } from "../core/utxos.js";
+import { getDecryptToOriginCandidates } from "../core/wallet.js";
 
 export const txDecryptRouter = express.Router();
 
+// Parameters shared by each decrypt proof and its populate call
+const decryptParametersHash = (body: any) =>
+  proofParametersHash("decrypt", [
+    body.txidVersion,
+    body.networkName,
+    body.dopWalletID,
+    body.erc20AmountRecipients,
+    body.nftAmountRecipients,
+    body.broadcasterFeeERC20AmountRecipient,
+    body.sendWithPublicWallet,
+    body.overallBatchMinGasPrice,
+  ]);
+
+const decryptToOriginParametersHash = (body: any) =>
+  proofParametersHash("decrypt-to-origin", [
+    body.txidVersion,
+    body.networkName,
+    body.dopWalletID,
+    body.erc20AmountRecipients,
+    body.nftAmountRecipients,
+  ]);
+
+const decryptBaseTokenParametersHash = (body: any) =>
+  proofParametersHash("decrypt-base-token", [
+    body.txidVersion,
+    body.networkName,
+    body.publicWalletAddress,
+    body.dopWalletID,
+    body.wrappedERC20Amount,
+    body.broadcasterFeeERC20AmountRecipient,
+    body.sendWithPublicWallet,
+    body.overallBatchMinGasPrice,
+  ]);
+
 function safeJsonResponse(res: express.Response, data: any) {
   res.setHeader("Content-Type", "application/json");
   res.send(
@@ -23,33 +67,40 @@ function safeJsonResponse(res: express.Response, data: any) {
 
 // POST /wallet/generate-decrypt-proof
 txDecryptRouter.post("/generate-decrypt-proof", async (req, res) => {
+  const job = startProofJob(req.body.proofJobId);
   try {
-    await generateDecryptProof(
-      req.body.txidVersion,
-      req.body.networkName,
-      req.body.dopWalletID,
-      req.body.encryptionKey,
-      req.body.erc20AmountRecipients,
-      req.body.nftAmountRecipients,
-      req.body.broadcasterFeeERC20AmountRecipient,
-      req.body.sendWithPublicWallet,
-      req.body.overallBatchMinGasPrice
-        ? BigInt(req.body.overallBatchMinGasPrice)
-        : null,
-      () => {}, // Empty callback
-      BigInt(req.body.value)
+    await withUtxoSelection(req.body.dopWalletID, req.body.utxoSelection, () =>
+      generateDecryptProof(
+        req.body.txidVersion,
+        req.body.networkName,
+        req.body.dopWalletID,
+        req.body.encryptionKey,
+        req.body.erc20AmountRecipients,
+        req.body.nftAmountRecipients,
+        req.body.broadcasterFeeERC20AmountRecipient,
+        req.body.sendWithPublicWallet,
+        req.body.overallBatchMinGasPrice
+          ? BigInt(req.body.overallBatchMinGasPrice)
+          : null,
+        proofProgressCallback("decrypt", job),
+        BigInt(req.body.value)
+      )
     );
-    res.json({ success: true });
+    const proved = completeProofJob(job, decryptParametersHash(req.body));
+    res.json({ success: true, provedTransaction: proved });
   } catch (err) {
     res.status(500).json({
       error: "generateDecryptProof failed",
       details: String(err),
     });
+  } finally {
+    endProofJob(job);
   }
 });
 
 // POST /wallet/generate-decrypt-to-origin-proof
 txDecryptRouter.post("/generate-decrypt-to-origin-proof", async (req, res) => {
+  const job = startProofJob(req.body.proofJobId);
   try {
     await generateDecryptToOriginProof(
       req.body.originalEncryptTxid,
@@ -59,47 +110,66 @@ txDecryptRouter.post("/generate-decrypt-to-origin-proof", async (req, res) => {
       req.body.encryptionKey,
       req.body.erc20AmountRecipients,
       req.body.nftAmountRecipients,
-      () => {}, // Empty callback
+      proofProgressCallback("decrypt-to-origin", job),
       BigInt(req.body.value)
     );
-    res.json({ success: true });
+    const proved = completeProofJob(
+      job,
+      decryptToOriginParametersHash(req.body)
+    );
+    res.json({ success: true, provedTransaction: proved });
   } catch (err) {
     res.status(500).json({
       error: "generateDecryptToOriginProof failed",
       details: String(err),
     });
+  } finally {
+    endProofJob(job);
   }
 });
 
 // POST /wallet/generate-decrypt-base-token-proof
 txDecryptRouter.post("/generate-decrypt-base-token-proof", async (req, res) => {
+  const job = startProofJob(req.body.proofJobId);
   try {
-    await generateDecryptBaseTokenProof(
-      req.body.txidVersion,
-      req.body.networkName,
-      req.body.publicWalletAddress,
-      req.body.dopWalletID,
-      req.body.encryptionKey,
-      req.body.wrappedERC20Amount,
-      req.body.broadcasterFeeERC20AmountRecipient,
-      req.body.sendWithPublicWallet,
-      req.body.overallBatchMinGasPrice
-        ? BigInt(req.body.overallBatchMinGasPrice)
-        : null,
-      () => {} // Empty callback
+    await withUtxoSelection(req.body.dopWalletID, req.body.utxoSelection, () =>
+      generateDecryptBaseTokenProof(
+        req.body.txidVersion,
+        req.body.networkName,
+        req.body.publicWalletAddress,
+        req.body.dopWalletID,
+        req.body.encryptionKey,
+        req.body.wrappedERC20Amount,
+        req.body.broadcasterFeeERC20AmountRecipient,
+        req.body.sendWithPublicWallet,
+        req.body.overallBatchMinGasPrice
+          ? BigInt(req.body.overallBatchMinGasPrice)
+          : null,
+        proofProgressCallback("decrypt-base-token", job)
+      )
+    );
+    const proved = completeProofJob(
+      job,
+      decryptBaseTokenParametersHash(req.body)
     );
-    res.json({ success: true });
+    res.json({ success: true, provedTransaction: proved });
   } catch (err) {
     res.status(500).json({
       error: "generateDecryptBaseTokenProof failed",
       details: String(err),
     });
+  } finally {
+    endProofJob(job);
   }
 });
 
 // POST /wallet/populate-proved-decrypt
 txDecryptRouter.post("/populate-proved-decrypt", async (req, res) => {
   try {
+    assertProofUsable(
+      req.body.provedTransaction,
+      decryptParametersHash(req.body)
+    );
     const tx = await populateProvedDecrypt(
       req.body.txidVersion,
       req.body.networkName,
@@ -126,6 +196,10 @@ txDecryptRouter.post(
   "/populate-proved-decrypt-base-token",
   async (req, res) => {
     try {
+      assertProofUsable(
+        req.body.provedTransaction,
+        decryptBaseTokenParametersHash(req.body)
+      );
       const tx = await populateProvedDecryptBaseToken(
         req.body.txidVersion,
         req.body.networkName,
@@ -152,17 +226,19 @@ txDecryptRouter.post(
 // POST /wallet/gas-estimate-for-unproven-decrypt
 txDecryptRouter.post("/gas-estimate-for-unproven-decrypt", async (req, res) => {
   try {
-    const estimate = await gasEstimateForUnprovenDecrypt(
-      req.body.txidVersion,
-      req.body.networkName,
-      req.body.dopWalletID,
-      req.body.encryptionKey,
-      req.body.erc20AmountRecipients,
-      req.body.nftAmountRecipients,
-      req.body.originalGasDetails,
-      req.body.feeTokenDetails,
-      req.body.sendWithPublicWallet,
-      BigInt(req.body.value)
+    const estimate = await withUtxoSelection(req.body.dopWalletID, req.body.utxoSelection, () =>
+      gasEstimateForUnprovenDecrypt(
+        req.body.txidVersion,
+        req.body.networkName,
+        req.body.dopWalletID,
+        req.body.encryptionKey,
+        req.body.erc20AmountRecipients,
+        req.body.nftAmountRecipients,
+        req.body.originalGasDetails,
+        req.body.feeTokenDetails,
+        req.body.sendWithPublicWallet,
+        BigInt(req.body.value)
+      )
     );
     safeJsonResponse(res, estimate);
   } catch (err) {
@@ -178,16 +254,18 @@ txDecryptRouter.post(
   "/gas-estimate-for-unproven-decrypt-base-token",
   async (req, res) => {
     try {
-      const estimate = await gasEstimateForUnprovenDecryptBaseToken(
-        req.body.txidVersion,
-        req.body.networkName,
-        req.body.publicWalletAddress,
-        req.body.dopWalletID,
-        req.body.encryptionKey,
-        req.body.wrappedERC20Amount,
-        req.body.originalGasDetails,
-        req.body.feeTokenDetails,
-        req.body.sendWithPublicWallet
+      const estimate = await withUtxoSelection(req.body.dopWalletID, req.body.utxoSelection, () =>
+        gasEstimateForUnprovenDecryptBaseToken(
+          req.body.txidVersion,
+          req.body.networkName,
+          req.body.publicWalletAddress,
+          req.body.dopWalletID,
+          req.body.encryptionKey,
+          req.body.wrappedERC20Amount,
+          req.body.originalGasDetails,
+          req.body.feeTokenDetails,
+          req.body.sendWithPublicWallet
+        )
       );
       safeJsonResponse(res, estimate);
     } catch (err) {
@@ -220,9 +298,30 @@ txDecryptRouter.post(
   }
 );
 
+// POST /wallet/decrypt-to-origin-candidates
+txDecryptRouter.post("/decrypt-to-origin-candidates", async (req, res) => {
+  try {
+    const candidates = await getDecryptToOriginCandidates(
+      req.body.txidVersion,
+      req.body.networkName,
+      req.body.dopWalletID
+    );
+    safeJsonResponse(res, candidates);
+  } catch (err) {
+    res.status(500).json({
+      error: "getDecryptToOriginCandidates failed",
+      details: String(err),
+    });
+  }
+});
+
 // POST /wallet/populate-proved-decrypt-to-origin
 txDecryptRouter.post("/populate-proved-decrypt-to-origin", async (req, res) => {
   try {
+    assertProofUsable(
+      req.body.provedTransaction,
+      decryptToOriginParametersHash(req.body)
+    );
     const tx = await populateProvedDecryptToOrigin(
       req.body.txidVersion,
       req.body.networkName,
@@ -4,9 +4,34 @@ import {
   gasEstimateForUnprovenTransfer,
   generateTransferProof,
 } from "dop-wallet-v3";
+import { proofProgressCallback } from "./callback.js";
+import {
+  assertProofUsable,
+  completeProofJob,
+  endProofJob,
+  proofParametersHash,
+  startProofJob,
+} from "../core/proof-jobs.js";
+import { assertValidMemoText } from "../core/memo.js";
+import { withUtxoSelection } from "../core/utxos.js";
 
 export const txTransferRouter = express.Router();
 
+// Parameters shared by generate-transfer-proof and populate-proved-transfer
+const transferParametersHash = (body: any) =>
+  proofParametersHash("transfer", [
+    body.txidVersion,
+    body.networkName,
+    body.dopWalletID,
+    body.showSenderAddressToRecipient ?? false,
+    body.memoText,
+    body.erc20AmountRecipients ?? [],
+    body.nftAmountRecipients ?? [],
+    body.broadcasterFeeERC20AmountRecipient,
+    body.sendWithPublicWallet ?? false,
+    body.overallBatchMinGasPrice,
+  ]);
+
 function safeJsonResponse(res: express.Response, data: any) {
   res.setHeader("Content-Type", "application/json");
   res.send(
@@ -31,6 +56,7 @@ txTransferRouter.post("/populate-proved-transfer", async (req, res) => {
       sendWithPublicWallet,
       overallBatchMinGasPrice,
       gasDetails,
+      provedTransaction,
     } = req.body;
 
     if (!txidVersion || !networkName || !dopWalletID || !gasDetails) {
@@ -38,6 +64,8 @@ txTransferRouter.post("/populate-proved-transfer", async (req, res) => {
       return;
     }
 
+    assertValidMemoText(memoText);
+    assertProofUsable(provedTransaction, transferParametersHash(req.body));
     const tx = await populateProvedTransfer(
       txidVersion,
       networkName,
@@ -77,6 +105,7 @@ txTransferRouter.post(
         originalGasDetails,
         feeTokenDetails,
         sendWithPublicWallet,
+        utxoSelection,
       } = req.body;
 
       if (
@@ -90,17 +119,21 @@ txTransferRouter.post(
         return;
       }
 
-      const estimate = await gasEstimateForUnprovenTransfer(
-        txidVersion,
-        networkName,
-        dopWalletID,
-        encryptionKey,
-        memoText,
-        erc20AmountRecipients ?? [],
-        nftAmountRecipients ?? [],
-        originalGasDetails,
-        feeTokenDetails,
-        sendWithPublicWallet ?? false
+      assertValidMemoText(memoText);
+
+      const estimate = await withUtxoSelection(dopWalletID, utxoSelection, () =>
+        gasEstimateForUnprovenTransfer(
+          txidVersion,
+          networkName,
+          dopWalletID,
+          encryptionKey,
+          memoText,
+          erc20AmountRecipients ?? [],
+          nftAmountRecipients ?? [],
+          originalGasDetails,
+          feeTokenDetails,
+          sendWithPublicWallet ?? false
+        )
       );
 
       safeJsonResponse(res, estimate);
@@ -114,6 +147,7 @@ txTransferRouter.post(
 );
 
 txTransferRouter.post("/generate-transfer-proof", async (req, res) => {
+  const job = startProofJob(req.body.proofJobId);
   try {
     const {
       txidVersion,
@@ -127,6 +161,7 @@ txTransferRouter.post("/generate-transfer-proof", async (req, res) => {
       broadcasterFeeERC20AmountRecipient,
       sendWithPublicWallet,
       overallBatchMinGasPrice,
+      utxoSelection,
     } = req.body;
 
     if (!txidVersion || !networkName || !dopWalletID || !encryptionKey) {
@@ -134,28 +169,36 @@ txTransferRouter.post("/generate-transfer-proof", async (req, res) => {
       return;
     }
 
-    await generateTransferProof(
-      txidVersion,
-      networkName,
-      dopWalletID,
-      encryptionKey,
-      showSenderAddressToRecipient ?? false,
-      memoText,
-      erc20AmountRecipients ?? [],
-      nftAmountRecipients ?? [],
-      broadcasterFeeERC20AmountRecipient,
-      sendWithPublicWallet ?? false,
-      overallBatchMinGasPrice,
-      (_progress) => {
-        console.log("🔄 Generating transfer proof...");
-      }
+    assertValidMemoText(memoText);
+
+    await withUtxoSelection(dopWalletID, utxoSelection, () =>
+      generateTransferProof(
+        txidVersion,
+        networkName,
+        dopWalletID,
+        encryptionKey,
+        showSenderAddressToRecipient ?? false,
+        memoText,
+        erc20AmountRecipients ?? [],
+        nftAmountRecipients ?? [],
+        broadcasterFeeERC20AmountRecipient,
+        sendWithPublicWallet ?? false,
+        overallBatchMinGasPrice,
+        proofProgressCallback("transfer", job)
+      )
     );
+    const proved = completeProofJob(job, transferParametersHash(req.body));
 
-    res.status(200).json({ message: "Transfer proof generation complete" });
+    res.status(200).json({
+      message: "Transfer proof generation complete",
+      provedTransaction: proved,
+    });
   } catch (err) {
     res.status(500).json({
       error: "Failed to generate transfer proof",
       details: (err as Error)?.message ?? String(err),
     });
+  } finally {
+    endProofJob(job);
   }
 });
@@ -3,9 +3,17 @@ import {
   createViewOnlyWallet,
   createWallet,
   generateMnemonic,
+  getReceivedMemos,
+  getStoredWalletIds,
+  getTransactionHistory,
+  getWalletBackupInfo,
   getWalletById,
   getWalletShareableViewingKeyById,
+  rotateWalletEncryptionKey,
+  signWithWalletSpendingKey,
+  verifyWalletSpendingSignature,
 } from "../core/wallet.js";
+import { listUtxos } from "../core/utxos.js";
 import { extractWalletInfo, parseChain } from "../utils/json.js";
 import {
   assertValidDopAddress,
@@ -18,7 +26,6 @@ import {
   getDopWalletPrivateViewingKey,
   getWalletMnemonic,
   loadWalletByID,
-  signWithWalletViewingKey,
   unloadWalletByID,
   validateDopAddress,
   validateEthAddress,
@@ -36,12 +43,14 @@ walletRouter.get("/mnemonic", (req, res) => {
 });
 
 walletRouter.post("/wallet", async (req, res) => {
-  const { mnemonic, encryptionKey, creationBlockNumbers } = req.body;
+  const { mnemonic, encryptionKey, creationBlockNumbers, derivationIndex } =
+    req.body;
   try {
     const walletInfo = await createWallet(
       mnemonic,
       encryptionKey,
-      creationBlockNumbers
+      creationBlockNumbers,
+      derivationIndex
     );
     res.json(walletInfo);
   } catch (err) {
@@ -76,23 +85,97 @@ walletRouter.get("/wallet/:id/mnemonic", async (req, res) => {
   }
 });
 
+walletRouter.get("/wallet/:id/backup-info", async (req, res) => {
+  const { id } = req.params;
+  const { encryptionKey } = req.query;
+  try {
+    const info = await getWalletBackupInfo(id, encryptionKey as string);
+    res.json(info);
+  } catch (err) {
+    res.status(500).send("Failed to retrieve wallet backup info");
+  }
+});
+
+walletRouter.get("/wallets/stored", async (req, res) => {
+  try {
+    const walletIds = await getStoredWalletIds();
+    res.json({ walletIds });
+  } catch (err) {
+    res.status(500).send("Failed to list stored wallets");
+  }
+});
+
+walletRouter.post("/wallet/rotate-encryption-key", async (req, res) => {
+  const { dopWalletID, oldEncryptionKey, newEncryptionKey, dryRun } = req.body;
+  if (!dopWalletID || !oldEncryptionKey || !newEncryptionKey) {
+    res.status(400).send("Missing required fields");
+    return;
+  }
+  try {
+    const result = await rotateWalletEncryptionKey(
+      dopWalletID,
+      oldEncryptionKey,
+      newEncryptionKey,
+      dryRun ?? false
+    );
+    res.json(result);
+  } catch (err) {
+    res.status(500).send("Failed to rotate encryption key");
+  }
+});
+
 walletRouter.post("/wallet/sign-message", async (req, res) => {
-  const { walletId, message } = req.body;
+  const { walletId, encryptionKey, message, messageHex } = req.body;
 
   try {
-    if (typeof message !== "string" || !message.length) {
+    if (typeof encryptionKey !== "string") {
+      res.status(400).send("Missing encryption key");
+      return;
+    }
+
+    let hexMessage: string;
+    if (typeof messageHex === "string" && messageHex.length) {
+      hexMessage = messageHex.startsWith("0x") ? messageHex : "0x" + messageHex;
+    } else if (typeof message === "string" && message.length) {
+      hexMessage = "0x" + Buffer.from(toUtf8Bytes(message)).toString("hex");
+    } else {
       res.status(400).send("Message must be a non-empty string");
       return;
     }
 
-    const hexMessage = "0x" + Buffer.from(toUtf8Bytes(message)).toString("hex");
-    const signature = await signWithWalletViewingKey(walletId, hexMessage);
+    const signature = await signWithWalletSpendingKey(
+      walletId,
+      encryptionKey,
+      hexMessage
+    );
     res.json({ signature });
   } catch (err) {
     res.status(500).send("Failed to sign message");
   }
 });
 
+// Signatures are made with the wallet's spending key and checked against the
+// master public key encoded in the DOP address.
+walletRouter.post("/wallet/verify-signature", async (req, res) => {
+  const { dopAddress, messageHex, signature } = req.body;
+
+  try {
+    if (typeof dopAddress !== "string" || typeof signature !== "string") {
+      res.status(400).send("Missing required fields");
+      return;
+    }
+
+    const valid = await verifyWalletSpendingSignature(
+      dopAddress,
+      messageHex ?? "",
+      signature
+    );
+    res.json({ valid });
+  } catch (err) {
+    res.status(400).send("Failed to verify signature");
+  }
+});
+
 walletRouter.post("/wallet/load", async (req, res) => {
   const { encryptionKey, dopWalletID, isViewOnlyWallet } = req.body;
   try {
@@ -132,6 +215,57 @@ walletRouter.get("/wallet/:id/scan-multiple", async (req, res) => {
   }
 });
 
+walletRouter.get("/wallet/:id/received-memos", async (req, res) => {
+  const { id } = req.params;
+  try {
+    const chain = parseChain(req.query.chain);
+    const startingBlock = req.query.startingBlock
+      ? parseInt(req.query.startingBlock as string)
+      : undefined;
+    const memos = await getReceivedMemos(id, chain, startingBlock);
+    res.json(memos);
+  } catch (err) {
+    res.status(500).send("Failed to retrieve received memos");
+  }
+});
+
+walletRouter.get("/wallet/:id/transaction-history", async (req, res) => {
+  const { id } = req.params;
+  try {
+    const chain = parseChain(req.query.chain);
+    const { startingBlock, categories, tokenAddress, offset, limit } =
+      req.query as Record<string, string | undefined>;
+    if (tokenAddress) {
+      assertValidEthAddress(tokenAddress);
+    }
+    const history = await getTransactionHistory(id, chain, {
+      startingBlock: startingBlock ? parseInt(startingBlock) : undefined,
+      categories: categories ? categories.split(",") : undefined,
+      tokenAddress,
+      offset: offset ? parseInt(offset) : 0,
+      limit: limit ? parseInt(limit) : undefined,
+    });
+    res.json(history);
+  } catch (err) {
+    res.status(500).send("Failed to retrieve transaction history");
+  }
+});
+
+walletRouter.get("/wallet/:id/utxos", async (req, res) => {
+  const { id } = req.params;
+  try {
+    const chain = parseChain(req.query.chain);
+    const tokenAddress = req.query.tokenAddress as string | undefined;
+    if (tokenAddress) {
+      assertValidEthAddress(tokenAddress);
+    }
+    const utxos = await listUtxos(id, chain, tokenAddress);
+    res.json(utxos);
+  } catch (err) {
+    res.status(500).send("Failed to list UTXOs");
+  }
+});
+
 // Unload wallet
 walletRouter.get("/wallet/:id/unload", (req, res) => {
   const { id } = req.params;
@@ -0,0 +1,8 @@
+// Output limits of the engine's transaction circuits. Every transaction is
+// proved with one circuit, and the largest take five commitments, change
+// included. The engine adds a single decrypt note per token to each
+// transaction, so a decrypt can only pay one recipient per token at a time.
+export const CIRCUIT_LIMITS = {
+  maxOutputs: 5,
+  maxDecryptOutputsPerToken: 1,
+};
@@ -33,12 +33,14 @@ export const initEngine = async ({
   shouldDebug = false,
   useNativeArtifacts = false,
   skipMerkletreeScans = false,
+  poiNodeURLs,
 }: {
   engineName?: string;
   dbPath?: string;
   shouldDebug?: boolean;
   useNativeArtifacts?: boolean;
   skipMerkletreeScans?: boolean;
+  poiNodeURLs?: string[];
 }) => {
   const db = new LevelDOWN(dbPath);
 
@@ -48,7 +50,8 @@ export const initEngine = async ({
     shouldDebug,
     artifactStore,
     useNativeArtifacts,
-    skipMerkletreeScans
+    skipMerkletreeScans,
+    poiNodeURLs
   );
 };
 
@@ -0,0 +1,74 @@
+// core/poi.ts
+
+import { NetworkName, TXIDVersion } from "dop-sharedmodels-v3";
+import {
+  generatePOIsForWallet,
+  getChainTxidsStillPendingSpentPOIs,
+  getPOIRequiredForNetwork,
+  getTXOsReceivedPOIStatusInfoForWallet,
+  refreshReceivePOIsForWallet,
+} from "dop-wallet-v3";
+
+// Statuses per list come either as an object or as its JSON string, and
+// are missing until the engine has asked a POI node.
+export const parsePOIsPerList = (
+  poisPerList: unknown
+): Record<string, string> | null => {
+  if (typeof poisPerList === "string") {
+    return poisPerList.startsWith("{") ? JSON.parse(poisPerList) : null;
+  }
+  return (poisPerList as Record<string, string> | undefined) ?? null;
+};
+
+// One entry per note the wallet has received.
+export const getReceivedPOIStatuses = async (
+  txidVersion: TXIDVersion,
+  networkName: NetworkName,
+  id: string
+) => {
+  const infos = await getTXOsReceivedPOIStatusInfoForWallet(
+    txidVersion,
+    networkName,
+    id
+  );
+  return infos.map(({ strings }) => ({
+    tree: Number(strings.tree),
+    position: Number(strings.position),
+    txid: strings.txid,
+    commitment: strings.commitment,
+    blindedCommitment: strings.blindedCommitment,
+    poisPerList: parsePOIsPerList(strings.poisPerList),
+  }));
+};
+
+// Transactions sent from the wallet whose spent POIs are not yet valid.
+export const getTxidsPendingPOIs = async (
+  txidVersion: TXIDVersion,
+  networkName: NetworkName,
+  id: string
+) => {
+  return await getChainTxidsStillPendingSpentPOIs(
+    txidVersion,
+    networkName,
+    id
+  );
+};
+
+export const generateWalletPOIs = async (
+  networkName: NetworkName,
+  id: string
+) => {
+  await generatePOIsForWallet(networkName, id);
+};
+
+export const refreshReceivedPOIs = async (
+  txidVersion: TXIDVersion,
+  networkName: NetworkName,
+  id: string
+) => {
+  await refreshReceivePOIsForWallet(txidVersion, networkName, id);
+};
+
+export const isPOIRequired = async (networkName: NetworkName) => {
+  return await getPOIRequiredForNetwork(networkName);
+};
@@ -1,7 +1,17 @@
 // core/provider.ts
 
-import { FallbackProviderJsonConfig, NetworkName } from "dop-sharedmodels-v3";
-import { loadProvider } from "dop-wallet-v3";
+import {
+  FallbackProviderJsonConfig,
+  getEVMGasTypeForTransaction,
+  NetworkName,
+} from "dop-sharedmodels-v3";
+import { getFallbackProviderForNetwork, loadProvider } from "dop-wallet-v3";
+import {
+  FallbackProvider,
+  JsonRpcProvider,
+  toQuantity,
+  TransactionReceipt,
+} from "ethers";
 
 export const loadNetworkProvider = async (
   config: FallbackProviderJsonConfig,
@@ -10,3 +20,105 @@ export const loadNetworkProvider = async (
 ) => {
   return await loadProvider(config, network, pollingInterval);
 };
+
+const formatReceipt = (receipt: TransactionReceipt, confirmations: number) => ({
+  transactionHash: receipt.hash,
+  blockNumber: receipt.blockNumber,
+  blockHash: receipt.blockHash,
+  from: receipt.from,
+  to: receipt.to,
+  contractAddress: receipt.contractAddress,
+  status: receipt.status,
+  gasUsed: receipt.gasUsed.toString(),
+  effectiveGasPrice: receipt.gasPrice.toString(),
+  confirmations,
+});
+
+export const sendRawTransaction = async (
+  network: NetworkName,
+  signedTransaction: string
+) => {
+  const provider = getFallbackProviderForNetwork(network);
+  const response = await provider.broadcastTransaction(signedTransaction);
+  return response.hash;
+};
+
+export const getTransactionCount = async (
+  network: NetworkName,
+  address: string
+) => {
+  const provider = getFallbackProviderForNetwork(network);
+  return await provider.getTransactionCount(address, "pending");
+};
+
+// Resolves once the transaction has `confirmations` confirmations. Throws
+// an ethers TIMEOUT error if that takes longer than `timeoutMs`.
+export const waitForConfirmation = async (
+  network: NetworkName,
+  txHash: string,
+  confirmations: number,
+  timeoutMs: number
+) => {
+  const provider = getFallbackProviderForNetwork(network);
+  const receipt = await provider.waitForTransaction(
+    txHash,
+    confirmations,
+    timeoutMs
+  );
+  if (!receipt) {
+    return null;
+  }
+  return formatReceipt(receipt, await receipt.confirmations());
+};
+
+
+const FEE_HISTORY_BLOCKS = 20;
+const FEE_HISTORY_PERCENTILES = [25, 50, 75];
+
+const median = (values: bigint[]) => {
+  const sorted = [...values].sort((a, b) => (a < b ? -1 : a > b ? 1 : 0));
+  return sorted[Math.floor(sorted.length / 2)];
+};
+
+// Median priority fee paid at each of FEE_HISTORY_PERCENTILES over recent
+// blocks. FallbackProvider has no raw RPC access, so this asks the first
+// JSON-RPC backend that answers; null if none does.
+const getPriorityFeePercentiles = async (provider: FallbackProvider) => {
+  for (const { provider: backend } of provider.providerConfigs) {
+    if (!(backend instanceof JsonRpcProvider)) continue;
+    try {
+      const history = await backend.send("eth_feeHistory", [
+        toQuantity(FEE_HISTORY_BLOCKS),
+        "latest",
+        FEE_HISTORY_PERCENTILES,
+      ]);
+      const rewards: string[][] = history.reward ?? [];
+      if (!rewards.length) continue;
+      return FEE_HISTORY_PERCENTILES.map((_, i) =>
+        median(rewards.map((block) => BigInt(block[i]))).toString()
+      );
+    } catch {
+      continue;
+    }
+  }
+  return null;
+};
+
+export const getGasFeeData = async (
+  network: NetworkName,
+  sendWithPublicWallet: boolean
+) => {
+  const provider = getFallbackProviderForNetwork(network);
+  const [block, feeData, priorityFeePercentiles] = await Promise.all([
+    provider.getBlock("latest"),
+    provider.getFeeData(),
+    getPriorityFeePercentiles(provider),
+  ]);
+  return {
+    evmGasType: getEVMGasTypeForTransaction(network, sendWithPublicWallet),
+    gasPrice: feeData.gasPrice?.toString() ?? null,
+    baseFeePerGas: block?.baseFeePerGas?.toString() ?? null,
+    maxPriorityFeePerGas: feeData.maxPriorityFeePerGas?.toString() ?? null,
+    priorityFeePercentiles,
+  };
+};
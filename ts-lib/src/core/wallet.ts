@@ -1,17 +1,21 @@
 
-import { DopWalletInfo } from 'dop-sharedmodels-v3';
-import { createDopWallet, createViewOnlyDopWallet, fullWalletForID, getWalletShareableViewingKey } from 'dop-wallet-v3';
-import { Mnemonic, randomBytes } from 'ethers';
+import { Chain, DopWalletInfo, NETWORK_CONFIG, NetworkName, POIStatus, TransactionHistoryItemCategory, TXIDVersion } from 'dop-sharedmodels-v3';
+import { DopWallet, signEDDSA, verifyEDDSA, ViewOnlyWallet, WalletData, WalletNode } from 'dop-engine-v3';
+import { createDopWallet, createViewOnlyDopWallet, fullWalletForID, getDopWalletAddressData, getEngine, getERC20AndNFTAmountRecipientsForDecryptToOrigin, getWalletShareableViewingKey, getWalletTransactionHistory, walletForID } from 'dop-wallet-v3';
+import { keccak256, Mnemonic, randomBytes } from 'ethers';
+import { getReceivedPOIStatuses } from './poi.js';
 
 export const createWallet = async (
     mnemonic: string,
     encryptionKey: string,
-    creationBlockNumbers?: Record<string, number>
+    creationBlockNumbers?: Record<string, number>,
+    derivationIndex?: number
   ): Promise<DopWalletInfo> => {
     const walletInfo = await createDopWallet(
       encryptionKey,
       mnemonic,
-      creationBlockNumbers // <-- pass as-is
+      creationBlockNumbers, // <-- pass as-is
+      derivationIndex
     );
   
     if (!walletInfo) {
@@ -47,3 +51,315 @@ export const createViewOnlyWallet = (
     creationBlockNumbers
   );
 };
+
+// The engine stores creation blocks as [chainType][chainID]; wallet creation
+// takes them keyed by network name, so map them back for backups.
+const creationBlockNumbersByNetwork = (
+  blockNumbers?: number[][]
+): Record<string, number> | null => {
+  if (!blockNumbers) return null;
+  const result: Record<string, number> = {};
+  for (const [networkName, { chain }] of Object.entries(NETWORK_CONFIG)) {
+    const block = blockNumbers[chain.type]?.[chain.id];
+    if (block != null) result[networkName] = block;
+  }
+  return result;
+};
+
+export const getWalletBackupInfo = async (id: string, encryptionKey: string) => {
+  const wallet = walletForID(id);
+  const shareableViewingKey = await getWalletShareableViewingKey(id);
+
+  if (wallet instanceof ViewOnlyWallet) {
+    return {
+      isViewOnlyWallet: true,
+      mnemonic: null,
+      shareableViewingKey,
+      derivationIndex: null,
+      creationBlockNumbers: creationBlockNumbersByNetwork(wallet.creationBlockNumbers),
+    };
+  }
+
+  const { mnemonic, index, creationBlockNumbers } =
+    (await DopWallet.getEncryptedData(getEngine().db, encryptionKey, id)) as WalletData;
+
+  return {
+    isViewOnlyWallet: false,
+    mnemonic,
+    shareableViewingKey,
+    derivationIndex: index,
+    creationBlockNumbers: creationBlockNumbersByNetwork(creationBlockNumbers),
+  };
+};
+
+// IDs of every wallet record in the engine database, loaded or not. Records
+// live under DopWallet.dbPath(id), so the ID is the second key segment.
+export const getStoredWalletIds = async (): Promise<string[]> => {
+  const { db } = getEngine();
+  const [walletPrefix] = DopWallet.dbPath('');
+  const keys: string[] = await db.getNamespace([walletPrefix]);
+  return [...new Set(keys.map((key) => key.split(':')[1]).filter(Boolean))];
+};
+
+// Re-encrypts the stored wallet record under a new key. The record is read
+// and verified with the old key first, then replaced in a single put.
+export const rotateWalletEncryptionKey = async (
+  id: string,
+  oldEncryptionKey: string,
+  newEncryptionKey: string,
+  dryRun: boolean = false
+) => {
+  const { db } = getEngine();
+  const path = DopWallet.dbPath(id);
+  const data = await db.getEncrypted(path, oldEncryptionKey);
+
+  if (dryRun) {
+    return { rotated: false };
+  }
+
+  await db.putEncrypted(path, newEncryptionKey, data);
+  try {
+    await db.getEncrypted(path, newEncryptionKey);
+  } catch (err) {
+    await db.putEncrypted(path, oldEncryptionKey, data);
+    throw err;
+  }
+  return { rotated: true };
+};
+
+// EdDSA signs a single field element, so messages are hashed with keccak256
+// and reduced into the SNARK scalar field first.
+const SNARK_SCALAR_FIELD = BigInt(
+  '21888242871839275222246405745257275088548364400416034343698204186575808495617'
+);
+const SIGNATURE_WORDS = 6;
+
+const messageToField = (messageHex: string): bigint =>
+  BigInt(keccak256('0x' + messageHex.replace(/^0x/, ''))) % SNARK_SCALAR_FIELD;
+
+// Signatures are six 32-byte big-endian words: R8.x, R8.y, S, the spending
+// public key (x, y) and the nullifying key. The last three hash to the
+// master public key in the DOP address, which binds the key to the address.
+export const signWithWalletSpendingKey = async (
+  id: string,
+  encryptionKey: string,
+  messageHex: string
+): Promise<string> => {
+  const wallet = fullWalletForID(id);
+  const { privateKey, pubkey } = await wallet.getSpendingKeyPair(encryptionKey);
+  const { R8, S } = await signEDDSA(privateKey, messageToField(messageHex));
+  return [...R8, S, ...pubkey, wallet.nullifyingKey]
+    .map((word) => word.toString(16).padStart(64, '0'))
+    .join('');
+};
+
+export const verifyWalletSpendingSignature = async (
+  dopAddress: string,
+  messageHex: string,
+  signatureHex: string
+): Promise<boolean> => {
+  const hex = signatureHex.replace(/^0x/, '');
+  if (!/^[0-9a-fA-F]*$/.test(hex) || hex.length !== SIGNATURE_WORDS * 64) {
+    return false;
+  }
+  const [r8x, r8y, s, pubX, pubY, nullifyingKey] = Array.from(
+    { length: SIGNATURE_WORDS },
+    (_, i) => BigInt('0x' + hex.slice(i * 64, (i + 1) * 64))
+  );
+
+  const { masterPublicKey } = getDopWalletAddressData(dopAddress);
+  if (WalletNode.getMasterPublicKey([pubX, pubY], nullifyingKey) !== masterPublicKey) {
+    return false;
+  }
+  return verifyEDDSA(messageToField(messageHex), { R8: [r8x, r8y], S: s }, [pubX, pubY]);
+};
+
+// Incoming ERC20 notes with their decrypted memo. The sender address
+// is only known when the sender chose to reveal it.
+export const getReceivedMemos = async (
+  id: string,
+  chain: Chain,
+  startingBlock?: number
+) => {
+  const history = await getWalletTransactionHistory(chain, id, startingBlock);
+  return history.flatMap((item) =>
+    item.receiveERC20Amounts.map((received) => ({
+      txid: item.txid,
+      blockNumber: item.blockNumber ?? null,
+      timestamp: item.timestamp ?? null,
+      tokenAddress: received.tokenAddress,
+      amount: String(received.amount),
+      memoText: received.memoText ?? null,
+      senderAddress: received.senderAddress ?? null,
+    }))
+  );
+};
+
+export type HistoryFilter = {
+  startingBlock?: number;
+  categories?: string[];
+  tokenAddress?: string;
+  offset: number;
+  limit?: number;
+};
+
+const historyCategory = (category: TransactionHistoryItemCategory) => {
+  switch (category) {
+    case TransactionHistoryItemCategory.EncryptERC20s:
+      return 'encrypt';
+    case TransactionHistoryItemCategory.TransferReceiveERC20s:
+      return 'transfer-in';
+    case TransactionHistoryItemCategory.TransferSendERC20s:
+      return 'transfer-out';
+    case TransactionHistoryItemCategory.DecryptERC20s:
+      return 'decrypt';
+    default:
+      return 'unknown';
+  }
+};
+
+const historyAmount = (
+  amount: { tokenAddress: string; amount: bigint; memoText?: string },
+  counterparty?: string
+) => ({
+  tokenAddress: amount.tokenAddress,
+  amount: String(amount.amount),
+  counterparty: counterparty ?? null,
+  memoText: amount.memoText ?? null,
+});
+
+// Newest first. Change notes are left out; the broadcaster fee is reported
+// separately from the amounts sent.
+export const getTransactionHistory = async (
+  id: string,
+  chain: Chain,
+  filter: HistoryFilter
+) => {
+  const history = await getWalletTransactionHistory(
+    chain,
+    id,
+    filter.startingBlock
+  );
+  const tokenAddress = filter.tokenAddress?.toLowerCase();
+  const entries = history
+    .map((item) => ({
+      txid: item.txid,
+      blockNumber: item.blockNumber ?? null,
+      timestamp: item.timestamp ?? null,
+      category: historyCategory(item.category),
+      tokenAmounts: [
+        ...item.transferERC20Amounts.map((a) =>
+          historyAmount(a, a.recipientAddress)
+        ),
+        ...item.receiveERC20Amounts.map((a) =>
+          historyAmount(a, a.senderAddress)
+        ),
+        ...item.decryptERC20Amounts.map((a) =>
+          historyAmount(a, a.recipientAddress)
+        ),
+      ],
+      broadcasterFee: item.broadcasterFeeERC20Amount
+        ? historyAmount(item.broadcasterFeeERC20Amount)
+        : null,
+    }))
+    .filter(
+      (entry) =>
+        !filter.categories?.length ||
+        filter.categories.includes(entry.category)
+    )
+    .filter(
+      (entry) =>
+        !tokenAddress ||
+        entry.tokenAmounts.some(
+          (a) => a.tokenAddress.toLowerCase() === tokenAddress
+        )
+    )
+    .sort((a, b) => (b.timestamp ?? 0) - (a.timestamp ?? 0));
+
+  const end =
+    filter.limit === undefined ? undefined : filter.offset + filter.limit;
+  return {
+    total: entries.length,
+    items: entries.slice(filter.offset, end),
+  };
+};
+
+const normalizeTxid = (txid: string) => txid.toLowerCase().replace(/^0x/, '');
+
+// Worst POI status over all notes received in each txid. Without POI
+// information (e.g. no POI node configured) every status is unknown.
+const encryptPOIStatuses = async (
+  txidVersion: TXIDVersion,
+  networkName: NetworkName,
+  id: string
+) => {
+  const statuses = new Map<string, string>();
+  let infos;
+  try {
+    infos = await getReceivedPOIStatuses(txidVersion, networkName, id);
+  } catch {
+    return statuses;
+  }
+  for (const info of infos) {
+    const txid = normalizeTxid(info.txid);
+    const perList = Object.values(info.poisPerList ?? {});
+    let status = 'pending';
+    if (perList.includes(POIStatus.ShieldBlocked)) {
+      status = 'blocked';
+    } else if (
+      perList.length &&
+      perList.every((s) => s === POIStatus.Valid)
+    ) {
+      status = 'valid';
+    }
+    const previous = statuses.get(txid);
+    if (previous === 'blocked' || (previous === 'pending' && status === 'valid')) {
+      continue;
+    }
+    statuses.set(txid, status);
+  }
+  return statuses;
+};
+
+// Encrypts with notes that are still unspent, and so can be sent back to
+// their origin address, newest first.
+export const getDecryptToOriginCandidates = async (
+  txidVersion: TXIDVersion,
+  networkName: NetworkName,
+  id: string
+) => {
+  const { chain } = NETWORK_CONFIG[networkName];
+  const history = await getWalletTransactionHistory(chain, id, undefined);
+  const statuses = await encryptPOIStatuses(txidVersion, networkName, id);
+
+  const candidates = [];
+  for (const item of history) {
+    if (item.category !== TransactionHistoryItemCategory.EncryptERC20s) {
+      continue;
+    }
+    let recipients;
+    try {
+      recipients = await getERC20AndNFTAmountRecipientsForDecryptToOrigin(
+        txidVersion,
+        networkName,
+        id,
+        item.txid
+      );
+    } catch {
+      continue;
+    }
+    const { erc20AmountRecipients, nftAmountRecipients } = recipients;
+    if (!erc20AmountRecipients.length && !nftAmountRecipients.length) {
+      continue;
+    }
+    candidates.push({
+      txid: item.txid,
+      blockNumber: item.blockNumber ?? null,
+      timestamp: item.timestamp ?? null,
+      status: statuses.get(normalizeTxid(item.txid)) ?? 'unknown',
+      erc20AmountRecipients,
+      nftAmountRecipients,
+    });
+  }
+  return candidates.sort((a, b) => (b.timestamp ?? 0) - (a.timestamp ?? 0));
+};